@@ -0,0 +1,25 @@
+use axum::extract::State;
+
+use crate::api::service::{self, captcha::RespCaptcha};
+use pkg::result::response::{ApiOK, Result};
+use pkg::result::status::Reply;
+use pkg::state::AppState;
+
+/*
+
+    验证码模块
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/v1/captcha",
+    tag = "验证码",
+    operation_id = "captcha_create",
+    summary = "获取图形验证码",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespCaptcha>)),
+    security(()),
+)]
+pub async fn create(State(state): State<AppState>) -> Result<ApiOK<RespCaptcha>> {
+    service::captcha::create(&state).await
+}
@@ -1,7 +1,6 @@
-use std::collections::HashMap;
-
 use axum::{
-    extract::{Path, Query},
+    extract::{Path, Query, State},
+    response::Response,
     Extension, Json,
 };
 use axum_extra::extract::WithRejection;
@@ -9,68 +8,169 @@ use validator::Validate;
 
 use crate::api::service::{
     self,
-    department::{ReqCreate, RespInfo, RespList, UpdateInfo},
+    department::{ReqCreate, RespInfo, UpdateInfo},
 };
 use pkg::identity::Identity;
 use pkg::result::{
     rejection::IRejection,
+    page::Page,
     response::{ApiErr, ApiOK, Result},
+    status::{Empty, Reply},
 };
 
 use pkg::tree;
+use pkg::state::AppState;
+use pkg::query::{ListParams, ListQuery};
 
 /*
 
-    ģ
+    部门模块
 
 */
 
+#[utoipa::path(
+    post,
+    path = "/v1/departments",
+    tag = "部门管理",
+    operation_id = "department_create",
+    summary = "新增部门",
+    request_body = ReqCreate,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn create(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<ReqCreate>>,
 ) -> Result<ApiOK<()>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::department::create(req).await
+    service::department::create(&state, req).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/departments/{department_id}",
+    tag = "部门管理",
+    operation_id = "department_info",
+    summary = "部门详情",
+    params(
+        ("department_id" = i64, Path, description = "部门ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespInfo>)),
+)]
 pub async fn info(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(department_id): Path<i64>,
 ) -> Result<ApiOK<RespInfo>> {
-    service::department::info(department_id).await
+    service::department::info(&state, department_id).await
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/v1/departments",
+    tag = "部门管理",
+    operation_id = "department_list",
+    summary = "部门列表",
+    params(
+        ListParams,
+        ("department_name" = Option<String>, Query, description = "部门名称，模糊匹配"),
+        ("deptname" = Option<String>, Query, description = "部门名称，同 `department_name`，兼容旧参数名"),
+        ("parent_id" = Option<i64>, Query, description = "上级部门ID"),
+        ("manager_id" = Option<i64>, Query, description = "负责人ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespInfo>>)),
+)]
 pub async fn list(
+    State(state): State<AppState>,
     Extension(identity): Extension<Identity>,
-    Query(query): Query<HashMap<String, String>>,
-) -> Result<ApiOK<RespList>> {
+    Query(query): Query<ListQuery>,
+) -> Result<ApiOK<Page<RespInfo>>> {
 
-    service::department::list(query).await
+    service::department::list(&state, identity, query).await
 }
 
-pub async fn update(
+#[utoipa::path(
+    get,
+    path = "/v1/departments/export",
+    tag = "部门管理",
+    operation_id = "department_export",
+    summary = "导出部门，查询条件同列表",
+    params(
+        ("format" = Option<String>, Query, description = "导出格式：csv（默认）、xlsx"),
+        ("department_name" = Option<String>, Query, description = "部门名称，模糊匹配"),
+        ("deptname" = Option<String>, Query, description = "部门名称，同 `department_name`，兼容旧参数名"),
+        ("parent_id" = Option<i64>, Query, description = "上级部门ID"),
+        ("manager_id" = Option<i64>, Query, description = "负责人ID"),
+    ),
+    responses(
+        (status = 200, description = "导出文件，失败时返回 `Reply`", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+    ),
+)]
+pub async fn export(
+    State(state): State<AppState>,
     Extension(identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<Response> {
+    service::department::export(&state, identity, query).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/departments/update",
+    tag = "部门管理",
+    operation_id = "department_update",
+    summary = "修改部门",
+    request_body = UpdateInfo,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<UpdateInfo>>,
 ) -> Result<ApiOK<()>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::department::update(req).await
+    service::department::update(&state, req).await
 }
 
+#[utoipa::path(
+    delete,
+    path = "/v1/departments/{department_id}",
+    tag = "部门管理",
+    operation_id = "department_delete",
+    summary = "删除部门",
+    params(
+        ("department_id" = i64, Path, description = "部门ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn delete(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(department_id): Path<i64>,
 ) -> Result<ApiOK<()>>  {
-    service::department::delete(department_id).await
+    service::department::delete(&state, department_id).await
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/v1/departments/select_list",
+    tag = "部门管理",
+    operation_id = "department_select_list",
+    summary = "部门树",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<tree::TreeNode>>)),
+)]
 pub async fn select_list(
-    Extension(identity): Extension<Identity>
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>
 ) -> Result<ApiOK<Vec<tree::TreeNode>>>{
-    service::department::select_list().await
+    service::department::select_list(&state).await
 }
\ No newline at end of file
@@ -1,7 +1,6 @@
-use std::collections::HashMap;
-
 use axum::{
-    extract::{Path, Query},
+    extract::{multipart::MultipartRejection, Multipart, Path, Query, State},
+    response::Response,
     Extension, Json,
 };
 use axum_extra::extract::WithRejection;
@@ -9,84 +8,319 @@ use validator::Validate;
 
 use crate::api::service::{
     self,
-    employee::{ReqCreate, RespInfo, RespList, UpdateInfo,RespSelectOption},
+    employee::{ReqCreate, ReqImport, RespImport, RespInfo, UpdateInfo,RespSelectOption},
+    password::RespPassword,
 };
 use pkg::identity::Identity;
 use pkg::result::{
     rejection::IRejection,
+    page::Page,
     response::{ApiErr, ApiOK, Result},
+    status::{Empty, Reply},
 };
+use pkg::state::AppState;
+use pkg::query::{ListParams, ListQuery};
 
 /*
 
-    ûģ
+    用户模块
 
 */
 
 
+#[utoipa::path(
+    post,
+    path = "/v1/employees",
+    tag = "员工管理",
+    operation_id = "employee_create",
+    summary = "新增员工，返回初始密码",
+    request_body = ReqCreate,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespPassword>)),
+)]
 pub async fn create(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<ReqCreate>>,
-) -> Result<ApiOK<()>> {
+) -> Result<ApiOK<RespPassword>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::employee::create(req).await
+    service::employee::create(&state, req).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/employees/{employee_id}",
+    tag = "员工管理",
+    operation_id = "employee_info",
+    summary = "员工详情",
+    params(
+        ("employee_id" = i64, Path, description = "员工ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespInfo>)),
+)]
 pub async fn info(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(employee_id): Path<i64>,
 ) -> Result<ApiOK<RespInfo>> {
 
-    service::employee::info(employee_id).await
+    service::employee::info(&state, employee_id).await
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/v1/employees",
+    tag = "员工管理",
+    operation_id = "employee_list",
+    summary = "员工列表",
+    params(
+        ListParams,
+        ("login_name" = Option<String>, Query, description = "登录名，模糊匹配"),
+        ("realname" = Option<String>, Query, description = "姓名，模糊匹配"),
+        ("phone" = Option<String>, Query, description = "手机号码，模糊匹配"),
+        ("disabled_flag" = Option<i16>, Query, description = "禁用状态：0 启用、1 禁用"),
+        ("department_id" = Option<String>, Query, description = "部门ID，多个以逗号分隔"),
+        ("position_id" = Option<String>, Query, description = "职务ID，多个以逗号分隔"),
+        ("create_time" = Option<String>, Query, description = "创建时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespInfo>>)),
+)]
 pub async fn list(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<ApiOK<Page<RespInfo>>> {
+
+    service::employee::list(&state, identity, query).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/employees/export",
+    tag = "员工管理",
+    operation_id = "employee_export",
+    summary = "导出员工，查询条件同列表",
+    params(
+        ("format" = Option<String>, Query, description = "导出格式：csv（默认）、xlsx"),
+        ("login_name" = Option<String>, Query, description = "登录名，模糊匹配"),
+        ("realname" = Option<String>, Query, description = "姓名，模糊匹配"),
+        ("phone" = Option<String>, Query, description = "手机号码，模糊匹配"),
+        ("disabled_flag" = Option<i16>, Query, description = "禁用状态：0 启用、1 禁用"),
+        ("department_id" = Option<String>, Query, description = "部门ID，多个以逗号分隔"),
+        ("position_id" = Option<String>, Query, description = "职务ID，多个以逗号分隔"),
+        ("create_time" = Option<String>, Query, description = "创建时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses(
+        (status = 200, description = "导出文件，失败时返回 `Reply`", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+    ),
+)]
+pub async fn export(
+    State(state): State<AppState>,
     Extension(identity): Extension<Identity>,
-    Query(query): Query<HashMap<String, String>>,
-) -> Result<ApiOK<RespList>> {
+    Query(query): Query<ListQuery>,
+) -> Result<Response> {
+    service::employee::export(&state, identity, query).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/employees/import",
+    tag = "员工管理",
+    operation_id = "employee_import",
+    summary = "导入员工，默认仅校验；`dry_run` 为 false 时在一个事务中导入全部校验通过的行",
+    request_body(content = ReqImport, content_type = "multipart/form-data"),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespImport>)),
+)]
+pub async fn import(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    multipart: std::result::Result<Multipart, MultipartRejection>,
+) -> Result<ApiOK<RespImport>> {
+    let mut multipart = multipart.map_err(|e| ApiErr::ErrParams(Some(e.body_text())))?;
+    let mut file = None;
+    let mut dry_run = true;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(v)) => v,
+            Ok(None) => break,
+            Err(e) => return Err(ApiErr::ErrParams(Some(e.body_text()))),
+        };
+        match field.name() {
+            Some("file") => {
+                let data = field.bytes().await.map_err(|e| ApiErr::ErrParams(Some(e.body_text())))?;
+                file = Some(data);
+            }
+            Some("dry_run") => {
+                let v = field.text().await.map_err(|e| ApiErr::ErrParams(Some(e.body_text())))?;
+                dry_run = match v.trim() {
+                    "" | "true" | "1" => true,
+                    "false" | "0" => false,
+                    _ => return Err(ApiErr::ErrParams(Some("参数 dry_run 格式错误".to_string()))),
+                };
+            }
+            _ => {}
+        }
+    }
+    let file = file.ok_or(ApiErr::ErrParams(Some("请上传文件".to_string())))?;
 
-    service::employee::list(query).await
+    service::employee::import(&state, file, dry_run).await
 }
 
 
+#[utoipa::path(
+    post,
+    path = "/v1/employees/update",
+    tag = "员工管理",
+    operation_id = "employee_update",
+    summary = "修改员工",
+    request_body = UpdateInfo,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn update(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<UpdateInfo>>,
 ) -> Result<ApiOK<()>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::employee::update(req).await
+    service::employee::update(&state, req).await
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/v1/employees/disabled_flag/{employee_id}/{disabled_flag}",
+    tag = "员工管理",
+    operation_id = "employee_disabled_flag",
+    summary = "启用/禁用员工",
+    params(
+        ("employee_id" = i64, Path, description = "员工ID"),
+        ("disabled_flag" = i16, Path, description = "禁用状态：0 启用、1 禁用"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn disabled_flag(
-    Extension(identity): Extension<Identity>,
-    Path((employee_id, disabled_flag)): Path<(i64,u8)>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path((employee_id, disabled_flag)): Path<(i64,i16)>,
 )-> Result<ApiOK<()>> {
-    service::employee::disabled_flag(employee_id, disabled_flag).await
+    service::employee::disabled_flag(&state, employee_id, disabled_flag).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/employees/{employee_id}",
+    tag = "员工管理",
+    operation_id = "employee_delete",
+    summary = "删除员工（移入回收站）",
+    params(
+        ("employee_id" = i64, Path, description = "员工ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(employee_id): Path<i64>,
+) -> Result<ApiOK<()>> {
+    service::employee::delete(&state, identity, employee_id).await
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/v1/employees/reset_password/{employee_id}",
+    tag = "员工管理",
+    operation_id = "employee_reset_password",
+    summary = "重置密码，返回新密码",
+    params(
+        ("employee_id" = i64, Path, description = "员工ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespPassword>)),
+)]
 pub async fn reset_password(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path(employee_id): Path<i64>,
+)-> Result<ApiOK<RespPassword>> {
+    service::employee::reset_password(&state, employee_id).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/employees/unlock/{employee_id}",
+    tag = "员工管理",
+    operation_id = "employee_unlock",
+    summary = "解除登录锁定",
+    params(
+        ("employee_id" = i64, Path, description = "员工ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn unlock(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(employee_id): Path<i64>,
 )-> Result<ApiOK<()>> {
-    service::employee::reset_password(employee_id).await
+    service::lockout::unlock(&state, employee_id).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/employees/reset_totp/{employee_id}",
+    tag = "员工管理",
+    operation_id = "employee_reset_totp",
+    summary = "重置两步验证",
+    params(
+        ("employee_id" = i64, Path, description = "员工ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn reset_totp(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path(employee_id): Path<i64>,
+)-> Result<ApiOK<()>> {
+    service::totp::reset(&state, employee_id).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/employees/change_department/{employee_ids}/{department_id}",
+    tag = "员工管理",
+    operation_id = "employee_change_department",
+    summary = "调整部门",
+    params(
+        ("employee_ids" = String, Path, description = "员工ID"),
+        ("department_id" = i64, Path, description = "部门ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn change_department(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path((employee_id, department_id)): Path<(Vec<i64>, i64)>,
 )-> Result<ApiOK<()>> {
-    service::employee::change_department(employee_id, department_id).await
+    service::employee::change_department(&state, employee_id, department_id).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/employees/employee_select_list",
+    tag = "员工管理",
+    operation_id = "employee_employee_select_list",
+    summary = "员工下拉列表",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<RespSelectOption>>)),
+)]
 pub async fn employee_select_list(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
 )-> Result<ApiOK<Vec<RespSelectOption>>> {
-    service::employee::employee_select_list().await
+    service::employee::employee_select_list(&state).await
 }
\ No newline at end of file
@@ -1,37 +1,125 @@
-use axum::{Extension, Json};
+use axum::{extract::State, Extension, Json};
 use axum_extra::extract::WithRejection;
 use validator::Validate;
 
 use pkg::identity::Identity;
+use pkg::util::ClientInfo;
 use pkg::result::{
     rejection::IRejection,
     response::{ApiErr, ApiOK, Result},
+    status::{Empty, Reply},
 };
+use pkg::state::AppState;
 
 use crate::api::service::{
     self,
-    login::{ReqLogin, RespLogin},
+    login::{ReqChallenge, ReqLogin, ReqLoginTotp, ReqRefresh, RespLogin, RespRefresh},
+    totp::RespSetup,
 };
 
 /*
 
-    ¼ģ
+    登录模块
 
 */
 
+#[utoipa::path(
+    post,
+    path = "/v1/login",
+    tag = "登录",
+    operation_id = "login_login",
+    summary = "账号密码登录",
+    request_body = ReqLogin,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespLogin>)),
+    security(()),
+)]
 pub async fn login(
+    State(state): State<AppState>,
+    client: ClientInfo,
     WithRejection(Json(req), _): IRejection<Json<ReqLogin>>,
 ) -> Result<ApiOK<RespLogin>> {
 
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::login::login(req).await
+    service::login::login(&state, req, client).await
 }
 
-pub async fn logout(Extension(identity): Extension<Identity>) -> Result<ApiOK<()>> {
+#[utoipa::path(
+    post,
+    path = "/v1/login/totp",
+    tag = "登录",
+    operation_id = "login_login_totp",
+    summary = "两步验证登录",
+    request_body = ReqLoginTotp,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespLogin>)),
+    security(()),
+)]
+pub async fn login_totp(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    WithRejection(Json(req), _): IRejection<Json<ReqLoginTotp>>,
+) -> Result<ApiOK<RespLogin>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::login::login_totp(&state, req, client).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/login/totp/setup",
+    tag = "登录",
+    operation_id = "login_login_totp_setup",
+    summary = "登录时绑定认证器",
+    request_body = ReqChallenge,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespSetup>)),
+    security(()),
+)]
+pub async fn login_totp_setup(
+    State(state): State<AppState>,
+    WithRejection(Json(req), _): IRejection<Json<ReqChallenge>>,
+) -> Result<ApiOK<RespSetup>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::login::login_totp_setup(&state, req).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/logout",
+    tag = "登录",
+    operation_id = "login_logout",
+    summary = "退出登录",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+) -> Result<ApiOK<()>> {
     if identity.id() == 0 {
         return Ok(ApiOK(None));
     }
-    service::login::logout(identity).await
+    service::login::logout(&state, identity).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/token/refresh",
+    tag = "登录",
+    operation_id = "login_refresh",
+    summary = "刷新访问令牌",
+    request_body = ReqRefresh,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespRefresh>)),
+    security(()),
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    WithRejection(Json(req), _): IRejection<Json<ReqRefresh>>,
+) -> Result<ApiOK<RespRefresh>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::login::refresh(&state, req).await
 }
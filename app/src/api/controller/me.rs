@@ -0,0 +1,186 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use axum_extra::extract::WithRejection;
+use validator::Validate;
+
+use crate::api::service::{
+    self,
+    me::RespMe,
+    password::ReqChange,
+    session,
+    totp::{ReqCode, RespRecovery, RespSetup, RespStatus},
+};
+use pkg::identity::Identity;
+use pkg::result::{
+    rejection::IRejection,
+    response::{ApiErr, ApiOK, Result},
+    status::{Empty, Reply},
+};
+use pkg::state::AppState;
+
+/*
+
+    当前用户模块
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/v1/me",
+    tag = "个人中心",
+    operation_id = "me_info",
+    summary = "当前用户信息",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespMe>)),
+)]
+pub async fn info(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+) -> Result<ApiOK<RespMe>> {
+    service::me::info(&state, identity).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/password",
+    tag = "个人中心",
+    operation_id = "me_change_password",
+    summary = "修改密码",
+    request_body = ReqChange,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqChange>>,
+) -> Result<ApiOK<()>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::password::change(&state, identity, req).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/sessions",
+    tag = "个人中心",
+    operation_id = "me_sessions",
+    summary = "我的登录会话",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<session::RespInfo>>)),
+)]
+pub async fn sessions(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+) -> Result<ApiOK<Vec<session::RespInfo>>> {
+    service::session::my_list(&state, identity).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/me/sessions/{session_id}",
+    tag = "个人中心",
+    operation_id = "me_revoke_session",
+    summary = "撤销登录会话",
+    params(
+        ("session_id" = i64, Path, description = "登录会话ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(session_id): Path<i64>,
+) -> Result<ApiOK<()>> {
+    service::session::my_revoke(&state, identity, session_id).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/me/totp",
+    tag = "个人中心",
+    operation_id = "me_totp_status",
+    summary = "两步验证状态",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespStatus>)),
+)]
+pub async fn totp_status(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+) -> Result<ApiOK<RespStatus>> {
+    service::totp::status(&state, identity).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/totp/setup",
+    tag = "个人中心",
+    operation_id = "me_totp_setup",
+    summary = "生成两步验证密钥",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespSetup>)),
+)]
+pub async fn totp_setup(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+) -> Result<ApiOK<RespSetup>> {
+    service::totp::setup(&state, identity).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/totp/enable",
+    tag = "个人中心",
+    operation_id = "me_totp_enable",
+    summary = "开启两步验证",
+    request_body = ReqCode,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespRecovery>)),
+)]
+pub async fn totp_enable(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqCode>>,
+) -> Result<ApiOK<RespRecovery>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::totp::enable(&state, identity, req).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/totp/disable",
+    tag = "个人中心",
+    operation_id = "me_totp_disable",
+    summary = "关闭两步验证",
+    request_body = ReqCode,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn totp_disable(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqCode>>,
+) -> Result<ApiOK<()>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::totp::disable(&state, identity, req).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/me/totp/recovery_codes",
+    tag = "个人中心",
+    operation_id = "me_totp_recovery_codes",
+    summary = "重新生成恢复码",
+    request_body = ReqCode,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespRecovery>)),
+)]
+pub async fn totp_recovery_codes(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqCode>>,
+) -> Result<ApiOK<RespRecovery>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::totp::regenerate(&state, identity, req).await
+}
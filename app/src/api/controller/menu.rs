@@ -0,0 +1,176 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use axum_extra::extract::WithRejection;
+use validator::Validate;
+
+use crate::api::service::{
+    self,
+    menu::{ReqCreate, ReqSort, RespInfo, UpdateInfo},
+};
+use pkg::identity::Identity;
+use pkg::result::{
+    rejection::IRejection,
+    response::{ApiErr, ApiOK, Result},
+    status::{Empty, Reply},
+};
+
+use pkg::tree;
+use pkg::state::AppState;
+
+/*
+
+    菜单模块
+
+*/
+
+#[utoipa::path(
+    post,
+    path = "/v1/menus",
+    tag = "菜单管理",
+    operation_id = "menu_create",
+    summary = "新增菜单",
+    request_body = ReqCreate,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqCreate>>,
+) -> Result<ApiOK<()>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::menu::create(&state, identity, req).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/menus/{menu_id}",
+    tag = "菜单管理",
+    operation_id = "menu_info",
+    summary = "菜单详情",
+    params(
+        ("menu_id" = i64, Path, description = "菜单ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespInfo>)),
+)]
+pub async fn info(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path(menu_id): Path<i64>,
+) -> Result<ApiOK<RespInfo>> {
+    service::menu::info(&state, menu_id).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/menus",
+    tag = "菜单管理",
+    operation_id = "menu_tree",
+    summary = "菜单树",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<tree::Node<RespInfo>>>)),
+)]
+pub async fn tree(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+) -> Result<ApiOK<Vec<tree::Node<RespInfo>>>> {
+    service::menu::tree(&state).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/menus/update",
+    tag = "菜单管理",
+    operation_id = "menu_update",
+    summary = "修改菜单",
+    request_body = UpdateInfo,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<UpdateInfo>>,
+) -> Result<ApiOK<()>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::menu::update(&state, identity, req).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/menus/sort",
+    tag = "菜单管理",
+    operation_id = "menu_sort",
+    summary = "调整菜单排序",
+    request_body = Vec<ReqSort>,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn sort(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<Vec<ReqSort>>>,
+) -> Result<ApiOK<()>> {
+    service::menu::sort(&state, identity, req).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/menus/disabled_flag/{menu_id}/{disabled_flag}",
+    tag = "菜单管理",
+    operation_id = "menu_disabled_flag",
+    summary = "启用/禁用菜单",
+    params(
+        ("menu_id" = i64, Path, description = "菜单ID"),
+        ("disabled_flag" = i16, Path, description = "禁用状态：0 启用、1 禁用"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn disabled_flag(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((menu_id, disabled_flag)): Path<(i64, i16)>,
+) -> Result<ApiOK<()>> {
+    service::menu::disabled_flag(&state, identity, menu_id, disabled_flag).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/menus/visible_flag/{menu_id}/{visible_flag}",
+    tag = "菜单管理",
+    operation_id = "menu_visible_flag",
+    summary = "显示/隐藏菜单",
+    params(
+        ("menu_id" = i64, Path, description = "菜单ID"),
+        ("visible_flag" = i16, Path, description = "显示状态：0 隐藏、1 显示"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn visible_flag(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((menu_id, visible_flag)): Path<(i64, i16)>,
+) -> Result<ApiOK<()>> {
+    service::menu::visible_flag(&state, identity, menu_id, visible_flag).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/menus/{menu_id}",
+    tag = "菜单管理",
+    operation_id = "menu_delete",
+    summary = "删除菜单",
+    params(
+        ("menu_id" = i64, Path, description = "菜单ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(menu_id): Path<i64>,
+) -> Result<ApiOK<()>> {
+    service::menu::delete(&state, identity, menu_id).await
+}
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Response,
+    Extension,
+};
+
+use crate::api::service::{
+    self,
+    operate_log::RespInfo,
+};
+use pkg::identity::Identity;
+use pkg::result::page::Page;
+use pkg::result::response::{ApiOK, Result};
+use pkg::result::status::Reply;
+use pkg::state::AppState;
+use pkg::query::{CursorParams, ListParams, ListQuery};
+
+/*
+
+    操作记录模块
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/v1/operate_logs",
+    tag = "操作记录",
+    operation_id = "operate_log_list",
+    summary = "操作记录列表",
+    params(
+        ListParams,
+        CursorParams,
+        ("operate_user_id" = Option<i64>, Query, description = "操作人ID"),
+        ("operate_user_name" = Option<String>, Query, description = "操作人姓名，模糊匹配"),
+        ("module" = Option<String>, Query, description = "模块"),
+        ("success_flag" = Option<i16>, Query, description = "是否成功：0 失败、1 成功"),
+        ("create_time" = Option<String>, Query, description = "操作时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespInfo>>)),
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<ApiOK<Page<RespInfo>>> {
+    service::operate_log::list(&state, query).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/operate_logs/export",
+    tag = "操作记录",
+    operation_id = "operate_log_export",
+    summary = "导出操作记录，查询条件同列表",
+    params(
+        ("format" = Option<String>, Query, description = "导出格式：csv（默认）、xlsx"),
+        ("operate_user_id" = Option<i64>, Query, description = "操作人ID"),
+        ("operate_user_name" = Option<String>, Query, description = "操作人姓名，模糊匹配"),
+        ("module" = Option<String>, Query, description = "模块"),
+        ("success_flag" = Option<i16>, Query, description = "是否成功：0 失败、1 成功"),
+        ("create_time" = Option<String>, Query, description = "操作时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses(
+        (status = 200, description = "导出文件，失败时返回 `Reply`", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+    ),
+)]
+pub async fn export(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<Response> {
+    service::operate_log::export(&state, query).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/operate_logs/{operate_log_id}",
+    tag = "操作记录",
+    operation_id = "operate_log_info",
+    summary = "操作记录详情",
+    params(
+        ("operate_log_id" = i64, Path, description = "操作记录ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespInfo>)),
+)]
+pub async fn info(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path(operate_log_id): Path<i64>,
+) -> Result<ApiOK<RespInfo>> {
+    service::operate_log::info(&state, operate_log_id).await
+}
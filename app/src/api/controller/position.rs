@@ -1,7 +1,6 @@
-use std::collections::HashMap;
-
 use axum::{
-    extract::{Path, Query},
+    extract::{Path, Query, State},
+    response::Response,
     Extension, Json,
 };
 use axum_extra::extract::WithRejection;
@@ -9,68 +8,167 @@ use validator::Validate;
 
 use crate::api::service::{
     self,
-    position::{ReqCreate, RespInfo, RespList,UpdateInfo,RespSelect},
+    position::{ReqCreate, RespInfo, UpdateInfo,RespSelect},
 };
 use pkg::identity::Identity;
 use pkg::result::{
     rejection::IRejection,
+    page::Page,
     response::{ApiErr, ApiOK, Result},
+    status::{Empty, Reply},
 };
+use pkg::state::AppState;
+use pkg::query::{ListParams, ListQuery};
 
 /*
 
-    λģ
+    岗位模块
 
 */
 
+#[utoipa::path(
+    post,
+    path = "/v1/positions",
+    tag = "岗位管理",
+    operation_id = "position_create",
+    summary = "新增岗位",
+    request_body = ReqCreate,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn create(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<ReqCreate>>,
 ) -> Result<ApiOK<()>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::position::create(req).await
+    service::position::create(&state, req).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/positions/{post_id}",
+    tag = "岗位管理",
+    operation_id = "position_info",
+    summary = "岗位详情",
+    params(
+        ("post_id" = u64, Path, description = "岗位ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespInfo>)),
+)]
 pub async fn info(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(role_id): Path<u64>,
 ) -> Result<ApiOK<RespInfo>> {
 
-    service::position::info(role_id).await
+    service::position::info(&state, role_id).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/positions",
+    tag = "岗位管理",
+    operation_id = "position_list",
+    summary = "岗位列表",
+    params(
+        ListParams,
+        ("postname" = Option<String>, Query, description = "岗位名称，模糊匹配"),
+        ("level" = Option<String>, Query, description = "职级"),
+        ("create_time" = Option<String>, Query, description = "创建时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespInfo>>)),
+)]
 pub async fn list(
-    Extension(identity): Extension<Identity>,
-    Query(query): Query<HashMap<String, String>>,
-) -> Result<ApiOK<RespList>> {
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<ApiOK<Page<RespInfo>>> {
+
+    service::position::list(&state, query).await
+}
 
-    service::position::list(query).await
+#[utoipa::path(
+    get,
+    path = "/v1/positions/export",
+    tag = "岗位管理",
+    operation_id = "position_export",
+    summary = "导出岗位，查询条件同列表",
+    params(
+        ("format" = Option<String>, Query, description = "导出格式：csv（默认）、xlsx"),
+        ("postname" = Option<String>, Query, description = "岗位名称，模糊匹配"),
+        ("level" = Option<String>, Query, description = "职级"),
+        ("create_time" = Option<String>, Query, description = "创建时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses(
+        (status = 200, description = "导出文件，失败时返回 `Reply`", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+    ),
+)]
+pub async fn export(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<Response> {
+    service::position::export(&state, query).await
 }
 
 
+#[utoipa::path(
+    post,
+    path = "/v1/positions/update",
+    tag = "岗位管理",
+    operation_id = "position_update",
+    summary = "修改岗位",
+    request_body = UpdateInfo,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn update(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<UpdateInfo>>,
 ) -> Result<ApiOK<()>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::position::update(req).await
+    service::position::update(&state, req).await
 }
 
+#[utoipa::path(
+    delete,
+    path = "/v1/positions/{post_id}",
+    tag = "岗位管理",
+    operation_id = "position_delete",
+    summary = "删除岗位",
+    params(
+        ("post_id" = u64, Path, description = "岗位ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn delete(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(post_id): Path<u64>,
 ) -> Result<ApiOK<()>>  {
 
-    service::position::delete(post_id).await
+    service::position::delete(&state, post_id).await
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/v1/positions/select_list",
+    tag = "岗位管理",
+    operation_id = "position_select_list",
+    summary = "岗位下拉列表",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<RespSelect>>)),
+)]
 pub async fn select_list(
-    Extension(identity): Extension<Identity>
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>
 ) -> Result<ApiOK<Vec<RespSelect>>> {
-    service::position::select_list().await
+    service::position::select_list(&state).await
 }
\ No newline at end of file
@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+};
+
+use crate::api::service::{self, recycle_bin::RespInfo};
+use pkg::identity::Identity;
+use pkg::result::page::Page;
+use pkg::result::response::{ApiOK, Result};
+use pkg::result::status::{Empty, Reply};
+use pkg::state::AppState;
+use pkg::query::{ListParams, ListQuery};
+
+/*
+
+    回收站模块
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/v1/recycle_bin",
+    tag = "回收站",
+    operation_id = "recycle_bin_list",
+    summary = "回收站列表，按删除时间倒序",
+    params(
+        ListParams,
+        ("type" = String, Query, description = "数据类型：employee、department、position、role、menu"),
+        ("name" = Option<String>, Query, description = "名称，模糊匹配"),
+        ("delete_time" = Option<String>, Query, description = "删除时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespInfo>>)),
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<ApiOK<Page<RespInfo>>> {
+    service::recycle_bin::list(&state, query).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/recycle_bin/{type}/{id}/restore",
+    tag = "回收站",
+    operation_id = "recycle_bin_restore",
+    summary = "从回收站恢复",
+    params(
+        ("type" = String, Path, description = "数据类型：employee、department、position、role、menu"),
+        ("id" = i64, Path, description = "数据ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn restore(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path((kind, id)): Path<(String, i64)>,
+) -> Result<ApiOK<()>> {
+    service::recycle_bin::restore(&state, kind, id).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/recycle_bin/{type}/{id}",
+    tag = "回收站",
+    operation_id = "recycle_bin_purge",
+    summary = "永久删除，同时清除关联数据",
+    params(
+        ("type" = String, Path, description = "数据类型：employee、department、position、role、menu"),
+        ("id" = i64, Path, description = "数据ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn purge(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path((kind, id)): Path<(String, i64)>,
+) -> Result<ApiOK<()>> {
+    service::recycle_bin::purge(&state, kind, id).await
+}
@@ -1,7 +1,6 @@
-use std::collections::HashMap;
-
 use axum::{
-    extract::{Path, Query},
+    extract::{Path, Query, State},
+    response::Response,
     Extension, Json,
 };
 use axum_extra::extract::WithRejection;
@@ -9,88 +8,325 @@ use validator::Validate;
 
 use crate::api::service::{
     self,
-    role::{ReqCreate, RespInfo, RespList,UpdateInfo, RespSelect, RespEmpList,RespRoleMenu},
+    role::{ReqCreate, RespInfo, UpdateInfo, RespSelect, RespEmpInfo, RespRoleMenu, ReqRoleMenu, ReqRoleEmp, ReqRoleDepartment},
 };
 use pkg::identity::Identity;
 use pkg::result::{
     rejection::IRejection,
+    page::Page,
     response::{ApiErr, ApiOK, Result},
+    status::{Empty, Reply},
 };
 
 use pkg::tree;
+use pkg::state::AppState;
+use pkg::query::{ListParams, ListQuery};
 
+#[utoipa::path(
+    post,
+    path = "/v1/roles",
+    tag = "角色管理",
+    operation_id = "role_create",
+    summary = "新增角色",
+    request_body = ReqCreate,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn create(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<ReqCreate>>,
 ) -> Result<ApiOK<()>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::role::create(req).await
+    service::role::create(&state, req).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/roles/{role_id}",
+    tag = "角色管理",
+    operation_id = "role_info",
+    summary = "角色详情",
+    params(
+        ("role_id" = u64, Path, description = "角色ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<RespInfo>)),
+)]
 pub async fn info(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(role_id): Path<u64>,
 ) -> Result<ApiOK<RespInfo>> {
 
-    service::role::info(role_id).await
+    service::role::info(&state, role_id).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/roles",
+    tag = "角色管理",
+    operation_id = "role_list",
+    summary = "角色列表",
+    params(
+        ListParams,
+        ("rolename" = Option<String>, Query, description = "角色名称，模糊匹配"),
+        ("rolecode" = Option<String>, Query, description = "角色编码"),
+        ("data_scope" = Option<i16>, Query, description = "数据范围"),
+        ("create_time" = Option<String>, Query, description = "创建时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespInfo>>)),
+)]
 pub async fn list(
-    Extension(identity): Extension<Identity>,
-    Query(query): Query<HashMap<String, String>>,
-) -> Result<ApiOK<RespList>> {
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<ApiOK<Page<RespInfo>>> {
 
-    service::role::list(query).await
+    service::role::list(&state, query).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/roles/export",
+    tag = "角色管理",
+    operation_id = "role_export",
+    summary = "导出角色，查询条件同列表",
+    params(
+        ("format" = Option<String>, Query, description = "导出格式：csv（默认）、xlsx"),
+        ("rolename" = Option<String>, Query, description = "角色名称，模糊匹配"),
+        ("rolecode" = Option<String>, Query, description = "角色编码"),
+        ("data_scope" = Option<i16>, Query, description = "数据范围"),
+        ("create_time" = Option<String>, Query, description = "创建时间范围：逗号分隔的起止时间，格式 `yyyy-mm-dd hh:mm:ss`，可省略一端"),
+    ),
+    responses(
+        (status = 200, description = "导出文件，失败时返回 `Reply`", content(
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        )),
+    ),
+)]
+pub async fn export(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<Response> {
+    service::role::export(&state, query).await
 }
 
 
+#[utoipa::path(
+    post,
+    path = "/v1/roles/update",
+    tag = "角色管理",
+    operation_id = "role_update",
+    summary = "修改角色",
+    request_body = UpdateInfo,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn update(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     WithRejection(Json(req), _): IRejection<Json<UpdateInfo>>,
 ) -> Result<ApiOK<()>> {
     if let Err(e) = req.validate() {
         return Err(ApiErr::ErrParams(Some(e.to_string())));
     }
-    service::role::update(req).await
+    service::role::update(&state, req).await
 }
 
+#[utoipa::path(
+    delete,
+    path = "/v1/roles/{role_id}",
+    tag = "角色管理",
+    operation_id = "role_delete",
+    summary = "删除角色",
+    params(
+        ("role_id" = u64, Path, description = "角色ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
 pub async fn delete(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(role_id): Path<u64>,
 ) -> Result<ApiOK<()>>  {
 
-    service::role::delete(role_id).await
+    service::role::delete(&state, role_id).await
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/v1/roles/select_list",
+    tag = "角色管理",
+    operation_id = "role_select_list",
+    summary = "角色下拉列表",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<RespSelect>>)),
+)]
 pub async fn select_list(
-    Extension(identity): Extension<Identity>
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>
 ) -> Result<ApiOK<Vec<RespSelect>>> {
-    service::role::select_list().await
+    service::role::select_list(&state).await
 }
 
 
-// ݽɫIdѯӦɫµԱб,ɫIdԱԱֻš¼
+// 根据角色Id查询对应角色下的员工列表,参数包含角色Id、员工姓名、员工手机号、登录名
+#[utoipa::path(
+    get,
+    path = "/v1/roles/role_emp_list",
+    tag = "角色管理",
+    operation_id = "role_role_emp_list",
+    summary = "角色成员列表",
+    params(
+        ListParams,
+        ("roleid" = i64, Query, description = "角色ID"),
+        ("realname" = Option<String>, Query, description = "姓名，模糊匹配"),
+        ("login_name" = Option<String>, Query, description = "登录名，模糊匹配"),
+        ("phone" = Option<String>, Query, description = "手机号码，模糊匹配"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespEmpInfo>>)),
+)]
 pub async fn role_emp_list(
+    State(state): State<AppState>,
     Extension(identity): Extension<Identity>,
-    Query(query): Query<HashMap<String, String>>
-) -> Result<ApiOK<RespEmpList>> {
-    service::role::role_emp_list(query).await
+    Query(query): Query<ListQuery>
+) -> Result<ApiOK<Page<RespEmpInfo>>> {
+    service::role::role_emp_list(&state, identity, query).await
 }
 
-//Ȩ-ѯйȨ
+//功能权限-查询所有功能权限
+#[utoipa::path(
+    get,
+    path = "/v1/roles/role_func_list",
+    tag = "角色管理",
+    operation_id = "role_role_func_list",
+    summary = "全部功能权限",
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<tree::TreeNode>>)),
+)]
 pub async fn role_func_list(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
 ) -> Result<ApiOK<Vec<tree::TreeNode>>>{
-    service::role::menu_list().await
+    service::role::menu_list(&state).await
 }
 
-//Ȩ-ݽɫIdѯӦɫµĹIDб
+//功能权限-根据角色Id查询对应角色下的功能ID列表
+#[utoipa::path(
+    get,
+    path = "/v1/roles/role_func_id/{role_id}",
+    tag = "角色管理",
+    operation_id = "role_role_func_id",
+    summary = "角色的功能权限",
+    params(
+        ("role_id" = i64, Path, description = "角色ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<RespRoleMenu>>)),
+)]
 pub async fn role_func_id(
-    Extension(identity): Extension<Identity>,
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
     Path(role_id): Path<i64>
 ) -> Result<ApiOK<Vec<RespRoleMenu>>>{
-    service::role::role_menu(role_id).await
+    service::role::role_menu(&state, role_id).await
+}
+
+
+//功能权限-更新角色的功能权限
+#[utoipa::path(
+    post,
+    path = "/v1/roles/role_func_update",
+    tag = "角色管理",
+    operation_id = "role_update_role_func",
+    summary = "设置角色的功能权限",
+    request_body = ReqRoleMenu,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn update_role_func(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqRoleMenu>>,
+) -> Result<ApiOK<()>> {
+    service::role::update_role_menu(&state, req).await
 }
+
+//数据范围-获取角色自定义数据范围的部门ID
+#[utoipa::path(
+    get,
+    path = "/v1/roles/role_dept_id/{role_id}",
+    tag = "角色管理",
+    operation_id = "role_role_dept_id",
+    summary = "角色自定义数据范围的部门",
+    params(
+        ("role_id" = i64, Path, description = "角色ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Vec<i64>>)),
+)]
+pub async fn role_dept_id(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path(role_id): Path<i64>
+) -> Result<ApiOK<Vec<i64>>>{
+    service::role::role_department(&state, role_id).await
+}
+
+//数据范围-更新角色自定义数据范围的部门
+#[utoipa::path(
+    post,
+    path = "/v1/roles/role_dept_update",
+    tag = "角色管理",
+    operation_id = "role_update_role_dept",
+    summary = "设置角色自定义数据范围",
+    request_body = ReqRoleDepartment,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn update_role_dept(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqRoleDepartment>>,
+) -> Result<ApiOK<()>> {
+    service::role::update_role_department(&state, req).await
+}
+
+// 角色员工-批量添加
+#[utoipa::path(
+    post,
+    path = "/v1/roles/role_emp_add",
+    tag = "角色管理",
+    operation_id = "role_add_role_emp",
+    summary = "添加角色成员",
+    request_body = ReqRoleEmp,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn add_role_emp(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqRoleEmp>>,
+) -> Result<ApiOK<()>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::role::add_role_emp(&state, req).await
+}
+
+// 角色员工-批量移除
+#[utoipa::path(
+    post,
+    path = "/v1/roles/role_emp_remove",
+    tag = "角色管理",
+    operation_id = "role_remove_role_emp",
+    summary = "移除角色成员",
+    request_body = ReqRoleEmp,
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn remove_role_emp(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    WithRejection(Json(req), _): IRejection<Json<ReqRoleEmp>>,
+) -> Result<ApiOK<()>> {
+    if let Err(e) = req.validate() {
+        return Err(ApiErr::ErrParams(Some(e.to_string())));
+    }
+    service::role::remove_role_emp(&state, req).await
+}
\ No newline at end of file
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+};
+
+use crate::api::service::{self, session::RespInfo};
+use pkg::identity::Identity;
+use pkg::result::page::Page;
+use pkg::result::response::{ApiOK, Result};
+use pkg::result::status::{Empty, Reply};
+use pkg::state::AppState;
+use pkg::query::{ListParams, ListQuery};
+
+/*
+
+    在线用户模块
+
+*/
+
+#[utoipa::path(
+    get,
+    path = "/v1/sessions",
+    tag = "在线用户",
+    operation_id = "session_list",
+    summary = "在线用户列表",
+    params(
+        ListParams,
+        ("employee_id" = Option<i64>, Query, description = "员工ID"),
+        ("ip" = Option<String>, Query, description = "IP地址"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Page<RespInfo>>)),
+)]
+pub async fn list(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Query(query): Query<ListQuery>,
+) -> Result<ApiOK<Page<RespInfo>>> {
+    service::session::list(&state, query).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/sessions/{session_id}",
+    tag = "在线用户",
+    operation_id = "session_kick",
+    summary = "强制下线登录会话",
+    params(
+        ("session_id" = i64, Path, description = "登录会话ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn kick(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path(session_id): Path<i64>,
+) -> Result<ApiOK<()>> {
+    service::session::kick(&state, session_id).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/v1/sessions/employee/{employee_id}",
+    tag = "在线用户",
+    operation_id = "session_kick_employee",
+    summary = "强制下线员工的全部会话",
+    params(
+        ("employee_id" = i64, Path, description = "员工ID"),
+    ),
+    responses((status = 200, description = "成功时 `code` 为 0", body = Reply<Empty>)),
+)]
+pub async fn kick_employee(
+    State(state): State<AppState>,
+    Extension(_identity): Extension<Identity>,
+    Path(employee_id): Path<i64>,
+) -> Result<ApiOK<()>> {
+    service::session::kick_employee(&state, employee_id).await
+}
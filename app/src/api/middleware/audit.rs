@@ -0,0 +1,284 @@
+use axum::{
+    body::{self, Body, HttpBody},
+    extract::{MatchedPath, Request, State},
+    http::{
+        header::{CONTENT_TYPE, USER_AGENT},
+        HeaderMap, Method,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::LengthLimitError;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use serde_json::Value;
+use time::macros::offset;
+use tokio::sync::mpsc;
+
+use pkg::audit::{Queue, Record};
+use pkg::identity::Identity;
+use pkg::result::response::ApiErr;
+use pkg::state::AppState;
+use pkg::util;
+
+use crate::api::router;
+use crate::ent::{prelude::TEmployee, prelude::TOperateLog, t_operate_log};
+
+// 请求参数、失败原因的最大记录长度
+const MAX_PARAM_LEN: usize = 4096;
+// 读取的请求体、响应体最大长度，与 axum 默认的请求体大小限制一致
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+// 待写入队列长度，队列满时丢弃并记录日志
+const QUEUE_SIZE: usize = 1024;
+// 操作人类型：员工
+const USER_TYPE_EMPLOYEE: i32 = 1;
+
+/// 创建操作日志队列并启动写入任务，需在应用启动时于 tokio 运行时中调用
+pub fn start(state: &mut AppState) {
+    let (queue, rx) = Queue::new(QUEUE_SIZE);
+    tokio::spawn(worker(state.db.clone(), rx));
+    state.audit = queue;
+}
+
+/// 操作日志：记录写操作的请求信息与结果，异步写入 t_operate_log
+pub async fn handle(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = match request.extensions().get::<MatchedPath>() {
+        Some(v) => v.as_str().to_string(),
+        None => request.uri().path().to_string(),
+    };
+    if !is_mutating(&method, &path) {
+        return next.run(request).await;
+    }
+
+    let employee_id = request
+        .extensions()
+        .get::<Identity>()
+        .map(|v| v.id())
+        .unwrap_or_default();
+    let url = request.uri().to_string();
+    let ip = util::client_ip(&state.proxies, request.headers(), request.extensions());
+    let user_agent = request
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+
+    // 仅读取 JSON 请求体，文件上传等其它请求体直接转发，不记录内容
+    let (parts, body) = request.into_parts();
+    let (body, param) = if is_json(&parts.headers) {
+        let bytes = match body::to_bytes(body, MAX_BODY_SIZE).await {
+            Ok(v) => v,
+            Err(e) if is_length_limit(&e) => {
+                return ApiErr::ErrParams(Some("请求数据过大".to_string())).into_response();
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "error parse request body");
+                return ApiErr::ErrSystem(None).into_response();
+            }
+        };
+        let param = sanitize(Some(&bytes), parts.uri.query());
+        (Body::from(bytes), param)
+    } else {
+        let empty = body.size_hint().exact() == Some(0);
+        let param = sanitize(empty.then_some(&[][..]), parts.uri.query());
+        (body, param)
+    };
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    // 解析统一返回结构，获取执行结果；非 JSON 或过大的响应体按状态码判断
+    let (parts, body) = response.into_parts();
+    let readable = is_json(&parts.headers)
+        && body.size_hint().upper().is_some_and(|v| v <= MAX_BODY_SIZE as u64);
+    let (body, success, fail_reason) = if readable {
+        let bytes = match body::to_bytes(body, MAX_BODY_SIZE).await {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::error!(error = ?e, "error parse response body");
+                return ApiErr::ErrSystem(None).into_response();
+            }
+        };
+        let (success, fail_reason) = match serde_json::from_slice::<Value>(&bytes) {
+            Ok(v) if v.get("err").and_then(Value::as_bool) == Some(true) => (
+                false,
+                v.get("msg").and_then(Value::as_str).map(|v| truncate(v.to_string())),
+            ),
+            Ok(_) => (parts.status.is_success(), None),
+            Err(_) => (
+                parts.status.is_success(),
+                (!parts.status.is_success()).then(|| parts.status.to_string()),
+            ),
+        };
+        (Body::from(bytes), success, fail_reason)
+    } else {
+        let success = parts.status.is_success();
+        (body, success, (!success).then(|| parts.status.to_string()))
+    };
+
+    let create_time = state.clock.now(offset!(+8)).unix_timestamp();
+    state.audit.send(Record {
+        employee_id,
+        module: module(&path),
+        content: router::app::perm_key(method.as_str(), &path)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| format!("{} {}", method, path)),
+        url,
+        method: method.to_string(),
+        param,
+        ip,
+        user_agent,
+        success,
+        fail_reason,
+        create_time,
+    });
+
+    Response::from_parts(parts, body)
+}
+
+fn is_mutating(method: &Method, path: &str) -> bool {
+    match *method {
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE => true,
+        Method::GET => router::app::mutating_get(path),
+        _ => false,
+    }
+}
+
+fn module(path: &str) -> String {
+    let name = path
+        .trim_start_matches("/v1/")
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    match name {
+        "roles" => String::from("角色管理"),
+        "positions" => String::from("岗位管理"),
+        "departments" => String::from("部门管理"),
+        "employees" => String::from("员工管理"),
+        "menus" => String::from("菜单管理"),
+        "sessions" => String::from("在线用户"),
+        "recycle_bin" => String::from("回收站"),
+        "me" => String::from("个人中心"),
+        _ => name.to_string(),
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+fn is_length_limit(e: &axum::Error) -> bool {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(e);
+    while let Some(v) = source {
+        if v.is::<LengthLimitError>() {
+            return true;
+        }
+        source = v.source();
+    }
+    false
+}
+
+// 请求参数脱敏：隐藏密码、令牌等敏感字段；`body` 为 `None` 时表示未读取的非 JSON 请求体
+fn sanitize(body: Option<&[u8]>, query: Option<&str>) -> Option<String> {
+    let mut param = match body.map(serde_json::from_slice::<Value>) {
+        Some(Ok(mut v)) => {
+            mask(&mut v);
+            v.to_string()
+        }
+        Some(Err(_)) if body.is_some_and(|v| v.is_empty()) => String::new(),
+        _ => String::from("<binary>"),
+    };
+    if let Some(query) = query {
+        if !param.is_empty() {
+            param.push(' ');
+        }
+        param.push_str(query);
+    }
+    if param.is_empty() {
+        return None;
+    }
+    Some(truncate(param))
+}
+
+fn mask(v: &mut Value) {
+    match v {
+        Value::Object(m) => {
+            for (k, v) in m.iter_mut() {
+                let k = k.to_lowercase();
+                if k == "code" || ["password", "pwd", "token", "secret"].iter().any(|s| k.contains(s)) {
+                    *v = Value::String(String::from("******"));
+                } else {
+                    mask(v);
+                }
+            }
+        }
+        Value::Array(list) => list.iter_mut().for_each(mask),
+        _ => {}
+    }
+}
+
+fn truncate(mut s: String) -> String {
+    if s.len() > MAX_PARAM_LEN {
+        let mut end = MAX_PARAM_LEN;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        s.truncate(end);
+    }
+    s
+}
+
+// 后台任务：从队列读取日志并写入 t_operate_log
+async fn worker(db: DatabaseConnection, mut rx: mpsc::Receiver<Record>) {
+    while let Some(record) = rx.recv().await {
+        let operate_user_name = match TEmployee::find_by_id(record.employee_id)
+            .one(&db)
+            .await
+        {
+            Ok(v) => v.map(|v| v.realname).unwrap_or_default(),
+            Err(e) => {
+                tracing::error!(error = ?e, "error find t_employee");
+                String::new()
+            }
+        };
+
+        let model = t_operate_log::ActiveModel {
+            operate_user_id: Set(record.employee_id),
+            operate_user_type: Set(USER_TYPE_EMPLOYEE),
+            operate_user_name: Set(operate_user_name),
+            module: Set(Some(record.module)),
+            content: Set(Some(record.content)),
+            url: Set(Some(record.url)),
+            method: Set(Some(record.method)),
+            param: Set(record.param),
+            ip: Set(Some(record.ip)),
+            user_agent: Set(record.user_agent),
+            success_flag: Set(Some(record.success as i16)),
+            fail_reason: Set(record.fail_reason),
+            create_time: Set(record.create_time),
+            update_time: Set(record.create_time),
+            ..Default::default()
+        };
+        if let Err(e) = TOperateLog::insert(model).exec(&db).await {
+            tracing::error!(error = ?e, "error insert t_operate_log");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize;
+
+    #[test]
+    fn sanitize_param() {
+        assert_eq!(
+            sanitize(Some(br#"{"login_name":"admin","password":"123456","list":[{"login_pwd":"x"}]}"#), None),
+            Some(String::from(r#"{"list":[{"login_pwd":"******"}],"login_name":"admin","password":"******"}"#))
+        );
+        assert_eq!(sanitize(Some(b""), Some("page=1")), Some(String::from("page=1")));
+        assert_eq!(sanitize(Some(b""), None), None);
+        assert_eq!(sanitize(None, Some("dry_run=true")), Some(String::from("<binary> dry_run=true")));
+    }
+}
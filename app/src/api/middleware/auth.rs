@@ -1,22 +1,61 @@
 use axum::{
-    extract::Request,
+    extract::{MatchedPath, Request, State},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 
-use pkg::identity::Identity;
+use pkg::identity::{Identity, TokenError};
 use pkg::result::response::ApiErr;
+use pkg::state::AppState;
 
-use crate::api::auth_check;
+use crate::api::{auth_check, perm, router};
+
+pub async fn handle(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    // 令牌解析失败：区分过期与无效，便于前端选择刷新令牌或重新登录
+    if let Some(e) = request.extensions().get::<TokenError>() {
+        return ApiErr::from(e.clone()).into_response();
+    }
 
-pub async fn handle(request: Request, next: Next) -> Response {
     let identity = request.extensions().get::<Identity>();
-    match identity {
+    let employee = match identity {
         None => return ApiErr::ErrAuth(None).into_response(),
-        Some(v) => match auth_check(v).await {
-            Ok(_) => (),
+        Some(v) => match auth_check(&state, v).await {
+            Ok(v) => v,
             Err(e) => return ApiErr::ErrAuth(Some(e.to_string())).into_response(),
         },
+    };
+
+    let method = request.method().as_str();
+    let path = match request.extensions().get::<MatchedPath>() {
+        Some(v) => v.as_str(),
+        None => request.uri().path(),
+    };
+
+    // 首次登录或密码被重置，修改密码前仅可访问少数接口
+    if employee.must_change_password == 1 && !router::app::password_change(method, path) {
+        return ApiErr::ErrPasswordChange(None).into_response();
+    }
+
+    // 超级管理员不校验权限
+    if employee.administrator_flag == 1 {
+        return next.run(request).await;
     }
+
+    if router::app::login_only(method, path) {
+        return next.run(request).await;
+    }
+
+    match perm::resolve(&state, employee.employee_id).await {
+        Ok(v) => {
+            if !perm::allow(&v, method, path) {
+                return ApiErr::ErrPerm(None).into_response();
+            }
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "error resolve permissions");
+            return ApiErr::ErrSystem(None).into_response();
+        }
+    }
+
     next.run(request).await
 }
@@ -1,38 +1,48 @@
+use std::net::SocketAddr;
+
 use anyhow::anyhow;
 use anyhow::Result;
-use pkg::config;
-use pkg::db;
 use pkg::identity::Identity;
-use sea_orm::EntityTrait;
+use pkg::soft_delete::SoftDelete;
+use pkg::state::AppState;
+use sea_orm::{ColumnTrait, QueryFilter};
 
-use crate::ent::prelude::TEmployee;
+use crate::ent::{prelude::TEmployee, t_employee};
 
 pub mod controller;
 pub mod middleware;
+pub mod openapi;
+pub mod perm;
 pub mod router;
+pub mod scope;
 pub mod service;
 
 
-pub async fn auth_check(identity: &Identity) -> Result<()> {
+pub async fn auth_check(state: &AppState, identity: &Identity) -> Result<t_employee::Model> {
     if identity.id() == 0 {
         return Err(anyhow!("未授权，请先登录"));
     }
-    let ret = TEmployee::find_by_id(identity.id()).one(db::conn()).await?;
+    let ret = TEmployee::find_alive()
+        .filter(t_employee::Column::EmployeeId.eq(identity.id()))
+        .one(&state.db)
+        .await?;
     match ret {
-        None => return Err(anyhow!("授权账号不存在")),
+        None => Err(anyhow!("授权账号不存在")),
+        Some(v) if v.disabled_flag == 1 => Err(anyhow!("账号已被禁用")),
         Some(v) => {
-            if v.login_token.is_empty() || !identity.match_token(v.login_token) {
-                return Err(anyhow!("授权已失效"));
-            }
+            service::session::check(state, identity).await?;
+            Ok(v)
         }
     }
-    Ok(())
 }
 
 
-pub async fn serve(){
+pub async fn serve(mut state: AppState) {
+    // 启动操作日志写入任务
+    middleware::audit::start(&mut state);
+
     // run it with hyper on localhost:8000
-    let addr = config::global().get_int("app.port").unwrap_or(8000);
+    let addr = state.config.get_int("app.port").unwrap_or(8000);
 
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", addr))
         .await
@@ -40,5 +50,10 @@ pub async fn serve(){
 
     tracing::info!("listening on {}", addr);
 
-    axum::serve(listener, router::app::init()).await.unwrap();
+    axum::serve(
+        listener,
+        router::app::init(state).into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
\ No newline at end of file
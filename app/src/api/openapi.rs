@@ -0,0 +1,210 @@
+use axum::{response::Html, Json};
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use pkg::result::response::CODES;
+
+use crate::api::controller::{
+    captcha, department, employee, login, me, menu, operate_log, position, recycle_bin, role, session,
+};
+
+// Swagger UI 页面，静态资源从 CDN 加载
+const SWAGGER_UI: &str = r##"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+    <meta charset="utf-8" />
+    <title>rs-axum-admin API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+    <script>
+        window.onload = () => {
+            window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui", persistAuthorization: true });
+        };
+    </script>
+</body>
+</html>
+"##;
+
+/// `/v1` 全部路由的接口文档
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "rs-axum-admin",
+        description = "所有接口均返回 HTTP 200 及统一结构 `Reply`：成功时 `code` 为 0、`err` 为 false；\
+            失败时 `err` 为 true，`code` 为错误码，`msg` 为提示信息，`data` 省略。"
+    ),
+    paths(
+        captcha::create,
+        login::login,
+        login::login_totp,
+        login::login_totp_setup,
+        login::refresh,
+        login::logout,
+        me::info,
+        me::change_password,
+        me::sessions,
+        me::revoke_session,
+        me::totp_status,
+        me::totp_setup,
+        me::totp_enable,
+        me::totp_disable,
+        me::totp_recovery_codes,
+        role::list,
+        role::create,
+        role::info,
+        role::delete,
+        role::update,
+        role::select_list,
+        role::export,
+        role::role_emp_list,
+        role::role_func_list,
+        role::role_func_id,
+        role::update_role_func,
+        role::role_dept_id,
+        role::update_role_dept,
+        role::add_role_emp,
+        role::remove_role_emp,
+        position::list,
+        position::create,
+        position::info,
+        position::delete,
+        position::update,
+        position::select_list,
+        position::export,
+        department::list,
+        department::create,
+        department::info,
+        department::delete,
+        department::update,
+        department::select_list,
+        department::export,
+        employee::list,
+        employee::create,
+        employee::info,
+        employee::update,
+        employee::disabled_flag,
+        employee::delete,
+        employee::reset_password,
+        employee::unlock,
+        employee::reset_totp,
+        employee::change_department,
+        employee::employee_select_list,
+        employee::export,
+        employee::import,
+        menu::tree,
+        menu::create,
+        menu::info,
+        menu::delete,
+        menu::update,
+        menu::sort,
+        menu::disabled_flag,
+        menu::visible_flag,
+        operate_log::list,
+        operate_log::info,
+        operate_log::export,
+        session::list,
+        session::kick,
+        session::kick_employee,
+        recycle_bin::list,
+        recycle_bin::restore,
+        recycle_bin::purge,
+    ),
+    modifiers(&Bearer, &Codes),
+    security(("bearer" = [])),
+)]
+pub struct ApiDoc;
+
+// 访问令牌：`Authorization: Bearer <auth_token>`
+struct Bearer;
+
+impl Modify for Bearer {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+// 在文档说明中附加错误码列表
+struct Codes;
+
+impl Modify for Codes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let mut description = openapi.info.description.take().unwrap_or_default();
+        description.push_str("\n\n| 错误码 | 说明 |\n| --- | --- |\n");
+        for (code, msg) in CODES {
+            description.push_str(&format!("| {} | {} |\n", code, msg));
+        }
+        openapi.info.description = Some(description);
+    }
+}
+
+/// 接口文档
+pub fn document() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+/// `GET /openapi.json`
+pub async fn spec() -> Json<utoipa::openapi::OpenApi> {
+    Json(document())
+}
+
+/// `GET /swagger-ui`，需在配置中开启 `openapi.swagger_ui`
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::document;
+    use crate::api::router;
+
+    // 无需登录的路由
+    const OPEN: &[(&str, &str)] = &[
+        ("GET", "/v1/captcha"),
+        ("POST", "/v1/login"),
+        ("POST", "/v1/login/totp"),
+        ("POST", "/v1/login/totp/setup"),
+        ("POST", "/v1/token/refresh"),
+    ];
+
+    #[test]
+    fn routes() {
+        let doc = document();
+        let mut documented = Vec::new();
+        for (path, item) in &doc.paths.paths {
+            for (method, operation) in [("GET", &item.get), ("POST", &item.post), ("DELETE", &item.delete)] {
+                if operation.is_some() {
+                    documented.push((method.to_string(), path.clone()));
+                }
+            }
+        }
+
+        // 文档中的路由均已注册，且需授权的路由均在权限表或仅需登录的路由表中
+        for (method, path) in &documented {
+            let open = OPEN.iter().any(|(m, p)| m == method && p == path);
+            assert!(
+                open || router::app::login_only(method, path) || router::app::perm_key(method, path).is_some(),
+                "未声明的路由：{} {}",
+                method,
+                path
+            );
+        }
+        // 声明的路由均有文档
+        let declared = router::app::routes().into_iter().chain(OPEN.iter().copied());
+        for (method, path) in declared {
+            assert!(
+                documented.iter().any(|(m, p)| m == method && p == path),
+                "缺少接口文档：{} {}",
+                method,
+                path
+            );
+        }
+        assert_eq!(doc.components.unwrap().security_schemes.len(), 1);
+    }
+}
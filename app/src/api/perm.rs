@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+use pkg::perm::PermSet;
+use pkg::state::AppState;
+use pkg::soft_delete::SoftDelete;
+
+use crate::api::router;
+use crate::ent::{
+    prelude::{TMenu, TRole, TRoleEmployee, TRoleMenu},
+    t_menu, t_role_employee, t_role_menu,
+};
+
+/// 判断权限集合是否允许访问指定路由：匹配路由表中声明的权限标识或路由写法的权限
+pub fn allow(perms: &PermSet, method: &str, path: &str) -> bool {
+    if let Some(key) = router::app::perm_key(method, path) {
+        if perms.contains(key) {
+            return true;
+        }
+    }
+    perms.matches(method, path)
+}
+
+/// 查询员工权限（角色 -> 菜单 -> api_perms），结果按员工缓存
+pub async fn resolve(state: &AppState, employee_id: i64) -> Result<Arc<PermSet>> {
+    if let Some(v) = state.perms.get(employee_id) {
+        return Ok(v);
+    }
+
+    let role_ids = TRoleEmployee::find()
+        .select_only()
+        .column(t_role_employee::Column::RoleId)
+        .filter(t_role_employee::Column::EmployeeId.eq(employee_id))
+        .filter(t_role_employee::Column::RoleId.in_subquery(TRole::alive_ids()))
+        .into_tuple::<i64>()
+        .all(&state.db)
+        .await?;
+
+    let menu_ids = TRoleMenu::find()
+        .select_only()
+        .column(t_role_menu::Column::MenuId)
+        .filter(t_role_menu::Column::RoleId.is_in(role_ids))
+        .into_tuple::<i64>()
+        .all(&state.db)
+        .await?;
+
+    let api_perms = TMenu::find_alive()
+        .select_only()
+        .column(t_menu::Column::ApiPerms)
+        .filter(t_menu::Column::MenuId.is_in(menu_ids))
+        .filter(t_menu::Column::DisabledFlag.eq(0))
+        .into_tuple::<Option<String>>()
+        .all(&state.db)
+        .await?;
+
+    let perms = Arc::new(PermSet::new(api_perms.into_iter().flatten()));
+    state.perms.insert(employee_id, perms.clone());
+    Ok(perms)
+}
+
+/// 清除指定员工的权限缓存（角色分配变更时调用）
+pub fn invalidate(state: &AppState, employee_ids: &[i64]) {
+    state.perms.remove(employee_ids);
+}
+
+/// 清除全部权限缓存（角色菜单、菜单权限变更时调用）
+pub fn invalidate_all(state: &AppState) {
+    state.perms.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use pkg::perm::PermSet;
+
+    use super::allow;
+
+    #[test]
+    fn allow_route() {
+        let perms = PermSet::new([
+            "GET /v1/roles, POST /v1/roles/update",
+            "/v1/positions/*",
+            "DELETE /v1/departments/{department_id}",
+            "system:employee:query",
+        ]);
+
+        assert!(allow(&perms, "GET", "/v1/roles"));
+        assert!(!allow(&perms, "POST", "/v1/roles"));
+        assert!(allow(&perms, "POST", "/v1/roles/update"));
+
+        assert!(allow(&perms, "GET", "/v1/positions/{post_id}"));
+        assert!(allow(&perms, "POST", "/v1/positions/update"));
+        assert!(!allow(&perms, "GET", "/v1/positions"));
+
+        assert!(allow(&perms, "DELETE", "/v1/departments/{department_id}"));
+        assert!(!allow(&perms, "GET", "/v1/departments/{department_id}"));
+
+        assert!(allow(&perms, "GET", "/v1/employees"));
+        assert!(allow(&perms, "GET", "/v1/employees/{employee_id}"));
+        assert!(!allow(&perms, "POST", "/v1/employees"));
+
+        assert!(!allow(&PermSet::default(), "GET", "/v1/roles"));
+    }
+}
@@ -1,60 +1,256 @@
 use axum::{
     body::Body,
     http::Request,
-    routing::{get, post},
+    middleware::{from_fn, from_fn_with_state},
+    routing::{delete, get, post},
     Router,
 };
 use tower_http::trace::TraceLayer;
 
+use pkg::state::AppState;
+
 use crate::api::{
-    controller::{department, login, position, role,employee},
-    middleware,
+    controller::{captcha, department, login, me, menu, operate_log, position, recycle_bin, role, employee, session},
+    middleware, openapi,
 };
 
+// 仅需登录、无需授权的路由
+const LOGIN_ONLY: &[(&str, &str)] = &[
+    ("GET", "/v1/logout"),
+    ("GET", "/v1/me"),
+    ("POST", "/v1/me/password"),
+    ("GET", "/v1/me/sessions"),
+    ("DELETE", "/v1/me/sessions/{session_id}"),
+    ("GET", "/v1/me/totp"),
+    ("POST", "/v1/me/totp/setup"),
+    ("POST", "/v1/me/totp/enable"),
+    ("POST", "/v1/me/totp/disable"),
+    ("POST", "/v1/me/totp/recovery_codes"),
+];
+
+// 路由对应的权限标识，可在菜单的 api_perms 中直接配置
+const PERMS: &[(&str, &str, &str)] = &[
+    ("GET", "/v1/roles", "system:role:query"),
+    ("POST", "/v1/roles", "system:role:add"),
+    ("GET", "/v1/roles/{role_id}", "system:role:query"),
+    ("DELETE", "/v1/roles/{role_id}", "system:role:delete"),
+    ("POST", "/v1/roles/update", "system:role:update"),
+    ("GET", "/v1/roles/select_list", "system:role:query"),
+    ("GET", "/v1/roles/export", "system:role:export"),
+    ("GET", "/v1/roles/role_emp_list", "system:role:employee:query"),
+    ("GET", "/v1/roles/role_func_list", "system:role:menu:query"),
+    ("GET", "/v1/roles/role_func_id/{role_id}", "system:role:menu:query"),
+    ("POST", "/v1/roles/role_func_update", "system:role:menu:update"),
+    ("GET", "/v1/roles/role_dept_id/{role_id}", "system:role:scope:query"),
+    ("POST", "/v1/roles/role_dept_update", "system:role:scope:update"),
+    ("POST", "/v1/roles/role_emp_add", "system:role:employee:update"),
+    ("POST", "/v1/roles/role_emp_remove", "system:role:employee:update"),
+
+    ("GET", "/v1/positions", "system:position:query"),
+    ("POST", "/v1/positions", "system:position:add"),
+    ("GET", "/v1/positions/{post_id}", "system:position:query"),
+    ("DELETE", "/v1/positions/{post_id}", "system:position:delete"),
+    ("POST", "/v1/positions/update", "system:position:update"),
+    ("GET", "/v1/positions/select_list", "system:position:query"),
+    ("GET", "/v1/positions/export", "system:position:export"),
+
+    ("GET", "/v1/departments", "system:department:query"),
+    ("POST", "/v1/departments", "system:department:add"),
+    ("GET", "/v1/departments/{department_id}", "system:department:query"),
+    ("DELETE", "/v1/departments/{department_id}", "system:department:delete"),
+    ("POST", "/v1/departments/update", "system:department:update"),
+    ("GET", "/v1/departments/select_list", "system:department:query"),
+    ("GET", "/v1/departments/export", "system:department:export"),
+
+    ("GET", "/v1/employees", "system:employee:query"),
+    ("POST", "/v1/employees", "system:employee:add"),
+    ("GET", "/v1/employees/{employee_id}", "system:employee:query"),
+    ("POST", "/v1/employees/update", "system:employee:update"),
+    ("DELETE", "/v1/employees/{employee_id}", "system:employee:delete"),
+    ("GET", "/v1/employees/disabled_flag/{employee_id}/{disabled_flag}", "system:employee:disabled"),
+    ("GET", "/v1/employees/reset_password/{employee_id}", "system:employee:password:reset"),
+    ("GET", "/v1/employees/unlock/{employee_id}", "system:employee:unlock"),
+    ("GET", "/v1/employees/reset_totp/{employee_id}", "system:employee:totp:reset"),
+    ("GET", "/v1/employees/change_department/{employee_ids}/{department_id}", "system:employee:department:update"),
+    ("GET", "/v1/employees/employee_select_list", "system:employee:query"),
+    ("GET", "/v1/employees/export", "system:employee:export"),
+    ("POST", "/v1/employees/import", "system:employee:import"),
+
+    ("GET", "/v1/menus", "system:menu:query"),
+    ("POST", "/v1/menus", "system:menu:add"),
+    ("GET", "/v1/menus/{menu_id}", "system:menu:query"),
+    ("DELETE", "/v1/menus/{menu_id}", "system:menu:delete"),
+    ("POST", "/v1/menus/update", "system:menu:update"),
+    ("POST", "/v1/menus/sort", "system:menu:update"),
+    ("GET", "/v1/menus/disabled_flag/{menu_id}/{disabled_flag}", "system:menu:update"),
+    ("GET", "/v1/menus/visible_flag/{menu_id}/{visible_flag}", "system:menu:update"),
+
+    ("GET", "/v1/operate_logs", "system:operate_log:query"),
+    ("GET", "/v1/operate_logs/{operate_log_id}", "system:operate_log:query"),
+    ("GET", "/v1/operate_logs/export", "system:operate_log:export"),
+
+    ("GET", "/v1/sessions", "system:session:query"),
+    ("DELETE", "/v1/sessions/{session_id}", "system:session:kick"),
+    ("DELETE", "/v1/sessions/employee/{employee_id}", "system:session:kick"),
+
+    ("GET", "/v1/recycle_bin", "system:recycle_bin:query"),
+    ("POST", "/v1/recycle_bin/{type}/{id}/restore", "system:recycle_bin:restore"),
+    ("DELETE", "/v1/recycle_bin/{type}/{id}", "system:recycle_bin:purge"),
+];
+
+// 会修改数据的 GET 路由，需记录操作日志
+const MUTATING_GET: &[&str] = &[
+    "/v1/logout",
+    "/v1/employees/disabled_flag/{employee_id}/{disabled_flag}",
+    "/v1/employees/reset_password/{employee_id}",
+    "/v1/employees/unlock/{employee_id}",
+    "/v1/employees/reset_totp/{employee_id}",
+    "/v1/employees/change_department/{employee_ids}/{department_id}",
+    "/v1/menus/disabled_flag/{menu_id}/{disabled_flag}",
+    "/v1/menus/visible_flag/{menu_id}/{visible_flag}",
+];
+
+// 需修改密码时仍可访问的路由
+const PASSWORD_CHANGE: &[(&str, &str)] = &[
+    ("GET", "/v1/logout"),
+    ("GET", "/v1/me"),
+    ("POST", "/v1/me/password"),
+];
 
+pub fn mutating_get(path: &str) -> bool {
+    MUTATING_GET.contains(&path)
+}
 
-pub fn init() -> Router{
+pub fn login_only(method: &str, path: &str) -> bool {
+    LOGIN_ONLY.iter().any(|(m, p)| *m == method && *p == path)
+}
+
+pub fn password_change(method: &str, path: &str) -> bool {
+    PASSWORD_CHANGE.iter().any(|(m, p)| *m == method && *p == path)
+}
+
+pub fn perm_key(method: &str, path: &str) -> Option<&'static str> {
+    PERMS
+        .iter()
+        .find(|(m, p, _)| *m == method && *p == path)
+        .map(|(_, _, key)| *key)
+}
+
+/// 权限表及仅需登录的路由表中声明的全部路由
+pub fn routes() -> Vec<(&'static str, &'static str)> {
+    LOGIN_ONLY
+        .iter()
+        .copied()
+        .chain(PERMS.iter().map(|(m, p, _)| (*m, *p)))
+        .collect()
+}
+
+/// 路由表中声明的全部权限标识（去重，保持声明顺序）
+pub fn perm_keys() -> Vec<&'static str> {
+    let mut keys: Vec<&'static str> = Vec::new();
+    for (_, _, key) in PERMS {
+        if !keys.contains(key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+pub fn init(state: AppState) -> Router {
     // 开放
-    let open = Router::new().route("/login", post(login::login));
+    let open = Router::new()
+        .route("/captcha", get(captcha::create))
+        .route("/login", post(login::login))
+        .route("/login/totp", post(login::login_totp))
+        .route("/login/totp/setup", post(login::login_totp_setup))
+        .route("/token/refresh", post(login::refresh));
 
     // 需授权
     let auth = Router::new()
         .route("/logout", get(login::logout))
+        .route("/me", get(me::info))
+        .route("/me/password", post(me::change_password))
+        .route("/me/sessions", get(me::sessions))
+        .route("/me/sessions/{session_id}", delete(me::revoke_session))
+        .route("/me/totp", get(me::totp_status))
+        .route("/me/totp/setup", post(me::totp_setup))
+        .route("/me/totp/enable", post(me::totp_enable))
+        .route("/me/totp/disable", post(me::totp_disable))
+        .route("/me/totp/recovery_codes", post(me::totp_recovery_codes))
         .route("/roles", get(role::list).post(role::create))  
         .route("/roles/{role_id}", get(role::info).delete(role::delete))
         .route("/roles/update", post(role::update))
         .route("/roles/select_list", get(role::select_list))
+        .route("/roles/export", get(role::export))
         .route("/roles/role_emp_list", get(role::role_emp_list))
         .route("/roles/role_func_list", get(role::role_func_list))
-        .route("/roles/role_func_id", get(role::role_func_id))
+        .route("/roles/role_func_id/{role_id}", get(role::role_func_id))
+        .route("/roles/role_func_update", post(role::update_role_func))
+        .route("/roles/role_dept_id/{role_id}", get(role::role_dept_id))
+        .route("/roles/role_dept_update", post(role::update_role_dept))
+        .route("/roles/role_emp_add", post(role::add_role_emp))
+        .route("/roles/role_emp_remove", post(role::remove_role_emp))
         
         .route("/positions", get(position::list).post(position::create))
         .route("/positions/{post_id}", get(position::info).delete(position::delete))
         .route("/positions/update", post(position::update))
         .route("/positions/select_list", get(position::select_list))
+        .route("/positions/export", get(position::export))
         
         
         .route("/departments", get(department::list).post(department::create))
         .route("/departments/{department_id}", get(department::info).delete(department::delete))
         .route("/departments/update", post(department::update))
         .route("/departments/select_list", get(department::select_list))
+        .route("/departments/export", get(department::export))
         
         .route("/employees", get(employee::list).post(employee::create))
-        .route("/employees/{employee_id}", get(employee::info))
+        .route("/employees/{employee_id}", get(employee::info).delete(employee::delete))
         .route("/employees/update", post(employee::update))
         .route("/employees/disabled_flag/{employee_id}/{disabled_flag}", get(employee::disabled_flag))
         .route("/employees/reset_password/{employee_id}", get(employee::reset_password))
+        .route("/employees/unlock/{employee_id}", get(employee::unlock))
+        .route("/employees/reset_totp/{employee_id}", get(employee::reset_totp))
         .route("/employees/change_department/{employee_ids}/{department_id}", get(employee::change_department))
         .route("/employees/employee_select_list", get(employee::employee_select_list))
+        .route("/employees/export", get(employee::export))
+        .route("/employees/import", post(employee::import))
 
-        .layer(axum::middleware::from_fn(middleware::auth::handle));
+        .route("/menus", get(menu::tree).post(menu::create))
+        .route("/menus/{menu_id}", get(menu::info).delete(menu::delete))
+        .route("/menus/update", post(menu::update))
+        .route("/menus/sort", post(menu::sort))
+        .route("/menus/disabled_flag/{menu_id}/{disabled_flag}", get(menu::disabled_flag))
+        .route("/menus/visible_flag/{menu_id}/{visible_flag}", get(menu::visible_flag))
 
-        Router::new()
+        .route("/operate_logs", get(operate_log::list))
+        .route("/operate_logs/{operate_log_id}", get(operate_log::info))
+        .route("/operate_logs/export", get(operate_log::export))
+
+        .route("/sessions", get(session::list))
+        .route("/sessions/{session_id}", delete(session::kick))
+        .route("/sessions/employee/{employee_id}", delete(session::kick_employee))
+
+        .route("/recycle_bin", get(recycle_bin::list))
+        .route("/recycle_bin/{type}/{id}/restore", post(recycle_bin::restore))
+        .route("/recycle_bin/{type}/{id}", delete(recycle_bin::purge))
+
+        .layer(from_fn_with_state(state.clone(), middleware::audit::handle)) // 操作日志
+        .layer(from_fn_with_state(state.clone(), middleware::auth::handle));
+
+        let mut root = Router::new()
             .route("/", get(|| async { "☺ welcome to Rust app" }))
+            .route("/openapi.json", get(openapi::spec));
+        // 接口调试页面
+        if state.config.get_bool("openapi.swagger_ui").unwrap_or_default() {
+            root = root.route("/swagger-ui", get(openapi::swagger_ui));
+        }
+
+        root
             .nest("/v1", open.merge(auth))
-            .layer(axum::middleware::from_fn(pkg::middleware::log::handle)) // 请求日志
-            .layer(axum::middleware::from_fn(pkg::middleware::identity::handle))// 请求身份验证
-            .layer(axum::middleware::from_fn(pkg::middleware::cors::handle))// 请求跨域
+            .layer(from_fn(pkg::middleware::log::handle)) // 请求日志
+            .layer(from_fn_with_state(state.clone(), pkg::middleware::identity::handle))// 请求身份验证
+            .layer(from_fn(pkg::middleware::cors::handle))// 请求跨域
             .layer(
                 TraceLayer::new_for_http().make_span_with(|request: &Request<Body>| {
                     let req_id = match request
@@ -69,5 +265,6 @@ pub fn init() -> Router{
                     tracing::error_span!("request_id", id = req_id)
                 }),
             )
-            .layer(axum::middleware::from_fn(pkg::middleware::req_id::handle))
+            .layer(from_fn(pkg::middleware::req_id::handle))
+            .with_state(state)
 }
\ No newline at end of file
@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter, QuerySelect, Select};
+
+use pkg::state::AppState;
+use pkg::soft_delete::SoftDelete;
+
+use crate::ent::{
+    prelude::{TDepartment, TEmployee, TRole, TRoleDepartment, TRoleEmployee},
+    t_department, t_role, t_role_department, t_role_employee,
+};
+
+/// 全部数据
+pub const ALL: i16 = 1;
+/// 自定义部门
+pub const CUSTOM: i16 = 2;
+/// 本部门
+pub const DEPARTMENT: i16 = 3;
+/// 本部门及以下
+pub const DEPARTMENT_TREE: i16 = 4;
+/// 仅本人
+pub const SELF: i16 = 5;
+
+/// 数据范围名称
+pub fn label(data_scope: i16) -> &'static str {
+    match data_scope {
+        ALL => "全部数据",
+        CUSTOM => "自定义部门",
+        DEPARTMENT => "本部门",
+        DEPARTMENT_TREE => "本部门及以下",
+        SELF => "仅本人",
+        _ => "",
+    }
+}
+
+/// 员工的数据范围（多个角色取并集）
+#[derive(Debug, Default)]
+pub struct DataScope {
+    all: bool,
+    employee_id: i64,
+    department_id: i64,
+    self_only: bool,
+    department_ids: HashSet<i64>,
+}
+
+impl DataScope {
+    /// 不限制数据范围
+    pub fn all() -> Self {
+        Self {
+            all: true,
+            ..Default::default()
+        }
+    }
+
+    /// 为查询追加数据范围条件
+    ///
+    /// `department` 为数据所属部门列，`employee` 为数据所属员工列；
+    /// 数据没有所属员工时（如部门列表），“仅本人”按本人所在部门处理
+    pub fn apply<E: EntityTrait>(
+        &self,
+        select: Select<E>,
+        department: E::Column,
+        employee: Option<E::Column>,
+    ) -> Select<E> {
+        if self.all {
+            return select;
+        }
+
+        let mut department_ids: Vec<i64> = self.department_ids.iter().copied().collect();
+        let mut cond = Condition::any();
+        if self.self_only {
+            match employee {
+                Some(col) => cond = cond.add(col.eq(self.employee_id)),
+                None => department_ids.push(self.department_id),
+            }
+        }
+        // 空集合生成恒假条件，无任何数据
+        cond = cond.add(department.is_in(department_ids));
+
+        select.filter(cond)
+    }
+}
+
+/// 查询员工的数据范围：超级管理员不限制；未分配角色时仅本人
+pub async fn resolve(state: &AppState, employee_id: i64) -> Result<DataScope> {
+    let employee = match TEmployee::find_by_id(employee_id).one(&state.db).await? {
+        Some(v) => v,
+        None => return Ok(DataScope::default()),
+    };
+    if employee.administrator_flag == 1 {
+        return Ok(DataScope::all());
+    }
+
+    let mut scope = DataScope {
+        employee_id,
+        department_id: employee.department_id,
+        ..Default::default()
+    };
+
+    let role_ids = TRoleEmployee::find()
+        .select_only()
+        .column(t_role_employee::Column::RoleId)
+        .filter(t_role_employee::Column::EmployeeId.eq(employee_id))
+        .filter(t_role_employee::Column::RoleId.in_subquery(TRole::alive_ids()))
+        .into_tuple::<i64>()
+        .all(&state.db)
+        .await?;
+    let roles = TRole::find()
+        .select_only()
+        .column(t_role::Column::RoleId)
+        .column(t_role::Column::DataScope)
+        .filter(t_role::Column::RoleId.is_in(role_ids))
+        .into_tuple::<(i64, i16)>()
+        .all(&state.db)
+        .await?;
+    if roles.is_empty() {
+        scope.self_only = true;
+        return Ok(scope);
+    }
+
+    let mut custom = Vec::new();
+    for (role_id, data_scope) in roles {
+        match data_scope {
+            ALL => return Ok(DataScope::all()),
+            CUSTOM => custom.push(role_id),
+            DEPARTMENT => {
+                scope.department_ids.insert(employee.department_id);
+            }
+            DEPARTMENT_TREE => {
+                let tree = TDepartment::find_alive()
+                    .select_only()
+                    .column(t_department::Column::DepartmentId)
+                    .column(t_department::Column::ParentId)
+                    .into_tuple::<(i64, i64)>()
+                    .all(&state.db)
+                    .await?;
+                scope
+                    .department_ids
+                    .extend(descendants(&tree, employee.department_id));
+            }
+            _ => scope.self_only = true,
+        }
+    }
+
+    if !custom.is_empty() {
+        let ids = TRoleDepartment::find()
+            .select_only()
+            .column(t_role_department::Column::DepartmentId)
+            .filter(t_role_department::Column::RoleId.is_in(custom))
+            .into_tuple::<i64>()
+            .all(&state.db)
+            .await?;
+        scope.department_ids.extend(ids);
+    }
+
+    Ok(scope)
+}
+
+// 部门及其全部下级部门，`tree` 为 (department_id, parent_id)
+fn descendants(tree: &[(i64, i64)], root: i64) -> HashSet<i64> {
+    let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (id, parent_id) in tree {
+        children.entry(*parent_id).or_default().push(*id);
+    }
+
+    let mut ret = HashSet::from([root]);
+    let mut stack = vec![root];
+    while let Some(id) = stack.pop() {
+        for child in children.get(&id).into_iter().flatten() {
+            // 防止错误数据形成环
+            if ret.insert(*child) {
+                stack.push(*child);
+            }
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use sea_orm::{DbBackend, EntityTrait, QueryTrait};
+
+    use super::{descendants, DataScope};
+    use crate::ent::{prelude::TEmployee, t_employee};
+
+    #[test]
+    fn tree() {
+        let tree = [(1, 0), (2, 1), (3, 1), (4, 2), (5, 0), (6, 6)];
+        assert_eq!(descendants(&tree, 1), HashSet::from([1, 2, 3, 4]));
+        assert_eq!(descendants(&tree, 2), HashSet::from([2, 4]));
+        assert_eq!(descendants(&tree, 5), HashSet::from([5]));
+        assert_eq!(descendants(&tree, 6), HashSet::from([6]));
+    }
+
+    #[test]
+    fn apply() {
+        let sql = |scope: DataScope| {
+            scope
+                .apply(
+                    TEmployee::find(),
+                    t_employee::Column::DepartmentId,
+                    Some(t_employee::Column::EmployeeId),
+                )
+                .build(DbBackend::MySql)
+                .to_string()
+        };
+
+        assert!(!sql(DataScope::all()).contains("WHERE"));
+
+        let scope = DataScope {
+            employee_id: 7,
+            self_only: true,
+            ..Default::default()
+        };
+        assert!(sql(scope).ends_with("WHERE `t_employee`.`employee_id` = 7 OR 1 = 2"));
+
+        let scope = DataScope {
+            department_ids: HashSet::from([3]),
+            ..Default::default()
+        };
+        assert!(sql(scope).ends_with("WHERE `t_employee`.`department_id` IN (3)"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use pkg::{
+    captcha::{self, Captcha},
+    result::response::{ApiErr, ApiOK, Result},
+    state::AppState,
+    util,
+};
+
+use crate::api::service::lockout;
+
+/** 验证码启用方式 */
+#[derive(Debug, PartialEq, Eq)]
+enum Mode {
+    // 不启用
+    Off,
+    // 每次登录均需验证码
+    Always,
+    // 登录失败达到次数后需要验证码
+    Failures(i64),
+}
+
+fn mode(state: &AppState) -> Mode {
+    let cfg = &state.config;
+    match cfg.get_string("captcha.mode").unwrap_or_default().as_str() {
+        "always" => Mode::Always,
+        "failures" => Mode::Failures(cfg.get_int("captcha.after_failures").unwrap_or(3)),
+        _ => Mode::Off,
+    }
+}
+
+fn ttl(state: &AppState) -> u64 {
+    state.config.get_int("captcha.ttl").unwrap_or(120) as u64
+}
+
+/** 返回验证码 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = captcha::RespCaptcha)]
+pub struct RespCaptcha {
+    pub captcha_id: String,
+    // SVG 图片的 data URI
+    pub image: String,
+    pub expires_in: u64,
+}
+
+/** 生成验证码 */
+pub async fn create(state: &AppState) -> Result<ApiOK<RespCaptcha>> {
+    let captcha = Captcha::new(state.config.get_int("captcha.length").unwrap_or(4) as usize);
+    let captcha_id = util::nonce(32);
+    let ttl = ttl(state);
+
+    state.cache
+        .set(&key(&captcha_id), captcha.answer(), Duration::from_secs(ttl))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error save captcha");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    let resp = RespCaptcha {
+        captcha_id,
+        image: captcha.data_uri(),
+        expires_in: ttl,
+    };
+    Ok(ApiOK(Some(resp)))
+}
+
+/**
+ * 登录时校验验证码；验证码仅可使用一次
+ */
+pub async fn check(
+    state: &AppState,
+    login_name: &str,
+    ip: &str,
+    captcha_id: Option<&str>,
+    answer: Option<&str>,
+) -> Result<()> {
+    match mode(state) {
+        Mode::Off => return Ok(()),
+        Mode::Always => (),
+        Mode::Failures(n) => {
+            if lockout::failures(state, login_name, ip).await? < n {
+                return Ok(());
+            }
+        }
+    }
+
+    let (captcha_id, answer) = match (captcha_id, answer) {
+        (Some(id), Some(answer)) if !id.is_empty() && !answer.is_empty() => (id, answer),
+        _ => return Err(ApiErr::ErrCaptcha(Some("请输入验证码".to_string()))),
+    };
+
+    let store = &state.cache;
+    let expected = store.get(&key(captcha_id)).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find captcha");
+        ApiErr::ErrSystem(None)
+    })?;
+    if let Err(e) = store.del(&key(captcha_id)).await {
+        tracing::error!(error = ?e, "error remove captcha");
+    }
+
+    match expected {
+        None => Err(ApiErr::ErrCaptcha(Some("验证码已过期".to_string()))),
+        Some(v) if captcha::matches(&v, answer) => Ok(()),
+        Some(_) => Err(ApiErr::ErrCaptcha(None)),
+    }
+}
+
+fn key(captcha_id: &str) -> String {
+    format!("captcha:{}", captcha_id)
+}
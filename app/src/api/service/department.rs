@@ -1,28 +1,37 @@
 use std::collections::HashMap;
 
+use axum::response::Response;
 use sea_orm::{
-    ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set
+    ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QuerySelect, Set
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use time::macros::offset;
 use validator::Validate;
 
 use pkg::{
-    db,
-    result::response::{ApiErr, ApiOK, Result},
-    util,xtime,
+    export,
+    identity::Identity,
+    query::{Filter, Kind, ListQuery, Spec},
+    soft_delete::SoftDelete,
+    result::{
+        page::Page,
+        response::{ApiErr, ApiOK, Result},
+    },
+    state::AppState,
+    xtime,
     tree,
 };
 
 
-use crate::ent::{
-    t_department, prelude::TDepartment, t_employee, prelude::TEmployee
-};
+use crate::api::scope;
+use crate::ent::{t_department, prelude::TDepartment, t_employee, prelude::TEmployee};
 
 
 
 /** 封装添加数据对象 */
-#[derive(Debug, Validate,  Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = department::ReqCreate)]
 pub struct ReqCreate {
     #[validate(length(min = 1, message = "部门名称必填"))]
     pub deptname: String,
@@ -32,10 +41,10 @@ pub struct ReqCreate {
 }
 
 /** 添加方法 */
-pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
+pub async fn create(state: &AppState, req: ReqCreate) -> Result<ApiOK<()>> {
     let count: u64 = TDepartment::find()
         .filter(t_department::Column::DepartmentName.eq(req.deptname.clone()))
-        .count(db::conn())
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error count t_department");
@@ -46,8 +55,8 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
         return Err(ApiErr::ErrPerm(Some("部门名称重复".to_string())));
     }
 
-    /** 创建数据对象 */
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+    /* 创建数据对象 */
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_department::ActiveModel {
         department_name: Set(req.deptname),  
         sort: Set(req.sort),
@@ -57,7 +66,7 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
         ..Default::default()
     };
     /* 插入数据 */
-    if let Err(e) = TDepartment::insert(model).exec(db::conn()).await {
+    if let Err(e) = TDepartment::insert(model).exec(&state.db).await {
         tracing::error!(error = ?e, "error insert t_department");
         return Err(ApiErr::ErrSystem(None));
     }
@@ -66,7 +75,8 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
 }
 
 // 封装部门下拉列表数据对象
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = department::RespSelect)]
 pub struct RespSelect {
     pub department_id: i64,
     pub department_name: String,
@@ -74,13 +84,14 @@ pub struct RespSelect {
 }
 
 // 查询部门树形列表
-pub async fn select_list() ->  Result<ApiOK<Vec<tree::TreeNode>>> {
-    let department_list= TDepartment::find()
+pub async fn select_list(state: &AppState) ->  Result<ApiOK<Vec<tree::TreeNode>>> {
+    let department_list= TDepartment::find_alive()
             .select_only()
             .column(t_department::Column::DepartmentId)
             .column(t_department::Column::DepartmentName)
             .column(t_department::Column::ParentId)
-            .all(db::conn())
+            .into_tuple::<(i64, String, i64)>()
+            .all(&state.db)
             .await
             .map_err(|e| {
                 tracing::error!(error = ?e, "error find t_department");
@@ -88,11 +99,11 @@ pub async fn select_list() ->  Result<ApiOK<Vec<tree::TreeNode>>> {
             })?;
 
     let mut list = Vec::new();
-    for department in &department_list {
+    for (department_id, department_name, parent_id) in department_list {
         list.push(RespSelect {
-            department_id: department.department_id,
-            department_name: department.department_name.clone(),
-            parent_id: department.parent_id,
+            department_id,
+            department_name,
+            parent_id,
         });
     }
 
@@ -102,7 +113,8 @@ pub async fn select_list() ->  Result<ApiOK<Vec<tree::TreeNode>>> {
 }
 
 // 封装返回数据对象
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = department::RespInfo)]
 pub struct RespInfo{
     pub department_id: i64,
     pub department_name: String,
@@ -113,89 +125,145 @@ pub struct RespInfo{
     pub create_time_str: String,
 }
 
-/** 返回列表数据对象 */
-#[derive(Debug, Serialize)]
-pub struct RespList {
-    pub total: i64,
-    pub list: Vec<RespInfo>,
-}
+
+/** 列表查询条件 */
+const LIST: Spec<t_department::Column> = Spec {
+    filters: &[
+        Filter::like("department_name", t_department::Column::DepartmentName),
+        // 兼容旧参数名
+        Filter::like("deptname", t_department::Column::DepartmentName),
+        Filter::eq("parent_id", t_department::Column::ParentId, Kind::Int),
+        Filter::eq("manager_id", t_department::Column::ManagerId, Kind::Int),
+    ],
+    sorts: &[
+        ("department_id", t_department::Column::DepartmentId),
+        ("department_name", t_department::Column::DepartmentName),
+        ("sort", t_department::Column::Sort),
+        ("create_time", t_department::Column::CreateTime),
+    ],
+    default_sort: &[(t_department::Column::DepartmentId, Order::Desc)],
+    cursor: None,
+};
 
 /** 获取列表 */
-pub async fn list(query: HashMap<String, String>) -> Result<ApiOK<RespList>> {
-    let mut builder = TDepartment::find();
-    if let Some(deptrtment_name) = query.get("deptname") {
-        builder = builder.filter(t_department::Column::DepartmentName.contains(deptrtment_name));
+pub async fn list(state: &AppState, identity: Identity, query: ListQuery) -> Result<ApiOK<Page<RespInfo>>> {
+    let query = LIST.compile(&query)?;
+    let scope = scope::resolve(state, identity.id()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error resolve data scope");
+        ApiErr::ErrSystem(None)
+    })?;
+    let builder = scope.apply(TDepartment::find_alive(), t_department::Column::DepartmentId, None);
+
+    let page = query.fetch(&state.db, builder).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find t_department");
+        ApiErr::ErrSystem(None)
+    })?;
+    let resp = page.map(|model| RespInfo {
+        department_id: model.department_id,
+        department_name: model.department_name,
+        manager_id: model.manager_id,
+        parent_id: model.parent_id,
+        sort: model.sort,
+        create_time: model.create_time,
+        create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+            .unwrap_or_default(),
+    });
 
-    }
+    Ok(ApiOK(Some(resp)))
 
-    let mut total: i64 = 0;
-    let (offset, limit) = util::query_page(&query);
-    // 仅在第一页计算数量
-    if offset == 0 {
-        total = builder
-            .clone()
-            .select_only()
-            .column_as(t_department::Column::DepartmentId.count(), "count")
-            .into_tuple::<i64>()
-            .one(db::conn())
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "error count t_department");
-                ApiErr::ErrSystem(None)
-            })?
-            .unwrap_or_default();
-    }
 
+}
 
-    let models = builder
-        .order_by(t_department::Column::DepartmentId, Order::Desc)
-        .offset(offset)
-        .limit(limit)
-        .all(db::conn())
+/** 导出表头 */
+const EXPORT_HEADERS: &[&str] = &["部门ID", "部门名称", "上级部门", "负责人", "排序", "创建时间"];
+
+/** 导出：查询条件同列表，`format` 为 csv 或 xlsx */
+pub async fn export(state: &AppState, identity: Identity, mut query: ListQuery) -> Result<Response> {
+    let format = export::Format::parse(query.take("format").as_deref())?;
+    let query = LIST.compile(&query)?;
+    let scope = scope::resolve(state, identity.id()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error resolve data scope");
+        ApiErr::ErrSystem(None)
+    })?;
+    let builder = scope.apply(TDepartment::find_alive(), t_department::Column::DepartmentId, None);
+
+    let total = query.total(&state.db, builder.clone()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error count t_department");
+        ApiErr::ErrSystem(None)
+    })?;
+    export::check_rows(total, &state.config)?;
+
+    // 上级部门、负责人名称
+    let departments: HashMap<i64, String> = TDepartment::find()
+        .select_only()
+        .column(t_department::Column::DepartmentId)
+        .column(t_department::Column::DepartmentName)
+        .into_tuple::<(i64, String)>()
+        .all(&state.db)
         .await
         .map_err(|e| {
-            tracing::error!(error = ?e, "error find t_role");
+            tracing::error!(error = ?e, "error find t_department");
             ApiErr::ErrSystem(None)
-        })?;
-
-    let mut resp = RespList {
-        total,
-        list: (Vec::with_capacity(models.len())),
-    };
-
-    for model in models {
-        let info = RespInfo {
-            department_id: model.department_id,
-            department_name: model.department_name,
-            manager_id: model.manager_id,
-            parent_id: model.parent_id,
-            sort: model.sort,
-            create_time: model.create_time,
-            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
-            .unwrap_or_default(),
-        };
-        resp.list.push(info);
-    }
-
-    Ok(ApiOK(Some(resp)))
+        })?
+        .into_iter()
+        .collect();
+    let managers: HashMap<i64, String> = TEmployee::find()
+        .select_only()
+        .column(t_employee::Column::EmployeeId)
+        .column(t_employee::Column::Realname)
+        .filter(
+            t_employee::Column::EmployeeId.in_subquery(
+                sea_orm::sea_query::Query::select()
+                    .column(t_department::Column::ManagerId)
+                    .from(t_department::Entity)
+                    .to_owned(),
+            ),
+        )
+        .into_tuple::<(i64, String)>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .collect();
+
+    let batches = query.batches(builder, t_department::Column::DepartmentId, export::BATCH_SIZE);
+    let response = export::response(state.db.clone(), batches, format, "departments", EXPORT_HEADERS, move |model| {
+        vec![
+            model.department_id.to_string(),
+            model.department_name,
+            departments.get(&model.parent_id).cloned().unwrap_or_default(),
+            managers.get(&model.manager_id).cloned().unwrap_or_default(),
+            model.sort.to_string(),
+            xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8)).unwrap_or_default(),
+        ]
+    });
+
+    Ok(response)
+}
 
 
+// 查询未删除的部门
+async fn find_department(state: &AppState, department_id: i64) -> Result<t_department::Model> {
+    TDepartment::find_alive()
+        .filter(t_department::Column::DepartmentId.eq(department_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_department");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("部门信息不存在".to_string())))
 }
 
-
 /** 获取详情 */
-pub async fn info(department_id: i64) -> Result<ApiOK<RespInfo>> {
+pub async fn info(state: &AppState, department_id: i64) -> Result<ApiOK<RespInfo>> {
 
-    let model = TDepartment::find_by_id(department_id)
-        .one(db::conn())
-        .await 
-        .map_err(|e| {
-                    tracing::error!(error = ?e, "error find t_department");
-                    ApiErr::ErrSystem(None)
-        })?
-        .ok_or(ApiErr::ErrNotFound(Some("部门信息不存在".to_string())))?;
+    let model = find_department(state, department_id).await?;
 
-   let mut resp = RespInfo {
+   let resp = RespInfo {
         department_id: model.department_id,
         department_name: model.department_name,
         manager_id: model.manager_id,
@@ -210,7 +278,8 @@ pub async fn info(department_id: i64) -> Result<ApiOK<RespInfo>> {
 
 
 /** 封装修改数据对象 */
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = department::UpdateInfo)]
 pub struct UpdateInfo {
     pub deptid: i64,
     #[validate(length(min = 1, message = "部门名称必填"))]
@@ -222,9 +291,12 @@ pub struct UpdateInfo {
     pub create_time_str: String,
 }
 // 修改方法
-pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+pub async fn update(state: &AppState, req: UpdateInfo) -> Result<ApiOK<()>> {
+    find_department(state, req.deptid).await?;
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_department::ActiveModel {
+        department_id: Set(req.deptid),
         department_name: Set(req.deptname),  
         sort: Set(req.sort),
         manager_id: Set(req.managerid),
@@ -234,7 +306,7 @@ pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
     };
 
     if let Err(e) = TDepartment::update(model)
-    .exec(db::conn())
+    .exec(&state.db)
     .await {
         tracing::error!(error = ?e, "error update t_department");
         return Err(ApiErr::ErrSystem(None));
@@ -242,12 +314,14 @@ pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
     Ok(ApiOK(None))
 }
 
-// 删除部门
-pub async fn delete(department_id: i64) -> Result<ApiOK<()>> {
+// 删除部门（逻辑删除，可在回收站中恢复）
+pub async fn delete(state: &AppState, department_id: i64) -> Result<ApiOK<()>> {
+    find_department(state, department_id).await?;
+
     //判断是否有子部门
-    let department_count = TDepartment::find()
+    let department_count = TDepartment::find_alive()
         .filter(t_department::Column::ParentId.eq(department_id))
-        .count(db::conn())
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_department");
@@ -259,9 +333,9 @@ pub async fn delete(department_id: i64) -> Result<ApiOK<()>> {
     }
 
     //判断删除部门时，该部门下是否有用户
-    let  employee_count = TEmployee::find()
+    let  employee_count = TEmployee::find_alive()
         .filter(t_employee::Column::DepartmentId.eq(department_id))
-        .count(db::conn())
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_employee");
@@ -272,8 +346,9 @@ pub async fn delete(department_id: i64) -> Result<ApiOK<()>> {
         return Err(ApiErr::ErrPerm(Some("该部门下已分配员工，无法删除".to_string())));
     }
 
-
-    if let Err(e) = TDepartment::delete_by_id(department_id).exec(db::conn()).await {
+    // 角色自定义数据范围中的该部门在永久删除时清除
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    if let Err(e) = TDepartment::soft_delete(&state.db, vec![department_id], now).await {
         tracing::error!(error = ?e, "error delete t_department");
         return Err(ApiErr::ErrSystem(None));
     }
@@ -1,29 +1,43 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use sea_orm::prelude::Expr;
 use sea_orm::{
-    ColumnTrait, Condition, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set
+    ColumnTrait, Condition, EntityTrait, Order, PaginatorTrait, QueryFilter, QuerySelect, Set, SqlErr,
+    TransactionTrait,
 };
+use axum::body::Bytes;
+use axum::response::Response;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use time::macros::offset;
 use validator::Validate;
 
-use pkg::crypto::hash::md5;
 use pkg::{
-    db,
-    result::response::{ApiErr, ApiOK, Result},
-    util,xtime,
+    export,
+    identity::Identity,
+    import::{self, Sheet},
+    query::{Filter, Kind, ListQuery, Spec},
+    soft_delete::SoftDelete,
+    result::{
+        page::Page,
+        response::{ApiErr, ApiOK, Result},
+    },
+    state::AppState,
+    xtime,
 
 };
 
 
-use crate::ent::{prelude::TEmployee, t_employee,t_department,prelude::TDepartment};
+use crate::api::{perm, scope};
+use crate::api::service::{self, password::RespPassword};
+use crate::ent::{prelude::TEmployee, t_employee,t_department,prelude::TDepartment, prelude::TPosition, t_position};
 
 
 
 
 /** 封装添加数据对象 */
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = employee::ReqCreate)]
 pub struct ReqCreate{
     #[validate(length(min = 1, message = "员工姓名必填"))]
     pub realname:String,
@@ -34,8 +48,8 @@ pub struct ReqCreate{
     pub login_name: String,
     #[validate(length(min = 1, message = "邮箱必填"))]
     pub email: String,
-    pub gender:u8,
-    pub disabled_flag:u8,
+    pub gender:i16,
+    pub disabled_flag:i16,
     pub position_id:i64,
 
 }
@@ -43,11 +57,11 @@ pub struct ReqCreate{
 
  
 
-pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
+pub async fn create(state: &AppState, req: ReqCreate) -> Result<ApiOK<RespPassword>> {
     // 验证登录名是否已存在
     let login_name_count = TEmployee::find()
         .filter(t_employee::Column::LoginName.eq(req.login_name.clone()))
-        .count(db::conn())
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error count login_name");
@@ -60,7 +74,7 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
 
     let phone_count = TEmployee::find()
         .filter(t_employee::Column::Phone.eq(req.phone.clone()))
-        .count(db::conn())
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error count phone");
@@ -71,32 +85,41 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
         return Err(ApiErr::ErrPerm(Some("手机号码已重复".to_string())));
     }
 
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+    // 初始密码随机生成，首次登录需修改
+    let (plain, login_pwd) = service::password::one_time(state)?;
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_employee::ActiveModel {
         realname: Set(req.realname),
         phone: Set(req.phone),
         department_id: Set(req.department_id),
-        login_name: Set(req.login_name.clone()),
-        login_pwd: Set(md5(req.login_name.clone().as_bytes()).to_string()),
+        login_name: Set(req.login_name),
+        login_pwd: Set(login_pwd.clone()),
         email: Set(req.email),
         gender: Set(req.gender),
         disabled_flag: Set(req.disabled_flag),
         position_id: Set(req.position_id),
+        must_change_password: Set(1),
         create_time: Set(now),
         ..Default::default()
     };
 
-    if let Err(e) = TEmployee::insert(model)
-        .exec(db::conn())
+    let ret = match TEmployee::insert(model)
+        .exec(&state.db)
         .await{
-            tracing::error!(error = ?e, "error insert t_employee");
-            return Err(ApiErr::ErrSystem(None));
-        }
+            Ok(v) => v,
+            Err(e) => {
+                tracing::error!(error = ?e, "error insert t_employee");
+                return Err(ApiErr::ErrSystem(None));
+            }
+        };
+    service::password::save_history(state, &state.db, ret.last_insert_id, &login_pwd).await?;
 
-    Ok(ApiOK(None))     
+    Ok(ApiOK(Some(RespPassword { password: plain })))
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = employee::RespInfo)]
 pub struct RespInfo{
     pub employee_id:i64,
     pub realname:String,
@@ -104,112 +127,437 @@ pub struct RespInfo{
     pub department_id:i64,
     pub login_name: String,
     pub email: String,
-    pub gender:u8,
-    pub disabled_flag:u8,
+    pub gender:i16,
+    pub disabled_flag:i16,
     pub position_id:i64,
     pub create_time:i64,
     pub create_time_str:String,
 }
 
-/** 返回列表数据对象 */
-#[derive(Debug, Serialize)]
-pub struct RespList {
-    pub total: i64,
-    pub list: Vec<RespInfo>,
+
+/** 列表查询条件 */
+const LIST: Spec<t_employee::Column> = Spec {
+    filters: &[
+        Filter::like("login_name", t_employee::Column::LoginName),
+        Filter::like("realname", t_employee::Column::Realname),
+        Filter::like("phone", t_employee::Column::Phone),
+        Filter::eq("disabled_flag", t_employee::Column::DisabledFlag, Kind::Int),
+        Filter::is_in("department_id", t_employee::Column::DepartmentId, Kind::Int),
+        Filter::is_in("position_id", t_employee::Column::PositionId, Kind::Int),
+        Filter::range("create_time", t_employee::Column::CreateTime, Kind::Time),
+    ],
+    sorts: &[
+        ("employee_id", t_employee::Column::EmployeeId),
+        ("login_name", t_employee::Column::LoginName),
+        ("realname", t_employee::Column::Realname),
+        ("create_time", t_employee::Column::CreateTime),
+    ],
+    default_sort: &[(t_employee::Column::EmployeeId, Order::Desc)],
+    cursor: None,
+};
+
+/** 获取列表 */
+pub async fn list(state: &AppState, identity: Identity, query: ListQuery) -> Result<ApiOK<Page<RespInfo>>> {
+    let query = LIST.compile(&query)?;
+    let scope = scope::resolve(state, identity.id()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error resolve data scope");
+        ApiErr::ErrSystem(None)
+    })?;
+    let builder = scope.apply(
+        TEmployee::find_alive(),
+        t_employee::Column::DepartmentId,
+        Some(t_employee::Column::EmployeeId),
+    );
+
+    let page = query.fetch(&state.db, builder).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find t_employee");
+        ApiErr::ErrSystem(None)
+    })?;
+    let resp = page.map(|model| RespInfo {
+        employee_id: model.employee_id,
+        login_name: model.login_name,
+        realname: model.realname,
+        phone: model.phone,
+        email: model.email,
+        gender: model.gender,
+        disabled_flag: model.disabled_flag,
+        position_id: model.position_id,
+        department_id: model.department_id,
+        create_time: model.create_time,
+        create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+            .unwrap_or_default(),
+    });
+
+    Ok(ApiOK(Some(resp)))
+
+}
+
+/** 导出表头 */
+const EXPORT_HEADERS: &[&str] = &[
+    "员工ID", "登录名", "姓名", "性别", "手机号码", "邮箱", "部门", "职务", "状态", "创建时间",
+];
+
+/** 导出：查询条件同列表，`format` 为 csv 或 xlsx */
+pub async fn export(state: &AppState, identity: Identity, mut query: ListQuery) -> Result<Response> {
+    let format = export::Format::parse(query.take("format").as_deref())?;
+    let query = LIST.compile(&query)?;
+    let scope = scope::resolve(state, identity.id()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error resolve data scope");
+        ApiErr::ErrSystem(None)
+    })?;
+    let builder = scope.apply(
+        TEmployee::find_alive(),
+        t_employee::Column::DepartmentId,
+        Some(t_employee::Column::EmployeeId),
+    );
+
+    let total = query.total(&state.db, builder.clone()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error count t_employee");
+        ApiErr::ErrSystem(None)
+    })?;
+    export::check_rows(total, &state.config)?;
+
+    // 部门、职务名称
+    let departments: HashMap<i64, String> = TDepartment::find()
+        .select_only()
+        .column(t_department::Column::DepartmentId)
+        .column(t_department::Column::DepartmentName)
+        .into_tuple::<(i64, String)>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_department");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .collect();
+    let positions: HashMap<i64, String> = TPosition::find()
+        .select_only()
+        .column(t_position::Column::PositionId)
+        .column(t_position::Column::PositionName)
+        .into_tuple::<(i64, String)>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_position");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .collect();
+
+    let batches = query.batches(builder, t_employee::Column::EmployeeId, export::BATCH_SIZE);
+    let response = export::response(state.db.clone(), batches, format, "employees", EXPORT_HEADERS, move |model| {
+        vec![
+            model.employee_id.to_string(),
+            model.login_name,
+            model.realname,
+            gender_label(model.gender).to_string(),
+            model.phone,
+            model.email,
+            departments.get(&model.department_id).cloned().unwrap_or_default(),
+            positions.get(&model.position_id).cloned().unwrap_or_default(),
+            disabled_label(model.disabled_flag).to_string(),
+            xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8)).unwrap_or_default(),
+        ]
+    });
+
+    Ok(response)
+}
+
+/** 导入文件（multipart/form-data） */
+#[derive(Debug, ToSchema)]
+#[schema(as = employee::ReqImport)]
+pub struct ReqImport {
+    /// CSV 或 XLSX 文件，列同导出：登录名、姓名、手机号码、邮箱、部门为必填列，性别、职务、状态可省略
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    /// 是否仅校验，默认 true；为 false 时导入全部校验通过的行
+    pub dry_run: Option<bool>,
 }
 
+/** 导入结果 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = employee::RespImport)]
+pub struct RespImport {
+    pub dry_run: bool,
+    /// 数据行数
+    pub total: usize,
+    /// 校验通过的行数
+    pub valid: usize,
+    /// 已导入的行数
+    pub imported: usize,
+    pub rows: Vec<ImportRow>,
+}
+
+/** 每行的校验结果 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = employee::ImportRow)]
+pub struct ImportRow {
+    /// 表格行号（表头为第 1 行）
+    pub line: usize,
+    pub login_name: String,
+    pub realname: String,
+    /// 校验失败原因，为空表示校验通过
+    pub errors: Vec<String>,
+    /// 导入后的初始密码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/**
+ * 导入：部门、职务按名称匹配，登录名、手机号码与已有员工及文件中的其它行均不可重复；
+ * `dry_run` 为 true 时仅返回校验结果，否则在一个事务中导入全部校验通过的行
+ */
+pub async fn import(state: &AppState, data: Bytes, dry_run: bool) -> Result<ApiOK<RespImport>> {
+    // 解析 XLSX 为 CPU 密集操作，在阻塞线程池中执行
+    let max_rows = import::max_rows(&state.config);
+    let sheet = tokio::task::spawn_blocking(move || Sheet::read(&data, max_rows))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error join import task");
+            ApiErr::ErrSystem(None)
+        })??;
+    let login_name = sheet.require("登录名")?;
+    let realname = sheet.require("姓名")?;
+    let phone = sheet.require("手机号码")?;
+    let email = sheet.require("邮箱")?;
+    let department = sheet.require("部门")?;
+    let gender = sheet.column("性别");
+    let position = sheet.column("职务");
+    let disabled = sheet.column("状态");
+
+    let departments: HashMap<String, i64> = TDepartment::find_alive()
+        .select_only()
+        .column(t_department::Column::DepartmentName)
+        .column(t_department::Column::DepartmentId)
+        .into_tuple::<(String, i64)>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_department");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .collect();
+    let positions: HashMap<String, i64> = TPosition::find_alive()
+        .select_only()
+        .column(t_position::Column::PositionName)
+        .column(t_position::Column::PositionId)
+        .into_tuple::<(String, i64)>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_position");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .collect();
+
+    // 已存在的登录名、手机号码
+    let values = |column: usize| -> Vec<String> {
+        sheet.rows().iter().map(|row| row.get(Some(column)).to_string()).filter(|v| !v.is_empty()).collect()
+    };
+    let login_names: HashSet<String> = TEmployee::find()
+        .select_only()
+        .column(t_employee::Column::LoginName)
+        .filter(t_employee::Column::LoginName.is_in(values(login_name)))
+        .into_tuple::<String>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find login_name");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .collect();
+    let phones: HashSet<String> = TEmployee::find()
+        .select_only()
+        .column(t_employee::Column::Phone)
+        .filter(t_employee::Column::Phone.is_in(values(phone)))
+        .into_tuple::<String>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find phone");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .collect();
 
-pub async fn list(query: HashMap<String, String>) -> Result<ApiOK<RespList>> {
-    let mut builder = TEmployee::find();
-    if let Some(disabled_flag) = query.get("disabled_flag") {
-        if disabled_flag == "1" {
-            builder = builder.filter(t_employee::Column::DisabledFlag.eq(1));
-        } else {
-            builder = builder.filter(t_employee::Column::DisabledFlag.eq(0));
+    let mut rows = Vec::with_capacity(sheet.rows().len());
+    let mut reqs = Vec::new();
+    let mut seen_login_names: HashMap<&str, usize> = HashMap::new();
+    let mut seen_phones: HashMap<&str, usize> = HashMap::new();
+    for row in sheet.rows() {
+        let mut errors = Vec::new();
+        let mut req = ReqCreate {
+            realname: row.get(Some(realname)).to_string(),
+            phone: row.get(Some(phone)).to_string(),
+            department_id: 0,
+            login_name: row.get(Some(login_name)).to_string(),
+            email: row.get(Some(email)).to_string(),
+            gender: 0,
+            disabled_flag: 0,
+            position_id: 0,
+        };
+        if let Err(e) = req.validate() {
+            let fields = e.field_errors();
+            for field in ["login_name", "realname", "phone", "email"] {
+                for err in fields.get(field).into_iter().flat_map(|v| v.iter()) {
+                    errors.extend(err.message.as_ref().map(|v| v.to_string()));
+                }
+            }
         }
-    }
 
-    if let Some(login_name) = query.get("login_name") {
-        if !login_name.is_empty() {
-            builder = builder.filter(t_employee::Column::LoginName.contains(login_name));
+        if !req.login_name.is_empty() {
+            if login_names.contains(&req.login_name) {
+                errors.push("登录名已重复".to_string());
+            } else if let Some(line) = seen_login_names.insert(row.get(Some(login_name)), row.line) {
+                errors.push(format!("登录名与第 {} 行重复", line));
+            }
+        }
+        if !req.phone.is_empty() {
+            if phones.contains(&req.phone) {
+                errors.push("手机号码已重复".to_string());
+            } else if let Some(line) = seen_phones.insert(row.get(Some(phone)), row.line) {
+                errors.push(format!("手机号码与第 {} 行重复", line));
+            }
         }
-    }
 
-    if let Some(phone) = query.get("phone") {
-        if !phone.is_empty() {
-            builder = builder.filter(t_employee::Column::Phone.contains(phone));  
+        match row.get(Some(department)) {
+            "" => errors.push("部门必填".to_string()),
+            name => match departments.get(name) {
+                Some(id) => req.department_id = *id,
+                None => errors.push(format!("部门不存在：{}", name)),
+            },
+        }
+        match row.get(position) {
+            "" => {}
+            name => match positions.get(name) {
+                Some(id) => req.position_id = *id,
+                None => errors.push(format!("职务不存在：{}", name)),
+            },
+        }
+        match row.get(gender) {
+            "" | "未知" => {}
+            "男" => req.gender = 1,
+            "女" => req.gender = 2,
+            v => errors.push(format!("性别应为 男、女 或 未知：{}", v)),
+        }
+        match row.get(disabled) {
+            "" | "启用" => {}
+            "禁用" => req.disabled_flag = 1,
+            v => errors.push(format!("状态应为 启用 或 禁用：{}", v)),
         }
-    }
 
+        if errors.is_empty() {
+            reqs.push((rows.len(), req));
+        }
+        rows.push(ImportRow {
+            line: row.line,
+            login_name: row.get(Some(login_name)).to_string(),
+            realname: row.get(Some(realname)).to_string(),
+            errors,
+            password: None,
+        });
+    }
 
+    let mut resp = RespImport {
+        dry_run,
+        total: rows.len(),
+        valid: reqs.len(),
+        imported: 0,
+        rows,
+    };
+    if dry_run || reqs.is_empty() {
+        return Ok(ApiOK(Some(resp)));
+    }
 
-    let mut total: i64 = 0;
-    let (offset, limit) = util::query_page(&query);
-    // 仅在第一页计算数量
-    if offset == 0 {
-        total = builder
-            .clone()
-            .select_only()
-            .column_as(t_employee::Column::EmployeeId.count(), "count")
-            .into_tuple::<i64>()
-            .one(db::conn())
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "error count t_employee");
-                ApiErr::ErrSystem(None)
-            })?
-            .unwrap_or_default();
+    // 初始密码随机生成，首次登录需修改
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let passwords = service::password::one_time_batch(state, reqs.len()).await?;
+    let mut models = Vec::with_capacity(reqs.len());
+    for ((index, req), (plain, login_pwd)) in reqs.into_iter().zip(passwords) {
+        resp.rows[index].password = Some(plain);
+        let model = t_employee::ActiveModel {
+            realname: Set(req.realname),
+            phone: Set(req.phone),
+            department_id: Set(req.department_id),
+            login_name: Set(req.login_name),
+            login_pwd: Set(login_pwd.clone()),
+            email: Set(req.email),
+            gender: Set(req.gender),
+            disabled_flag: Set(req.disabled_flag),
+            position_id: Set(req.position_id),
+            must_change_password: Set(1),
+            create_time: Set(now),
+            ..Default::default()
+        };
+        models.push((model, login_pwd));
     }
 
-    let models = builder
-        .order_by(t_employee::Column::EmployeeId, Order::Desc)
-        .offset(offset)
-        .limit(limit)
-        .all(db::conn())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = ?e, "error find t_employee");
-            ApiErr::ErrSystem(None)
-        })?;
-    let mut resp = RespList {
-        total,
-        list: (Vec::with_capacity(models.len())),
-    };
-    for model in models {
-        let info = RespInfo {
-            employee_id: model.employee_id,
-            login_name: model.login_name,
-            realname: model.realname,
-            phone: model.phone,
-            email: model.email,
-            gender: model.gender,
-            disabled_flag: model.disabled_flag,
-            position_id: model.position_id,
-            department_id: model.department_id,
-            create_time: model.create_time,
-            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
-            .unwrap_or_default(),
+    // 任一行写入失败则全部不生效
+    let txn = state.db.begin().await.map_err(|e| {
+        tracing::error!(error = ?e, "error begin transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+    for (model, login_pwd) in models {
+        let ret = match TEmployee::insert(model).exec(&txn).await {
+            Ok(v) => v,
+            Err(e) => {
+                if let Some(SqlErr::UniqueConstraintViolation(_)) = e.sql_err() {
+                    return Err(ApiErr::ErrParams(Some("登录名已重复，请重新校验后导入".to_string())));
+                }
+                tracing::error!(error = ?e, "error insert t_employee");
+                return Err(ApiErr::ErrSystem(None));
+            }
         };
-        resp.list.push(info);
+        service::password::save_history(state, &txn, ret.last_insert_id, &login_pwd).await?;
     }
+    txn.commit().await.map_err(|e| {
+        tracing::error!(error = ?e, "error commit transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+    resp.imported = resp.valid;
 
     Ok(ApiOK(Some(resp)))
+}
 
+/** 性别：0 未知、1 男、2 女 */
+pub fn gender_label(gender: i16) -> &'static str {
+    match gender {
+        1 => "男",
+        2 => "女",
+        _ => "未知",
+    }
+}
+
+/** 禁用状态：0 启用、1 禁用 */
+pub fn disabled_label(disabled_flag: i16) -> &'static str {
+    match disabled_flag {
+        1 => "禁用",
+        _ => "启用",
+    }
 }
 
 
-pub async fn info(employee_id: i64) -> Result<ApiOK<RespInfo>> {
-    let model = TEmployee::find_by_id(employee_id)
-        .one(db::conn())
+// 查询未删除的员工
+async fn find_employee(state: &AppState, employee_id: i64) -> Result<t_employee::Model> {
+    TEmployee::find_alive()
+        .filter(t_employee::Column::EmployeeId.eq(employee_id))
+        .one(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_employee");
             ApiErr::ErrSystem(None)
         })?
-        .ok_or(ApiErr::ErrNotFound(Some("员工信息不存在".to_string())))?;
+        .ok_or(ApiErr::ErrNotFound(Some("员工信息不存在".to_string())))
+}
 
+pub async fn info(state: &AppState, employee_id: i64) -> Result<ApiOK<RespInfo>> {
+    let model = find_employee(state, employee_id).await?;
 
-    let mut resp = RespInfo {
+    let resp = RespInfo {
         employee_id: model.employee_id,
         login_name: model.login_name,
         realname: model.realname,
@@ -226,7 +574,8 @@ pub async fn info(employee_id: i64) -> Result<ApiOK<RespInfo>> {
     Ok(ApiOK(Some(resp)))
 }
 
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = employee::UpdateInfo)]
 pub struct UpdateInfo{
 
     pub employee_id:i64,
@@ -240,17 +589,20 @@ pub struct UpdateInfo{
     pub login_name: String,
     #[validate(length(min = 1, message = "邮箱必填"))]
     pub email: String,
-    pub gender:u8,
-    pub disabled_flag:u8,
+    pub gender:i16,
+    pub disabled_flag:i16,
     pub position_id:i64,
 
 }
 
-pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
+// 修改：由启用改为禁用时，已登录的会话立即失效
+pub async fn update(state: &AppState, req: UpdateInfo) -> Result<ApiOK<()>> {
+      let employee = find_employee(state, req.employee_id).await?;
       /* 判断登录名或者手机号是否重复*/
       let count = TEmployee::find()
       .filter(Condition::any().add(t_employee::Column::LoginName.eq(req.login_name.clone())).add(t_employee::Column::Phone.eq(req.phone.clone())))
-      .count(db::conn())
+      .filter(t_employee::Column::EmployeeId.ne(req.employee_id))
+      .count(&state.db)
       .await
       .map_err(|e| {
           tracing::error!(error = ?e, "error count t_employee");
@@ -261,7 +613,9 @@ pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
         return Err(ApiErr::ErrPerm(Some("登录名称或手机号码重复".to_string())));
     }
 
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+    let employee_id = req.employee_id;
+    let disabled = employee.disabled_flag == 0 && req.disabled_flag == 1;
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_employee::ActiveModel {
         employee_id: Set(req.employee_id),
         login_name: Set(req.login_name),
@@ -277,54 +631,91 @@ pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
     };
 
     if let Err(e) = TEmployee::update(model)
-            .exec(db::conn())
+            .exec(&state.db)
             .await{
                 tracing::error!(error = ?e, "error update t_employee");
                 return Err(ApiErr::ErrSystem(None));
             }
+
+    if disabled {
+        service::session::kick_employee(state, employee_id).await?;
+    }
             Ok(ApiOK(None))
 }
 
-// 禁用
-pub async fn disabled_flag(employee_id: i64, disabled_flag:u8) -> Result<ApiOK<()>> {
-    let _update_model = TEmployee::update_many()
-        .col_expr(t_employee::Column::DeletedFlag, Expr::value(disabled_flag))
+// 禁用、启用：禁用后已登录的会话立即失效
+pub async fn disabled_flag(state: &AppState, employee_id: i64, disabled_flag:i16) -> Result<ApiOK<()>> {
+    if disabled_flag != 0 && disabled_flag != 1 {
+        return Err(ApiErr::ErrParams(Some("状态值错误".to_string())));
+    }
+    find_employee(state, employee_id).await?;
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    if let Err(e) = TEmployee::update_many()
+        .col_expr(t_employee::Column::DisabledFlag, Expr::value(disabled_flag))
+        .col_expr(t_employee::Column::UpdateTime, Expr::value(now))
         .filter(t_employee::Column::EmployeeId.eq(employee_id))
-        .exec(db::conn())
-        .await;   
-    
+        .exec(&state.db)
+        .await
+    {
+        tracing::error!(error = ?e, "error update t_employee");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    if disabled_flag == 1 {
+        service::session::kick_employee(state, employee_id).await?;
+    }
     Ok(ApiOK(None))
 }
 
-// 重置密码
-pub async fn reset_password(employee_id: i64) -> Result<ApiOK<()>> {
-        let _update_model = TEmployee::update_many()
-            .col_expr(t_employee::Column::LoginPwd, Expr::value(md5("123456".as_bytes()).to_string()))
-            .filter(t_employee::Column::EmployeeId.eq(employee_id))
-            .exec(db::conn())
-            .await;     
+// 删除（逻辑删除，可在回收站中恢复）：不能删除自己及超级管理员，已登录的会话立即失效
+pub async fn delete(state: &AppState, identity: Identity, employee_id: i64) -> Result<ApiOK<()>> {
+    if identity.id() == employee_id {
+        return Err(ApiErr::ErrPerm(Some("不能删除当前登录账号".to_string())));
+    }
+    let model = find_employee(state, employee_id).await?;
+    if model.administrator_flag == 1 {
+        return Err(ApiErr::ErrPerm(Some("不能删除超级管理员".to_string())));
+    }
 
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    TEmployee::soft_delete(&state.db, vec![employee_id], now)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error delete t_employee");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    service::session::kick_employee(state, employee_id).await?;
+    perm::invalidate(state, &[employee_id]);
     Ok(ApiOK(None))
 }
 
+// 重置密码：生成一次性密码，员工登录后需修改
+pub async fn reset_password(state: &AppState, employee_id: i64) -> Result<ApiOK<RespPassword>> {
+    service::password::reset(state, employee_id).await
+}
+
 // 调整部门
-pub async fn change_department(employee_id: Vec<i64>, department_id:i64) -> Result<ApiOK<()>> {
+pub async fn change_department(state: &AppState, employee_id: Vec<i64>, department_id:i64) -> Result<ApiOK<()>> {
          let _update_model = TEmployee::update_many()
                 .col_expr(t_employee::Column::DepartmentId, Expr::value(department_id))
                 .filter(t_employee::Column::EmployeeId.is_in(employee_id))
-                .exec(db::conn())
+                .exec(&state.db)
                 .await;    
         Ok(ApiOK(None))
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = employee::RespEmpInfo)]
 pub struct RespEmpInfo {
     pub employee_id: i64,
     pub realname: String,
     pub department_id: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = employee::RespDeptInfo)]
 pub struct RespDeptInfo {
     pub department_id: i64,
     pub department_name: String,
@@ -332,7 +723,8 @@ pub struct RespDeptInfo {
 
 
 // 临时存储人员下拉数据结构
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = employee::RespSelectOption)]
 pub struct RespSelectOption {
     pub employee_id: i64,
     pub realname: String,
@@ -341,40 +733,41 @@ pub struct RespSelectOption {
 }
 
 //人员下拉框
-pub async fn employee_select_list() -> Result<ApiOK<Vec<RespSelectOption>>> {
+pub async fn employee_select_list(state: &AppState) -> Result<ApiOK<Vec<RespSelectOption>>> {
     
     //查询所有未删除的员工，并取出员工id，姓名，部门id
-    let employee_models = TEmployee::find()
+    let employee_models = TEmployee::find_alive()
         .select_only()
         .column(t_employee::Column::EmployeeId)
         .column(t_employee::Column::Realname)
         .column(t_employee::Column::DepartmentId)
-        .filter(t_employee::Column::DeletedFlag.eq(0))
-        .all(db::conn())
+        .into_tuple::<(i64, String, i64)>()
+        .all(&state.db)
         .await
         .map_err(|e| {
-            tracing::error!(error = ?e, "error find t_role");
+            tracing::error!(error = ?e, "error find t_employee");
             ApiErr::ErrSystem(None)
         })?;
 
     // 将查询出来的数据封装到临时结构体RespEmpInfo中
     let mut emp_list = Vec::with_capacity(employee_models.len());
-    for emp in employee_models {
+    for (employee_id, realname, department_id) in employee_models {
         emp_list.push(RespEmpInfo {
-            employee_id: emp.employee_id,
-            realname: emp.realname,
-            department_id: emp.department_id,
+            employee_id,
+            realname,
+            department_id,
         });
     }
 
     
 
     // 查询部门表，并取出部门id，名称
-    let department_models = TDepartment::find()
+    let department_models = TDepartment::find_alive()
         .select_only()
         .column(t_department::Column::DepartmentId)
         .column(t_department::Column::DepartmentName)
-        .all(db::conn())
+        .into_tuple::<(i64, String)>()
+        .all(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_department");
@@ -383,10 +776,10 @@ pub async fn employee_select_list() -> Result<ApiOK<Vec<RespSelectOption>>> {
     
     // 将查询出来的数据封装到临时结构体RespDeptInfo中
     let mut dept_list = Vec::with_capacity(department_models.len());
-    for dept in department_models {
+    for (department_id, department_name) in department_models {
         dept_list.push(RespDeptInfo {
-            department_id: dept.department_id,
-            department_name: dept.department_name,
+            department_id,
+            department_name,
         });
     }
 
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use sea_orm::EntityTrait;
+
+use pkg::{
+    cache,
+    result::response::{ApiErr, ApiOK, Result},
+    state::AppState,
+};
+
+use crate::ent::prelude::TEmployee;
+
+/** 登录失败限制策略 */
+struct Policy {
+    // 账号连续失败次数上限
+    max_attempts: i64,
+    // 同一IP失败次数上限
+    ip_max_attempts: i64,
+    // 失败次数统计窗口
+    window: Duration,
+    // 锁定时长
+    lock_time: Duration,
+}
+
+fn policy(state: &AppState) -> Policy {
+    let cfg = &state.config;
+    Policy {
+        max_attempts: cfg.get_int("login.max_attempts").unwrap_or(5),
+        ip_max_attempts: cfg.get_int("login.ip_max_attempts").unwrap_or(20),
+        window: Duration::from_secs(cfg.get_int("login.window").unwrap_or(900) as u64),
+        lock_time: Duration::from_secs(cfg.get_int("login.lock_time").unwrap_or(900) as u64),
+    }
+}
+
+/**
+ * 登录前检查账号、IP是否被锁定
+ */
+pub async fn check(state: &AppState, login_name: &str, ip: &str) -> Result<()> {
+    let mut keys = vec![lock_key("account", &login_name.to_lowercase())];
+    if !ip.is_empty() {
+        keys.push(lock_key("ip", ip));
+    }
+    for key in keys {
+        let ttl = state.cache.ttl(&key).await.map_err(|e| {
+            tracing::error!(error = ?e, "error get login lock");
+            ApiErr::ErrSystem(None)
+        })?;
+        if let Some(v) = ttl {
+            let minutes = v.as_secs().div_ceil(60).max(1);
+            return Err(ApiErr::ErrAuth(Some(format!(
+                "登录失败次数过多，请{}分钟后再试",
+                minutes
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * 记录一次登录失败，达到上限时锁定账号或IP
+ */
+pub async fn fail(state: &AppState, login_name: &str, ip: &str) -> Result<()> {
+    let policy = policy(state);
+    let mut targets = vec![("account", login_name.to_lowercase(), policy.max_attempts)];
+    if !ip.is_empty() {
+        targets.push(("ip", ip.to_string(), policy.ip_max_attempts));
+    }
+    for (kind, value, max_attempts) in targets {
+        if max_attempts <= 0 {
+            continue;
+        }
+        let count = state.cache
+            .incr(&fail_key(kind, &value), policy.window)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "error incr login failures");
+                ApiErr::ErrSystem(None)
+            })?;
+        if count >= max_attempts {
+            tracing::warn!(kind, value, count, "login locked");
+            let store = &state.cache;
+            let ret = store
+                .set(&lock_key(kind, &value), "1", policy.lock_time)
+                .await;
+            let ret = match ret {
+                Ok(_) => store.del(&fail_key(kind, &value)).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = ret {
+                tracing::error!(error = ?e, "error set login lock");
+                return Err(ApiErr::ErrSystem(None));
+            }
+        }
+    }
+    Ok(())
+}
+
+/**
+ * 账号、IP当前的失败次数（取较大值）
+ */
+pub async fn failures(state: &AppState, login_name: &str, ip: &str) -> Result<i64> {
+    let mut keys = vec![fail_key("account", &login_name.to_lowercase())];
+    if !ip.is_empty() {
+        keys.push(fail_key("ip", ip));
+    }
+    let mut count = 0;
+    for key in keys {
+        let v = state.cache.get(&key).await.map_err(|e| {
+            tracing::error!(error = ?e, "error get login failures");
+            ApiErr::ErrSystem(None)
+        })?;
+        count = count.max(v.and_then(|v| v.parse::<i64>().ok()).unwrap_or_default());
+    }
+    Ok(count)
+}
+
+/**
+ * 登录成功后清除账号的失败次数
+ */
+pub async fn reset(state: &AppState, login_name: &str) {
+    let key = fail_key("account", &login_name.to_lowercase());
+    if let Err(e) = state.cache.del(&key).await {
+        tracing::error!(error = ?e, "error reset login failures");
+    }
+}
+
+/** 解除员工账号的登录锁定 */
+pub async fn unlock(state: &AppState, employee_id: i64) -> Result<ApiOK<()>> {
+    let model = TEmployee::find_by_id(employee_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("员工信息不存在".to_string())))?;
+
+    let login_name = model.login_name.to_lowercase();
+    for key in [lock_key("account", &login_name), fail_key("account", &login_name)] {
+        if let Err(e) = state.cache.del(&key).await {
+            tracing::error!(error = ?e, "error unlock login");
+            return Err(ApiErr::ErrSystem(None));
+        }
+    }
+
+    Ok(ApiOK(None))
+}
+
+fn fail_key(kind: &str, value: &str) -> String {
+    format!("login:fail:{}:{}", kind, value)
+}
+
+// 锁定状态不能因缓存数量达到上限而写入失败
+fn lock_key(kind: &str, value: &str) -> String {
+    format!("{}login:lock:{}:{}", cache::PINNED_PREFIX, kind, value)
+}
@@ -1,122 +1,545 @@
+use std::time::Duration;
+
 use sea_orm::sea_query::Expr;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Set};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use time::macros::offset;
 use validator::Validate;
 
-use pkg::crypto::hash::md5;
-use pkg::identity::Identity;
+use pkg::crypto::hash::{md5, sha256};
+use pkg::crypto::password::{self, Verified};
+use pkg::identity::{self, Identity};
 use pkg::result::response::{ApiErr, ApiOK, Result};
-use pkg::{db, util, xtime};
+use pkg::soft_delete::SoftDelete;
+use pkg::util::{self, ClientInfo};
+use pkg::state::AppState;
 
+use crate::api::service::{captcha, lockout, session, totp};
 use crate::ent::t_employee;
 use crate::ent::prelude::TEmployee;
 
 use crate::ent::t_role_employee;
-use crate::ent::prelude::TRoleEmployee;
+use crate::ent::prelude::{TRole, TRoleEmployee};
+
+use crate::ent::t_refresh_token;
+use crate::ent::prelude::TRefreshToken;
 
 /** 封装输入参数 */
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = login::ReqLogin)]
 pub struct ReqLogin {
     #[validate(length(min = 1, message = "用户名必填"))]
     pub username: String,
     #[validate(length(min = 1, message = "密码必填"))]
     pub password: String,
+    // 登录设备名称，未传时根据 User-Agent 识别
+    #[serde(default)]
+    pub device: Option<String>,
+    // 图形验证码，按配置在需要时必填
+    #[serde(default)]
+    pub captcha_id: Option<String>,
+    #[serde(default)]
+    pub captcha_answer: Option<String>,
 }
 
 /** 封装返回参数 */
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[schema(as = login::RespLogin)]
 pub struct RespLogin {
     pub name: String,
     pub role: i64,
     pub auth_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+    // 是否需先修改密码（首次登录或密码被重置）
+    pub must_change_password: bool,
+    // 需要两步验证时返回，此时不签发令牌
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<RespChallenge>,
+    // 登录时完成两步验证绑定后返回，仅显示一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+/** 两步验证挑战 */
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[schema(as = login::RespChallenge)]
+pub struct RespChallenge {
+    pub challenge_token: String,
+    // 是否需要先绑定认证器（所属角色要求两步验证但尚未开启）
+    pub setup: bool,
+    pub expires_in: i64,
 }
 
+/** 两步验证挑战缓存数据 */
+#[derive(Debug, Deserialize, Serialize)]
+struct Challenge {
+    employee_id: i64,
+    login_name: String,
+    role_id: i64,
+    device: Option<String>,
+    setup: bool,
+    // 绑定认证器时生成的密钥
+    secret: Option<String>,
+}
+
+// 两步验证挑战有效期（秒）
+const CHALLENGE_TTL: u64 = 300;
+// 两步验证挑战允许的失败次数
+const CHALLENGE_MAX_ATTEMPTS: i64 = 5;
+
 /**
  * 登录接口
  */
-pub async fn login(req: ReqLogin) -> Result<ApiOK<RespLogin>> {
+pub async fn login(state: &AppState, req: ReqLogin, client: ClientInfo) -> Result<ApiOK<RespLogin>> {
+    /* 失败次数过多时拒绝登录 */
+    lockout::check(state, &req.username, &client.ip).await?;
+    /* 图形验证码 */
+    captcha::check(
+        state,
+        &req.username,
+        &client.ip,
+        req.captcha_id.as_deref(),
+        req.captcha_answer.as_deref(),
+    )
+    .await?;
+
     /* 根据用户名查询sys_user表，返回用户对象 */
-    let  model = TEmployee::find()
-        .filter(t_employee::Column::LoginName.eq(req.username))
-        .one(db::conn())
+    let ret = TEmployee::find_alive()
+        .filter(t_employee::Column::LoginName.eq(req.username.as_str()))
+        .one(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_employee");
             ApiErr::ErrSystem(None)
-        })?
-        .ok_or(ApiErr::ErrAuth(Some("账号不存在".to_string())))?;
-
-        /* 根据用户ID查询 sys_user_role表，返回用户角色关系表对象 */
-        let t_role_employee  = TRoleEmployee::find()
-            .filter(t_role_employee::Column::EmployeeId.eq(model.employee_id))
-            .one(db::conn())
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "error find sys_user_role");
-                ApiErr::ErrSystem(None)
-            })?
-            .ok_or(ApiErr::ErrAuth(Some("账号角色关系不存在".to_string())))?;
-
-        let pass = format!("{}", req.password);
-        if md5(pass.as_bytes()) != model.login_pwd {
-            return Err(ApiErr::ErrAuth(Some("密码错误".to_string())));
+        })?;
+
+    // 账号不存在时同样校验一次密码，避免通过响应时间判断账号是否存在
+    let hashed = match &ret {
+        Some(v) => v.login_pwd.as_str(),
+        None => &state.dummy_hash,
+    };
+    let verified = password::verify_with(state.hasher.as_ref(), &req.password, hashed).map_err(|e| {
+        tracing::error!(error = ?e, "error verify password");
+        ApiErr::ErrSystem(None)
+    })?;
+    let model = match ret {
+        Some(v) if verified.is_match() => v,
+        _ => {
+            lockout::fail(state, &req.username, &client.ip).await?;
+            return Err(ApiErr::ErrAuth(Some("账号或密码错误".to_string())));
         }
+    };
+    if model.disabled_flag == 1 {
+        return Err(ApiErr::ErrAuth(Some("账号已被禁用".to_string())));
+    }
+
+    // 旧格式密码（如MD5）登录成功后升级为当前哈希算法
+    if verified == Verified::Rehash {
+        rehash_password(state, model.employee_id, &req.password).await;
+    }
 
-        let now = xtime::now(offset!(+8)).unix_timestamp();
-        //自定义token
-        let login_token = md5(format!("auth.{}.{}.{}", model.employee_id, now, util::nonce(16)).as_bytes());
-        // 加密token
-        let auth_token = Identity::new(model.employee_id, login_token.clone())
-        .to_auth_token()
+    /* 根据用户ID查询 sys_user_role表，返回用户角色关系表对象 */
+    let t_role_employee = TRoleEmployee::find()
+        .filter(t_role_employee::Column::EmployeeId.eq(model.employee_id))
+        .filter(t_role_employee::Column::RoleId.in_subquery(TRole::alive_ids()))
+        .one(&state.db)
+        .await
         .map_err(|e| {
-            tracing::error!(error = ?e, "error identity encrypt");
+            tracing::error!(error = ?e, "error find sys_user_role");
             ApiErr::ErrSystem(None)
-        })?;
+        })?
+        .ok_or(ApiErr::ErrAuth(Some("账号角色关系不存在".to_string())))?;
 
-        // 封装修改model
-        let update_model = t_employee::ActiveModel {
-            login_at: Set(now),
-            login_token: Set(login_token),
-            update_time: Set(now),
-            ..Default::default()
+    /* 两步验证：已开启或所属角色要求开启时，先返回挑战令牌 */
+    let enabled = totp::find(state, model.employee_id)
+        .await?
+        .is_some_and(|v| v.enabled_flag == 1);
+    if enabled || totp::required(state, model.employee_id).await? {
+        let challenge = Challenge {
+            employee_id: model.employee_id,
+            login_name: model.login_name,
+            role_id: t_role_employee.role_id,
+            device: req.device,
+            setup: !enabled,
+            secret: None,
         };
-        // 更新T_employee表数据
-        let ret_update = TEmployee::update_many()
-            .filter(t_employee::Column::EmployeeId.eq(model.employee_id))
-            .set(update_model)
-            .exec(db::conn())
-            .await;
-        if let Err(e) = ret_update {
-            tracing::error!(error = ?e, "error update t_employee");
-            return Err(ApiErr::ErrSystem(None));
-        }
-    
+        let challenge_token = util::nonce(32);
+        save_challenge(state, &challenge_token, &challenge).await?;
+
         let resp = RespLogin {
             name: model.realname,
             role: t_role_employee.role_id,
-            auth_token,
+            auth_token: String::new(),
+            refresh_token: String::new(),
+            expires_in: 0,
+            must_change_password: false,
+            challenge: Some(RespChallenge {
+                challenge_token,
+                setup: challenge.setup,
+                expires_in: CHALLENGE_TTL as i64,
+            }),
+            recovery_codes: None,
         };
-    
-        Ok(ApiOK(Some(resp)))
+        return Ok(ApiOK(Some(resp)));
+    }
+
+    // 需要两步验证时，验证通过后才清除失败次数
+    lockout::reset(state, &req.username).await;
+    let resp = sign_in(state, model, t_role_employee.role_id, req.device, &client).await?;
+
+    Ok(ApiOK(Some(resp)))
 }
 
-/**退出接口 */
-pub async fn logout(identity: Identity) -> Result<ApiOK<()>> {
-    let ret: std::result::Result<_, _> = TEmployee::update_many()
-        .filter(t_employee::Column::EmployeeId.eq(identity.id()))
-        .col_expr(t_employee::Column::LoginToken, Expr::value(""))
-        .col_expr(
-            t_employee::Column::CreateTime,
-            Expr::value(xtime::now(offset!(+8)).unix_timestamp()),
-        )
-        .exec(db::conn())
+/** 两步验证输入参数 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = login::ReqLoginTotp)]
+pub struct ReqLoginTotp {
+    #[validate(length(min = 1, message = "挑战令牌必填"))]
+    pub challenge_token: String,
+    #[validate(length(min = 1, message = "验证码必填"))]
+    pub code: String,
+}
+
+/**
+ * 两步验证登录：校验动态验证码或恢复码后签发令牌；
+ * 需要绑定认证器时，校验通过即启用两步验证并返回恢复码。
+ * 验证失败计入账号的登录失败次数
+ */
+pub async fn login_totp(state: &AppState, req: ReqLoginTotp, client: ClientInfo) -> Result<ApiOK<RespLogin>> {
+    let challenge = find_challenge(state, &req.challenge_token).await?;
+    lockout::check(state, &challenge.login_name, &client.ip).await?;
+
+    let recovery_codes = if challenge.setup {
+        let secret = challenge
+            .secret
+            .as_deref()
+            .ok_or(ApiErr::ErrParams(Some("请先获取两步验证密钥".to_string())))?;
+        match totp::enable_with(state, challenge.employee_id, secret, &req.code).await {
+            Ok(v) => Some(v),
+            Err(ApiErr::ErrParams(_)) => {
+                return Err(challenge_failed(state, &req.challenge_token, &challenge, &client).await)
+            }
+            Err(e) => return Err(e),
+        }
+    } else {
+        let model = totp::find(state, challenge.employee_id)
+            .await?
+            .filter(|v| v.enabled_flag == 1)
+            .ok_or(ApiErr::ErrAuth(Some("两步验证未开启，请重新登录".to_string())))?;
+        if !totp::check_code(state, &model, &req.code).await? {
+            return Err(challenge_failed(state, &req.challenge_token, &challenge, &client).await);
+        }
+        None
+    };
+    remove_challenge(state, &req.challenge_token).await;
+    lockout::reset(state, &challenge.login_name).await;
+
+    let model = TEmployee::find_alive()
+        .filter(t_employee::Column::EmployeeId.eq(challenge.employee_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrAuth(Some("账号或密码错误".to_string())))?;
+    // 两步验证期间账号可能已被禁用或删除，签发令牌前重新校验
+    if model.disabled_flag == 1 {
+        return Err(ApiErr::ErrAuth(Some("账号已被禁用".to_string())));
+    }
+
+    let mut resp = sign_in(state, model, challenge.role_id, challenge.device, &client).await?;
+    resp.recovery_codes = recovery_codes;
+
+    Ok(ApiOK(Some(resp)))
+}
+
+/** 挑战令牌输入参数 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = login::ReqChallenge)]
+pub struct ReqChallenge {
+    #[validate(length(min = 1, message = "挑战令牌必填"))]
+    pub challenge_token: String,
+}
+
+/**
+ * 登录时绑定认证器：生成密钥，随后通过两步验证登录接口校验并启用
+ */
+pub async fn login_totp_setup(state: &AppState, req: ReqChallenge) -> Result<ApiOK<totp::RespSetup>> {
+    let mut challenge = find_challenge(state, &req.challenge_token).await?;
+    if !challenge.setup {
+        return Err(ApiErr::ErrParams(Some("两步验证已开启".to_string())));
+    }
+
+    let secret = match &challenge.secret {
+        Some(v) => v.clone(),
+        None => {
+            let v = pkg::crypto::totp::generate_secret();
+            challenge.secret = Some(v.clone());
+            save_challenge(state, &req.challenge_token, &challenge).await?;
+            v
+        }
+    };
+
+    Ok(ApiOK(Some(totp::provisioning(state, &challenge.login_name, &secret))))
+}
+
+// 签发令牌并创建登录会话
+async fn sign_in(
+    state: &AppState,
+    model: t_employee::Model,
+    role_id: i64,
+    device: Option<String>,
+    client: &ClientInfo,
+) -> Result<RespLogin> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    //自定义token
+    let login_token = md5(format!("auth.{}.{}.{}", model.employee_id, now, util::nonce(16)).as_bytes());
+    // 加密token
+    let auth_token = Identity::new(model.employee_id, login_token.clone())
+        .to_auth_token(&state.config)
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error identity encrypt");
+            ApiErr::ErrSystem(None)
+        })?;
+    // 登录会话
+    session::create(state, model.employee_id, &login_token, device, client).await?;
+    // 刷新token
+    let refresh_token = issue_refresh_token(state, model.employee_id, &login_token, 0).await?;
+
+    // 更新T_employee表数据
+    let ret_update = TEmployee::update_many()
+        .col_expr(t_employee::Column::LoginAt, Expr::value(now))
+        .col_expr(t_employee::Column::LoginToken, Expr::value(login_token))
+        .col_expr(t_employee::Column::UpdateTime, Expr::value(now))
+        .filter(t_employee::Column::EmployeeId.eq(model.employee_id))
+        .exec(&state.db)
         .await;
+    if let Err(e) = ret_update {
+        tracing::error!(error = ?e, "error update t_employee");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    Ok(RespLogin {
+        name: model.realname,
+        role: role_id,
+        auth_token,
+        refresh_token,
+        expires_in: identity::access_ttl(&state.config),
+        must_change_password: model.must_change_password == 1,
+        challenge: None,
+        recovery_codes: None,
+    })
+}
 
+async fn rehash_password(state: &AppState, employee_id: i64, pwd: &str) {
+    let hashed = match state.hasher.hash(pwd) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = ?e, "error rehash password");
+            return;
+        }
+    };
+    let ret = TEmployee::update_many()
+        .col_expr(t_employee::Column::LoginPwd, Expr::value(hashed))
+        .filter(t_employee::Column::EmployeeId.eq(employee_id))
+        .exec(&state.db)
+        .await;
     if let Err(e) = ret {
         tracing::error!(error = ?e, "error update t_employee");
-        return Err(ApiErr::ErrSystem(None));
     }
+}
+
+async fn save_challenge(state: &AppState, token: &str, challenge: &Challenge) -> Result<()> {
+    let value = serde_json::to_string(challenge).map_err(|e| {
+        tracing::error!(error = ?e, "error encode login challenge");
+        ApiErr::ErrSystem(None)
+    })?;
+    state.cache
+        .set(&challenge_key(token), &value, Duration::from_secs(CHALLENGE_TTL))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error save login challenge");
+            ApiErr::ErrSystem(None)
+        })
+}
+
+async fn find_challenge(state: &AppState, token: &str) -> Result<Challenge> {
+    let value = state.cache
+        .get(&challenge_key(token))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find login challenge");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrAuth(Some("验证已过期，请重新登录".to_string())))?;
+    serde_json::from_str(&value).map_err(|e| {
+        tracing::error!(error = ?e, "error decode login challenge");
+        ApiErr::ErrSystem(None)
+    })
+}
+
+async fn remove_challenge(state: &AppState, token: &str) {
+    for key in [challenge_key(token), format!("{}:fail", challenge_key(token))] {
+        if let Err(e) = state.cache.del(&key).await {
+            tracing::error!(error = ?e, "error remove login challenge");
+        }
+    }
+}
+
+// 记录验证失败（同时计入账号、IP的登录失败次数），超过次数后挑战作废
+async fn challenge_failed(state: &AppState, token: &str, challenge: &Challenge, client: &ClientInfo) -> ApiErr {
+    if let Err(e) = lockout::fail(state, &challenge.login_name, &client.ip).await {
+        return e;
+    }
+    let key = format!("{}:fail", challenge_key(token));
+    let count = match state.cache
+        .incr(&key, Duration::from_secs(CHALLENGE_TTL))
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!(error = ?e, "error incr login challenge failures");
+            return ApiErr::ErrSystem(None);
+        }
+    };
+    if count >= CHALLENGE_MAX_ATTEMPTS {
+        remove_challenge(state, token).await;
+        return ApiErr::ErrAuth(Some("验证失败次数过多，请重新登录".to_string()));
+    }
+    ApiErr::ErrParams(Some("验证码错误".to_string()))
+}
+
+fn challenge_key(token: &str) -> String {
+    format!("login:challenge:{}", token)
+}
+
+/**退出接口 */
+pub async fn logout(state: &AppState, identity: Identity) -> Result<ApiOK<()>> {
+    session::revoke(state, vec![identity.token().to_string()]).await?;
 
     Ok(ApiOK(None))
-}
\ No newline at end of file
+}
+
+/** 刷新令牌输入参数 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = login::ReqRefresh)]
+pub struct ReqRefresh {
+    #[validate(length(min = 1, message = "刷新令牌必填"))]
+    pub refresh_token: String,
+}
+
+/** 刷新令牌返回参数 */
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[schema(as = login::RespRefresh)]
+pub struct RespRefresh {
+    pub auth_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+}
+
+/**
+ * 刷新令牌接口：轮换刷新令牌并签发新的访问令牌；
+ * 已使用或已撤销的刷新令牌再次出现时，视为泄露，撤销整个登录会话
+ */
+pub async fn refresh(state: &AppState, req: ReqRefresh) -> Result<ApiOK<RespRefresh>> {
+    let model = TRefreshToken::find()
+        .filter(t_refresh_token::Column::TokenHash.eq(sha256(req.refresh_token.as_bytes())))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_refresh_token");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrAuth(Some("刷新令牌无效".to_string())))?;
+
+    if model.used_flag == 1 || model.revoked_flag == 1 {
+        tracing::warn!(employee_id = model.employee_id, "refresh token reused");
+        session::revoke(state, vec![model.login_token]).await?;
+        return Err(ApiErr::ErrAuth(Some("刷新令牌已失效，请重新登录".to_string())));
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    if model.expire_time <= now {
+        return Err(ApiErr::ErrAuth(Some("刷新令牌已过期，请重新登录".to_string())));
+    }
+
+    // 登录会话需仍然有效（未退出、未被踢出）
+    let login_session = match session::find(state, &model.login_token).await? {
+        Some(v) if v.employee_id == model.employee_id => v,
+        _ => return Err(ApiErr::ErrAuth(Some("登录已失效，请重新登录".to_string()))),
+    };
+
+    // 员工需未删除、未禁用，否则一并撤销登录会话
+    let employee = TEmployee::find_alive()
+        .filter(t_employee::Column::EmployeeId.eq(model.employee_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?;
+    if employee.is_none_or(|v| v.disabled_flag != 0) {
+        session::revoke(state, vec![model.login_token]).await?;
+        return Err(ApiErr::ErrAuth(Some("账号不存在或已被禁用".to_string())));
+    }
+
+    // 标记为已使用，并发刷新时仅有一个请求成功
+    let ret = TRefreshToken::update_many()
+        .col_expr(t_refresh_token::Column::UsedFlag, Expr::value(1))
+        .col_expr(t_refresh_token::Column::UpdateTime, Expr::value(now))
+        .filter(t_refresh_token::Column::Id.eq(model.id))
+        .filter(t_refresh_token::Column::UsedFlag.eq(0))
+        .exec(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error update t_refresh_token");
+            ApiErr::ErrSystem(None)
+        })?;
+    if ret.rows_affected == 0 {
+        session::revoke(state, vec![model.login_token]).await?;
+        return Err(ApiErr::ErrAuth(Some("刷新令牌已失效，请重新登录".to_string())));
+    }
+
+    let auth_token = Identity::new(model.employee_id, model.login_token.clone())
+        .to_auth_token(&state.config)
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error identity encrypt");
+            ApiErr::ErrSystem(None)
+        })?;
+    let refresh_token = issue_refresh_token(state, model.employee_id, &model.login_token, model.id).await?;
+    session::renew(state, login_session.session_id).await?;
+
+    let resp = RespRefresh {
+        auth_token,
+        refresh_token,
+        expires_in: identity::access_ttl(&state.config),
+    };
+
+    Ok(ApiOK(Some(resp)))
+}
+
+// 签发刷新令牌，仅保存其哈希值
+async fn issue_refresh_token(state: &AppState, employee_id: i64, login_token: &str, parent_id: i64) -> Result<String> {
+    let token = util::nonce(64);
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = t_refresh_token::ActiveModel {
+        employee_id: Set(employee_id),
+        login_token: Set(login_token.to_string()),
+        token_hash: Set(sha256(token.as_bytes())),
+        parent_id: Set(parent_id),
+        used_flag: Set(0),
+        revoked_flag: Set(0),
+        expire_time: Set(now + identity::refresh_ttl(&state.config)),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    };
+    if let Err(e) = TRefreshToken::insert(model).exec(&state.db).await {
+        tracing::error!(error = ?e, "error insert t_refresh_token");
+        return Err(ApiErr::ErrSystem(None));
+    }
+    Ok(token)
+}
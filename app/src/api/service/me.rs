@@ -0,0 +1,240 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use sea_orm::{ColumnTrait, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use pkg::{
+    identity::Identity,
+    result::response::{ApiErr, ApiOK, Result},
+    soft_delete::SoftDelete,
+    state::AppState,
+    tree,
+};
+
+use crate::api::service::menu::{MENU_TYPE_CATALOG, MENU_TYPE_MENU};
+use crate::ent::{
+    prelude::{TDepartment, TEmployee, TMenu, TPosition, TRole, TRoleEmployee, TRoleMenu},
+    t_menu, t_role, t_role_employee, t_role_menu,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = me::RespRole)]
+pub struct RespRole {
+    pub role_id: i64,
+    pub role_name: String,
+    pub role_code: String,
+}
+
+/** 前端路由 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = me::RespRoute)]
+pub struct RespRoute {
+    pub menu_id: i64,
+    pub menu_name: String,
+    pub menu_type: i32,
+    pub parent_id: i64,
+    pub sort: Option<i32>,
+    pub path: Option<String>,
+    pub component: Option<String>,
+    pub icon: Option<String>,
+    pub visible_flag: i16,
+    pub cache_flag: i16,
+    pub frame_flag: i16,
+    pub frame_url: Option<String>,
+}
+
+/** 当前登录用户信息 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = me::RespMe)]
+pub struct RespMe {
+    pub employee_id: i64,
+    pub login_name: String,
+    pub realname: String,
+    pub avatar: String,
+    pub gender: i16,
+    pub phone: String,
+    pub email: String,
+    pub department_id: i64,
+    pub department_name: String,
+    pub position_id: i64,
+    pub position_name: String,
+    pub administrator_flag: i16,
+    pub must_change_password: i16,
+    pub roles: Vec<RespRole>,
+    pub menus: Vec<tree::Node<RespRoute>>,
+    pub perms: Vec<String>,
+}
+
+/** 当前登录用户的资料、角色、菜单及前端权限 */
+pub async fn info(state: &AppState, identity: Identity) -> Result<ApiOK<RespMe>> {
+    let employee = TEmployee::find_by_id(identity.id())
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("员工信息不存在".to_string())))?;
+
+    let department_name = TDepartment::find_by_id(employee.department_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_department");
+            ApiErr::ErrSystem(None)
+        })?
+        .map(|v| v.department_name)
+        .unwrap_or_default();
+
+    let position_name = TPosition::find_by_id(employee.position_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_position");
+            ApiErr::ErrSystem(None)
+        })?
+        .map(|v| v.position_name)
+        .unwrap_or_default();
+
+    /* 角色 */
+    let role_ids = TRoleEmployee::find()
+        .select_only()
+        .column(t_role_employee::Column::RoleId)
+        .filter(t_role_employee::Column::EmployeeId.eq(employee.employee_id))
+        .filter(t_role_employee::Column::RoleId.in_subquery(TRole::alive_ids()))
+        .into_tuple::<i64>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_role_employee");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    let roles = TRole::find()
+        .filter(t_role::Column::RoleId.is_in(role_ids.clone()))
+        .order_by(t_role::Column::RoleId, Order::Asc)
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_role");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .map(|v| RespRole {
+            role_id: v.role_id,
+            role_name: v.role_name,
+            role_code: v.role_code,
+        })
+        .collect();
+
+    /* 菜单：超级管理员拥有全部菜单 */
+    let mut builder = TMenu::find_alive()
+        .filter(t_menu::Column::DisabledFlag.eq(0));
+    if employee.administrator_flag != 1 {
+        let menu_ids = TRoleMenu::find()
+            .select_only()
+            .column(t_role_menu::Column::MenuId)
+            .filter(t_role_menu::Column::RoleId.is_in(role_ids))
+            .into_tuple::<i64>()
+            .all(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "error find t_role_menu");
+                ApiErr::ErrSystem(None)
+            })?;
+        builder = builder.filter(t_menu::Column::MenuId.is_in(menu_ids));
+    }
+    let granted = builder
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    // 前端权限
+    let perms: BTreeSet<String> = granted
+        .iter()
+        .filter_map(|v| v.web_perms.as_deref())
+        .flat_map(|v| v.split(','))
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .collect();
+
+    let menus = route_tree(state, granted).await?;
+
+    let resp = RespMe {
+        employee_id: employee.employee_id,
+        login_name: employee.login_name,
+        realname: employee.realname,
+        avatar: employee.avatar,
+        gender: employee.gender,
+        phone: employee.phone,
+        email: employee.email,
+        department_id: employee.department_id,
+        department_name,
+        position_id: employee.position_id,
+        position_name,
+        administrator_flag: employee.administrator_flag,
+        must_change_password: employee.must_change_password,
+        roles,
+        menus,
+        perms: perms.into_iter().collect(),
+    };
+
+    Ok(ApiOK(Some(resp)))
+}
+
+// 根据已授权菜单构建路由树，仅包含目录和菜单，并补齐未授权的上级目录
+async fn route_tree(state: &AppState, granted: Vec<t_menu::Model>) -> Result<Vec<tree::Node<RespRoute>>> {
+    let all = TMenu::find_alive()
+        .filter(t_menu::Column::MenuType.is_in([MENU_TYPE_CATALOG, MENU_TYPE_MENU]))
+        .filter(t_menu::Column::DisabledFlag.eq(0))
+        .order_by(t_menu::Column::Sort, Order::Asc)
+        .order_by(t_menu::Column::MenuId, Order::Asc)
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    let parents: HashMap<i64, i64> = all.iter().map(|v| (v.menu_id, v.parent_id)).collect();
+
+    let mut ids = HashSet::new();
+    for menu in &granted {
+        let mut current = menu.menu_id;
+        while current != 0 && parents.contains_key(&current) && ids.insert(current) {
+            current = parents[&current];
+        }
+    }
+
+    let nodes = all
+        .into_iter()
+        .filter(|v| ids.contains(&v.menu_id))
+        .map(|v| {
+            (
+                v.menu_id,
+                v.parent_id,
+                RespRoute {
+                    menu_id: v.menu_id,
+                    menu_name: v.menu_name,
+                    menu_type: v.menu_type,
+                    parent_id: v.parent_id,
+                    sort: v.sort,
+                    path: v.path,
+                    component: v.component,
+                    icon: v.icon,
+                    visible_flag: v.visible_flag,
+                    cache_flag: v.cache_flag,
+                    frame_flag: v.frame_flag,
+                    frame_url: v.frame_url,
+                },
+            )
+        })
+        .collect();
+
+    Ok(tree::build(nodes, 0))
+}
@@ -0,0 +1,508 @@
+use std::collections::{HashMap, HashSet};
+
+use sea_orm::prelude::Expr;
+use sea_orm::{
+    ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use time::macros::offset;
+use validator::Validate;
+
+use pkg::{
+    identity::Identity,
+    result::response::{ApiErr, ApiOK, Result},
+    soft_delete::SoftDelete,
+    state::AppState,
+    tree, xtime,
+};
+
+use crate::api::perm;
+use crate::ent::{prelude::TMenu, t_menu};
+
+/// 菜单类型：目录
+pub const MENU_TYPE_CATALOG: i32 = 1;
+/// 菜单类型：菜单
+pub const MENU_TYPE_MENU: i32 = 2;
+/// 菜单类型：功能点
+pub const MENU_TYPE_POINTS: i32 = 3;
+
+/** 封装添加数据对象 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = menu::ReqCreate)]
+pub struct ReqCreate {
+    #[validate(length(min = 1, max = 200, message = "菜单名称必填"))]
+    pub menu_name: String,
+    #[validate(range(min = 1, max = 3, message = "菜单类型错误"))]
+    pub menu_type: i32,
+    #[serde(default)]
+    pub parent_id: i64,
+    pub sort: Option<i32>,
+    pub path: Option<String>,
+    pub component: Option<String>,
+    pub perms_type: Option<i32>,
+    pub api_perms: Option<String>,
+    pub web_perms: Option<String>,
+    pub icon: Option<String>,
+    pub context_menu_id: Option<i64>,
+    #[serde(default)]
+    pub frame_flag: i16,
+    pub frame_url: Option<String>,
+    #[serde(default)]
+    pub cache_flag: i16,
+    #[serde(default = "default_visible_flag")]
+    pub visible_flag: i16,
+    #[serde(default)]
+    pub disabled_flag: i16,
+}
+
+fn default_visible_flag() -> i16 {
+    1
+}
+
+/** 添加方法 */
+pub async fn create(state: &AppState, identity: Identity, mut req: ReqCreate) -> Result<ApiOK<()>> {
+    check_parent(state, req.menu_type, req.parent_id).await?;
+    check_frame(req.frame_flag, &req.frame_url)?;
+    check_name(state, &req.menu_name, req.parent_id, 0).await?;
+
+    // 功能点默认关联所属菜单
+    if req.menu_type == MENU_TYPE_POINTS && req.context_menu_id.is_none() {
+        req.context_menu_id = Some(req.parent_id);
+    }
+
+    /* 创建数据对象 */
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = t_menu::ActiveModel {
+        menu_name: Set(req.menu_name),
+        menu_type: Set(req.menu_type),
+        parent_id: Set(req.parent_id),
+        sort: Set(req.sort),
+        path: Set(req.path),
+        component: Set(req.component),
+        perms_type: Set(req.perms_type),
+        api_perms: Set(req.api_perms),
+        web_perms: Set(req.web_perms),
+        icon: Set(req.icon),
+        context_menu_id: Set(req.context_menu_id),
+        frame_flag: Set(req.frame_flag),
+        frame_url: Set(req.frame_url),
+        cache_flag: Set(req.cache_flag),
+        visible_flag: Set(req.visible_flag),
+        disabled_flag: Set(req.disabled_flag),
+        deleted_flag: Set(0),
+        create_user_id: Set(identity.id()),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    };
+    /* 插入数据 */
+    if let Err(e) = TMenu::insert(model).exec(&state.db).await {
+        tracing::error!(error = ?e, "error insert t_menu");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    perm::invalidate_all(state);
+
+    Ok(ApiOK(None))
+}
+
+/** 封装返回数据对象 */
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(as = menu::RespInfo)]
+pub struct RespInfo {
+    pub menu_id: i64,
+    pub menu_name: String,
+    pub menu_type: i32,
+    pub parent_id: i64,
+    pub sort: Option<i32>,
+    pub path: Option<String>,
+    pub component: Option<String>,
+    pub perms_type: Option<i32>,
+    pub api_perms: Option<String>,
+    pub web_perms: Option<String>,
+    pub icon: Option<String>,
+    pub context_menu_id: Option<i64>,
+    pub frame_flag: i16,
+    pub frame_url: Option<String>,
+    pub cache_flag: i16,
+    pub visible_flag: i16,
+    pub disabled_flag: i16,
+    pub create_user_id: i64,
+    pub create_time: i64,
+    pub create_time_str: String,
+}
+
+impl From<t_menu::Model> for RespInfo {
+    fn from(model: t_menu::Model) -> Self {
+        Self {
+            menu_id: model.menu_id,
+            menu_name: model.menu_name,
+            menu_type: model.menu_type,
+            parent_id: model.parent_id,
+            sort: model.sort,
+            path: model.path,
+            component: model.component,
+            perms_type: model.perms_type,
+            api_perms: model.api_perms,
+            web_perms: model.web_perms,
+            icon: model.icon,
+            context_menu_id: model.context_menu_id,
+            frame_flag: model.frame_flag,
+            frame_url: model.frame_url,
+            cache_flag: model.cache_flag,
+            visible_flag: model.visible_flag,
+            disabled_flag: model.disabled_flag,
+            create_user_id: model.create_user_id,
+            create_time: model.create_time,
+            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/** 获取详情 */
+pub async fn info(state: &AppState, menu_id: i64) -> Result<ApiOK<RespInfo>> {
+    let model = find(state, menu_id).await?;
+    Ok(ApiOK(Some(RespInfo::from(model))))
+}
+
+/** 菜单树（包含全部字段） */
+pub async fn tree(state: &AppState) -> Result<ApiOK<Vec<tree::Node<RespInfo>>>> {
+    let models = TMenu::find_alive()
+        .order_by(t_menu::Column::Sort, Order::Asc)
+        .order_by(t_menu::Column::MenuId, Order::Asc)
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    let nodes = models
+        .into_iter()
+        .map(|model| (model.menu_id, model.parent_id, RespInfo::from(model)))
+        .collect::<Vec<_>>();
+
+    Ok(ApiOK(Some(tree::build(nodes, 0))))
+}
+
+/** 封装修改数据对象 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = menu::UpdateInfo)]
+pub struct UpdateInfo {
+    pub menu_id: i64,
+    #[validate(length(min = 1, max = 200, message = "菜单名称必填"))]
+    pub menu_name: String,
+    #[validate(range(min = 1, max = 3, message = "菜单类型错误"))]
+    pub menu_type: i32,
+    #[serde(default)]
+    pub parent_id: i64,
+    pub sort: Option<i32>,
+    pub path: Option<String>,
+    pub component: Option<String>,
+    pub perms_type: Option<i32>,
+    pub api_perms: Option<String>,
+    pub web_perms: Option<String>,
+    pub icon: Option<String>,
+    pub context_menu_id: Option<i64>,
+    #[serde(default)]
+    pub frame_flag: i16,
+    pub frame_url: Option<String>,
+    #[serde(default)]
+    pub cache_flag: i16,
+    #[serde(default = "default_visible_flag")]
+    pub visible_flag: i16,
+    #[serde(default)]
+    pub disabled_flag: i16,
+}
+
+/** 修改方法 */
+pub async fn update(state: &AppState, identity: Identity, mut req: UpdateInfo) -> Result<ApiOK<()>> {
+    let model = find(state, req.menu_id).await?;
+
+    check_parent(state, req.menu_type, req.parent_id).await?;
+    check_frame(req.frame_flag, &req.frame_url)?;
+    check_name(state, &req.menu_name, req.parent_id, req.menu_id).await?;
+    check_cycle(state, req.menu_id, req.parent_id).await?;
+
+    // 下级菜单须与修改后的类型相符：目录下为目录、菜单，菜单下为功能点，功能点下不能有下级
+    if model.menu_type != req.menu_type {
+        let invalid = match req.menu_type {
+            MENU_TYPE_CATALOG => vec![MENU_TYPE_POINTS],
+            MENU_TYPE_MENU => vec![MENU_TYPE_CATALOG, MENU_TYPE_MENU],
+            _ => vec![MENU_TYPE_CATALOG, MENU_TYPE_MENU, MENU_TYPE_POINTS],
+        };
+        let count = TMenu::find_alive()
+            .filter(t_menu::Column::ParentId.eq(req.menu_id))
+            .filter(t_menu::Column::MenuType.is_in(invalid))
+            .count(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "error count t_menu");
+                ApiErr::ErrSystem(None)
+            })?;
+        if count > 0 {
+            return Err(ApiErr::ErrParams(Some("该菜单下存在其它类型的下级菜单，无法修改菜单类型".to_string())));
+        }
+    }
+
+    if req.menu_type == MENU_TYPE_POINTS && req.context_menu_id.is_none() {
+        req.context_menu_id = Some(req.parent_id);
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = t_menu::ActiveModel {
+        menu_id: Set(req.menu_id),
+        menu_name: Set(req.menu_name),
+        menu_type: Set(req.menu_type),
+        parent_id: Set(req.parent_id),
+        sort: Set(req.sort),
+        path: Set(req.path),
+        component: Set(req.component),
+        perms_type: Set(req.perms_type),
+        api_perms: Set(req.api_perms),
+        web_perms: Set(req.web_perms),
+        icon: Set(req.icon),
+        context_menu_id: Set(req.context_menu_id),
+        frame_flag: Set(req.frame_flag),
+        frame_url: Set(req.frame_url),
+        cache_flag: Set(req.cache_flag),
+        visible_flag: Set(req.visible_flag),
+        disabled_flag: Set(req.disabled_flag),
+        update_user_id: Set(Some(identity.id())),
+        update_time: Set(now),
+        ..Default::default()
+    };
+
+    if let Err(e) = TMenu::update(model).exec(&state.db).await {
+        tracing::error!(error = ?e, "error update t_menu");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    perm::invalidate_all(state);
+
+    Ok(ApiOK(None))
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[schema(as = menu::ReqSort)]
+pub struct ReqSort {
+    pub menu_id: i64,
+    pub sort: i32,
+}
+
+/** 批量调整排序：在一个事务中执行，存在不存在的菜单时全部不生效 */
+pub async fn sort(state: &AppState, identity: Identity, req: Vec<ReqSort>) -> Result<ApiOK<()>> {
+    let menu_ids: HashSet<i64> = req.iter().map(|v| v.menu_id).collect();
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+
+    let txn = state.db.begin().await.map_err(|e| {
+        tracing::error!(error = ?e, "error begin transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+    let count = TMenu::find_alive()
+        .filter(t_menu::Column::MenuId.is_in(menu_ids.iter().copied()))
+        .count(&txn)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+    if count != menu_ids.len() as u64 {
+        return Err(ApiErr::ErrParams(Some("菜单不存在或已删除".to_string())));
+    }
+    for item in req {
+        let ret = TMenu::update_many()
+            .col_expr(t_menu::Column::Sort, Expr::value(item.sort))
+            .col_expr(t_menu::Column::UpdateUserId, Expr::value(identity.id()))
+            .col_expr(t_menu::Column::UpdateTime, Expr::value(now))
+            .filter(t_menu::Column::MenuId.eq(item.menu_id))
+            .exec(&txn)
+            .await;
+        if let Err(e) = ret {
+            tracing::error!(error = ?e, "error update t_menu");
+            return Err(ApiErr::ErrSystem(None));
+        }
+    }
+    txn.commit().await.map_err(|e| {
+        tracing::error!(error = ?e, "error commit transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+    Ok(ApiOK(None))
+}
+
+/** 禁用/启用 */
+pub async fn disabled_flag(state: &AppState, identity: Identity, menu_id: i64, disabled_flag: i16) -> Result<ApiOK<()>> {
+    update_flag(state, identity, menu_id, t_menu::Column::DisabledFlag, disabled_flag).await?;
+    perm::invalidate_all(state);
+    Ok(ApiOK(None))
+}
+
+/** 显示/隐藏 */
+pub async fn visible_flag(state: &AppState, identity: Identity, menu_id: i64, visible_flag: i16) -> Result<ApiOK<()>> {
+    update_flag(state, identity, menu_id, t_menu::Column::VisibleFlag, visible_flag).await?;
+    Ok(ApiOK(None))
+}
+
+/** 删除（逻辑删除，可在回收站中恢复） */
+pub async fn delete(state: &AppState, identity: Identity, menu_id: i64) -> Result<ApiOK<()>> {
+    find(state, menu_id).await?;
+
+    /* 判断是否存在下级菜单 */
+    let count = TMenu::find_alive()
+        .filter(t_menu::Column::ParentId.eq(menu_id))
+        .count(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+    if count > 0 {
+        return Err(ApiErr::ErrPerm(Some("该菜单下存在子菜单或功能点，无法删除".to_string())));
+    }
+
+    // 与其它数据相同的逻辑删除，并在同一事务中记录操作人
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let txn = state.db.begin().await.map_err(|e| {
+        tracing::error!(error = ?e, "error begin transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+    let rows = TMenu::soft_delete(&txn, vec![menu_id], now).await.map_err(|e| {
+        tracing::error!(error = ?e, "error delete t_menu");
+        ApiErr::ErrSystem(None)
+    })?;
+    if rows == 0 {
+        return Err(ApiErr::ErrNotFound(Some("菜单信息不存在".to_string())));
+    }
+    TMenu::update_many()
+        .col_expr(t_menu::Column::UpdateUserId, Expr::value(identity.id()))
+        .filter(t_menu::Column::MenuId.eq(menu_id))
+        .exec(&txn)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error update t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+    txn.commit().await.map_err(|e| {
+        tracing::error!(error = ?e, "error commit transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+
+    perm::invalidate_all(state);
+    Ok(ApiOK(None))
+}
+
+async fn update_flag(state: &AppState, identity: Identity, menu_id: i64, column: t_menu::Column, flag: i16) -> Result<()> {
+    if flag != 0 && flag != 1 {
+        return Err(ApiErr::ErrParams(Some("状态值错误".to_string())));
+    }
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let ret = TMenu::update_many()
+        .col_expr(column, Expr::value(flag))
+        .col_expr(t_menu::Column::UpdateUserId, Expr::value(identity.id()))
+        .col_expr(t_menu::Column::UpdateTime, Expr::value(now))
+        .filter(t_menu::Column::MenuId.eq(menu_id))
+        .filter(t_menu::Column::DeletedFlag.eq(0))
+        .exec(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error update t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+    if ret.rows_affected == 0 {
+        return Err(ApiErr::ErrNotFound(Some("菜单信息不存在".to_string())));
+    }
+    Ok(())
+}
+
+async fn find(state: &AppState, menu_id: i64) -> Result<t_menu::Model> {
+    TMenu::find_alive()
+        .filter(t_menu::Column::MenuId.eq(menu_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_menu");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("菜单信息不存在".to_string())))
+}
+
+// 校验上级菜单：功能点必须挂在菜单下，目录和菜单只能挂在目录下
+async fn check_parent(state: &AppState, menu_type: i32, parent_id: i64) -> Result<()> {
+    if parent_id == 0 {
+        if menu_type == MENU_TYPE_POINTS {
+            return Err(ApiErr::ErrParams(Some("功能点必须挂在菜单下".to_string())));
+        }
+        return Ok(());
+    }
+
+    let parent = TMenu::find_alive()
+        .filter(t_menu::Column::MenuId.eq(parent_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_menu");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrParams(Some("上级菜单不存在".to_string())))?;
+
+    match menu_type {
+        MENU_TYPE_POINTS if parent.menu_type != MENU_TYPE_MENU => {
+            Err(ApiErr::ErrParams(Some("功能点必须挂在菜单下".to_string())))
+        }
+        MENU_TYPE_CATALOG | MENU_TYPE_MENU if parent.menu_type != MENU_TYPE_CATALOG => {
+            Err(ApiErr::ErrParams(Some("目录和菜单只能挂在目录下".to_string())))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_frame(frame_flag: i16, frame_url: &Option<String>) -> Result<()> {
+    if frame_flag == 1 && frame_url.as_deref().unwrap_or_default().is_empty() {
+        return Err(ApiErr::ErrParams(Some("外链地址必填".to_string())));
+    }
+    Ok(())
+}
+
+// 同一上级下菜单名称不能重复
+async fn check_name(state: &AppState, menu_name: &str, parent_id: i64, menu_id: i64) -> Result<()> {
+    let count = TMenu::find_alive()
+        .filter(t_menu::Column::MenuName.eq(menu_name))
+        .filter(t_menu::Column::ParentId.eq(parent_id))
+        .filter(t_menu::Column::MenuId.ne(menu_id))
+        .count(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+    if count > 0 {
+        return Err(ApiErr::ErrPerm(Some("菜单名称重复".to_string())));
+    }
+    Ok(())
+}
+
+// 上级菜单不能是自身或自身的下级
+async fn check_cycle(state: &AppState, menu_id: i64, parent_id: i64) -> Result<()> {
+    let parents: HashMap<i64, i64> = TMenu::find_alive()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_menu");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .map(|m| (m.menu_id, m.parent_id))
+        .collect();
+
+    let mut visited = HashSet::new();
+    let mut current = parent_id;
+    while current != 0 && visited.insert(current) {
+        if current == menu_id {
+            return Err(ApiErr::ErrParams(Some("上级菜单不能是自身或下级菜单".to_string())));
+        }
+        current = parents.get(&current).copied().unwrap_or_default();
+    }
+    Ok(())
+}
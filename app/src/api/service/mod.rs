@@ -2,4 +2,13 @@ pub mod login;
 pub mod role;
 pub mod position;
 pub mod department;
-pub mod employee;
\ No newline at end of file
+pub mod employee;
+pub mod menu;
+pub mod me;
+pub mod operate_log;
+pub mod session;
+pub mod lockout;
+pub mod totp;
+pub mod captcha;
+pub mod password;
+pub mod recycle_bin;
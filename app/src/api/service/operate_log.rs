@@ -0,0 +1,141 @@
+use axum::response::Response;
+use sea_orm::{EntityTrait, Order};
+use serde::Serialize;
+use utoipa::ToSchema;
+use time::macros::offset;
+
+use pkg::{
+    export,
+    query::{Filter, Kind, ListQuery, Spec},
+    result::{
+        page::Page,
+        response::{ApiErr, ApiOK, Result},
+    },
+    state::AppState,
+    xtime,
+};
+
+use crate::ent::{prelude::TOperateLog, t_operate_log};
+
+/** 封装返回数据对象 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = operate_log::RespInfo)]
+pub struct RespInfo {
+    pub operate_log_id: i64,
+    pub operate_user_id: i64,
+    pub operate_user_name: String,
+    pub module: Option<String>,
+    pub content: Option<String>,
+    pub url: Option<String>,
+    pub method: Option<String>,
+    pub param: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub success_flag: Option<i16>,
+    pub fail_reason: Option<String>,
+    pub create_time: i64,
+    pub create_time_str: String,
+}
+
+impl From<t_operate_log::Model> for RespInfo {
+    fn from(model: t_operate_log::Model) -> Self {
+        Self {
+            operate_log_id: model.operate_log_id,
+            operate_user_id: model.operate_user_id,
+            operate_user_name: model.operate_user_name,
+            module: model.module,
+            content: model.content,
+            url: model.url,
+            method: model.method,
+            param: model.param,
+            ip: model.ip,
+            user_agent: model.user_agent,
+            success_flag: model.success_flag,
+            fail_reason: model.fail_reason,
+            create_time: model.create_time,
+            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/** 列表查询条件，操作记录只追加，按记录ID支持游标分页 */
+const LIST: Spec<t_operate_log::Column> = Spec {
+    filters: &[
+        Filter::eq("operate_user_id", t_operate_log::Column::OperateUserId, Kind::Int),
+        Filter::like("operate_user_name", t_operate_log::Column::OperateUserName),
+        Filter::eq("module", t_operate_log::Column::Module, Kind::Str),
+        Filter::eq("success_flag", t_operate_log::Column::SuccessFlag, Kind::Int),
+        Filter::range("create_time", t_operate_log::Column::CreateTime, Kind::Time),
+    ],
+    sorts: &[("operate_log_id", t_operate_log::Column::OperateLogId)],
+    default_sort: &[(t_operate_log::Column::OperateLogId, Order::Desc)],
+    cursor: Some(t_operate_log::Column::OperateLogId),
+};
+
+/** 获取列表 */
+pub async fn list(state: &AppState, query: ListQuery) -> Result<ApiOK<Page<RespInfo>>> {
+    let query = LIST.compile(&query)?;
+    let page = query.fetch(&state.db, TOperateLog::find()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find t_operate_log");
+        ApiErr::ErrSystem(None)
+    })?;
+
+    Ok(ApiOK(Some(page.map(RespInfo::from))))
+}
+
+/** 导出表头 */
+const EXPORT_HEADERS: &[&str] = &[
+    "记录ID", "操作人ID", "操作人", "模块", "操作内容", "请求地址", "请求方法", "IP", "IP归属地", "结果", "失败原因",
+    "操作时间",
+];
+
+/** 导出：查询条件同列表（不支持游标），`format` 为 csv 或 xlsx */
+pub async fn export(state: &AppState, mut query: ListQuery) -> Result<Response> {
+    let format = export::Format::parse(query.take("format").as_deref())?;
+    let query = LIST.compile(&query)?;
+    let total = query.total(&state.db, TOperateLog::find()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error count t_operate_log");
+        ApiErr::ErrSystem(None)
+    })?;
+    export::check_rows(total, &state.config)?;
+
+    let batches = query.batches(TOperateLog::find(), t_operate_log::Column::OperateLogId, export::BATCH_SIZE);
+    let response = export::response(state.db.clone(), batches, format, "operate_logs", EXPORT_HEADERS, |model| {
+        let success = match model.success_flag {
+            Some(1) => "成功",
+            Some(_) => "失败",
+            None => "",
+        };
+        vec![
+            model.operate_log_id.to_string(),
+            model.operate_user_id.to_string(),
+            model.operate_user_name,
+            model.module.unwrap_or_default(),
+            model.content.unwrap_or_default(),
+            model.url.unwrap_or_default(),
+            model.method.unwrap_or_default(),
+            model.ip.unwrap_or_default(),
+            model.ip_region.unwrap_or_default(),
+            success.to_string(),
+            model.fail_reason.unwrap_or_default(),
+            xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8)).unwrap_or_default(),
+        ]
+    });
+
+    Ok(response)
+}
+
+/** 获取详情 */
+pub async fn info(state: &AppState, operate_log_id: i64) -> Result<ApiOK<RespInfo>> {
+    let model = TOperateLog::find_by_id(operate_log_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_operate_log");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("操作记录不存在".to_string())))?;
+
+    Ok(ApiOK(Some(RespInfo::from(model))))
+}
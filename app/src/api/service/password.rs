@@ -0,0 +1,250 @@
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use time::macros::offset;
+use validator::Validate;
+
+use pkg::crypto::password;
+use pkg::{
+    identity::Identity,
+    result::response::{ApiErr, ApiOK, Result},
+    soft_delete::SoftDelete,
+    state::AppState,
+};
+
+use crate::api::service::session;
+use crate::ent::{
+    prelude::{TEmployee, TLoginSession, TPasswordHistory},
+    t_employee, t_login_session, t_password_history,
+};
+
+/** 修改密码输入参数 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = password::ReqChange)]
+pub struct ReqChange {
+    #[validate(length(min = 1, message = "原密码必填"))]
+    pub old_password: String,
+    #[validate(length(min = 1, message = "新密码必填"))]
+    pub new_password: String,
+}
+
+/** 一次性密码，仅返回一次 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = password::RespPassword)]
+pub struct RespPassword {
+    pub password: String,
+}
+
+/**
+ * 生成符合密码策略的一次性密码，返回明文及哈希
+ */
+pub fn one_time(state: &AppState) -> Result<(String, String)> {
+    let plain = state.policy.generate();
+    let hashed = state.hasher.hash(&plain).map_err(|e| {
+        tracing::error!(error = ?e, "error hash password");
+        ApiErr::ErrSystem(None)
+    })?;
+    Ok((plain, hashed))
+}
+
+/**
+ * 批量生成一次性密码：哈希为 CPU 密集操作，在阻塞线程池中一次完成，避免占用异步工作线程
+ */
+pub async fn one_time_batch(state: &AppState, count: usize) -> Result<Vec<(String, String)>> {
+    let policy = state.policy.clone();
+    let hasher = state.hasher.clone();
+    let ret = tokio::task::spawn_blocking(move || {
+        (0..count)
+            .map(|_| {
+                let plain = policy.generate();
+                hasher.hash(&plain).map(|hashed| (plain, hashed))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!(error = ?e, "error join hash task");
+        ApiErr::ErrSystem(None)
+    })?;
+    ret.map_err(|e| {
+        tracing::error!(error = ?e, "error hash password");
+        ApiErr::ErrSystem(None)
+    })
+}
+
+/**
+ * 修改当前用户密码，成功后其它登录会话下线
+ */
+pub async fn change(state: &AppState, identity: Identity, req: ReqChange) -> Result<ApiOK<()>> {
+    let employee = TEmployee::find_by_id(identity.id())
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("员工信息不存在".to_string())))?;
+
+    let verified = password::verify_with(state.hasher.as_ref(), &req.old_password, &employee.login_pwd).map_err(|e| {
+        tracing::error!(error = ?e, "error verify password");
+        ApiErr::ErrSystem(None)
+    })?;
+    if !verified.is_match() {
+        return Err(ApiErr::ErrParams(Some("原密码错误".to_string())));
+    }
+
+    state.policy
+        .validate(&req.new_password, &employee.login_name)
+        .map_err(|e| ApiErr::ErrParams(Some(e)))?;
+    check_history(state, &employee, &req.new_password).await?;
+
+    let hashed = state.hasher.hash(&req.new_password).map_err(|e| {
+        tracing::error!(error = ?e, "error hash password");
+        ApiErr::ErrSystem(None)
+    })?;
+    update(state, employee.employee_id, &hashed, false).await?;
+
+    /* 其它登录会话下线 */
+    let tokens = TLoginSession::find()
+        .select_only()
+        .column(t_login_session::Column::LoginToken)
+        .filter(t_login_session::Column::EmployeeId.eq(employee.employee_id))
+        .filter(t_login_session::Column::LoginToken.ne(identity.token()))
+        .into_tuple::<String>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_login_session");
+            ApiErr::ErrSystem(None)
+        })?;
+    session::revoke(state, tokens).await?;
+
+    Ok(ApiOK(None))
+}
+
+/**
+ * 管理员重置密码：生成一次性密码，员工登录后需修改，原登录会话全部下线
+ */
+pub async fn reset(state: &AppState, employee_id: i64) -> Result<ApiOK<RespPassword>> {
+    TEmployee::find_alive()
+        .filter(t_employee::Column::EmployeeId.eq(employee_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("员工信息不存在".to_string())))?;
+
+    let (plain, hashed) = one_time(state)?;
+    update(state, employee_id, &hashed, true).await?;
+    session::kick_employee(state, employee_id).await?;
+
+    Ok(ApiOK(Some(RespPassword { password: plain })))
+}
+
+/**
+ * 记录历史密码，仅保留策略要求的条数；`db` 可为事务
+ */
+pub async fn save_history<C: ConnectionTrait>(state: &AppState, db: &C, employee_id: i64, hashed: &str) -> Result<()> {
+    let history = state.policy.history as u64;
+    if history == 0 {
+        return Ok(());
+    }
+
+    let model = t_password_history::ActiveModel {
+        employee_id: Set(employee_id),
+        login_pwd: Set(hashed.to_string()),
+        create_time: Set(state.clock.now(offset!(+8)).unix_timestamp()),
+        ..Default::default()
+    };
+    if let Err(e) = TPasswordHistory::insert(model).exec(db).await {
+        tracing::error!(error = ?e, "error insert t_password_history");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    // OFFSET 须与 LIMIT 同时使用（MySQL、SQLite），超出的部分在查询后跳过
+    let expired: Vec<i64> = TPasswordHistory::find()
+        .select_only()
+        .column(t_password_history::Column::Id)
+        .filter(t_password_history::Column::EmployeeId.eq(employee_id))
+        .order_by(t_password_history::Column::Id, Order::Desc)
+        .into_tuple::<i64>()
+        .all(db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_password_history");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .skip(history as usize)
+        .collect();
+    if !expired.is_empty() {
+        let ret = TPasswordHistory::delete_many()
+            .filter(t_password_history::Column::Id.is_in(expired))
+            .exec(db)
+            .await;
+        if let Err(e) = ret {
+            tracing::error!(error = ?e, "error delete t_password_history");
+            return Err(ApiErr::ErrSystem(None));
+        }
+    }
+    Ok(())
+}
+
+// 新密码不可与当前密码及最近使用过的密码相同
+async fn check_history(state: &AppState, employee: &t_employee::Model, pwd: &str) -> Result<()> {
+    let history = state.policy.history as u64;
+
+    let mut hashes = vec![employee.login_pwd.clone()];
+    if history > 0 {
+        let list = TPasswordHistory::find()
+            .select_only()
+            .column(t_password_history::Column::LoginPwd)
+            .filter(t_password_history::Column::EmployeeId.eq(employee.employee_id))
+            .order_by(t_password_history::Column::Id, Order::Desc)
+            .limit(history)
+            .into_tuple::<String>()
+            .all(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "error find t_password_history");
+                ApiErr::ErrSystem(None)
+            })?;
+        hashes.extend(list);
+    }
+
+    for hashed in hashes {
+        let verified = password::verify_with(state.hasher.as_ref(), pwd, &hashed).map_err(|e| {
+            tracing::error!(error = ?e, "error verify password");
+            ApiErr::ErrSystem(None)
+        })?;
+        if verified.is_match() {
+            return Err(ApiErr::ErrParams(Some(format!(
+                "新密码不能与最近{}次使用过的密码相同",
+                history.max(1)
+            ))));
+        }
+    }
+    Ok(())
+}
+
+// 更新密码及是否需要修改密码的状态
+async fn update(state: &AppState, employee_id: i64, hashed: &str, must_change: bool) -> Result<()> {
+    let ret = TEmployee::update_many()
+        .col_expr(t_employee::Column::LoginPwd, Expr::value(hashed))
+        .col_expr(t_employee::Column::MustChangePassword, Expr::value(must_change as i16))
+        .col_expr(
+            t_employee::Column::UpdateTime,
+            Expr::value(state.clock.now(offset!(+8)).unix_timestamp()),
+        )
+        .filter(t_employee::Column::EmployeeId.eq(employee_id))
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error update t_employee");
+        return Err(ApiErr::ErrSystem(None));
+    }
+    save_history(state, &state.db, employee_id, hashed).await
+}
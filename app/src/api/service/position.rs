@@ -1,36 +1,43 @@
-use std::collections::HashMap;
-
+use axum::response::Response;
 use sea_orm::{
-    ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set
+    ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, Set
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use time::macros::offset;
 use validator::Validate;
 
 use pkg::{
-    db,
-    result::response::{ApiErr, ApiOK, Result},
-    util, xtime,
+    export,
+    query::{Filter, Kind, ListQuery, Spec},
+    soft_delete::SoftDelete,
+    result::{
+        page::Page,
+        response::{ApiErr, ApiOK, Result},
+    },
+    state::AppState,
+    xtime,
 };
 
-use crate::ent::{t_position, prelude::TPosition};
+use crate::ent::{t_employee, t_position, prelude::TEmployee, prelude::TPosition};
 
 /** 封装添加数据对象 */
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = position::ReqCreate)]
 pub struct ReqCreate {
     #[validate(length(min = 1, message = "职务名称必填"))]
     pub postname: String,
     pub level: String,
-    pub sort: i64,
+    pub sort: i32,
     pub remark: String,
 }
 
 /** 添加方法 */
-pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
+pub async fn create(state: &AppState, req: ReqCreate) -> Result<ApiOK<()>> {
     
     let count = TPosition::find()
         .filter(t_position::Column::PositionName.eq(req.postname.clone()))
-        .count(db::conn())
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error count t_position");
@@ -41,8 +48,8 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
         return Err(ApiErr::ErrPerm(Some("职务名称重复".to_string())));
     }
 
-    /** 创建数据对象 */
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+    /* 创建数据对象 */
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_position::ActiveModel {
         position_name: Set(req.postname),
         level: Set(req.level),
@@ -53,7 +60,7 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
         ..Default::default()
     };
     /* 插入数据 */
-    if let Err(e) = TPosition::insert(model).exec(db::conn()).await {
+    if let Err(e) = TPosition::insert(model).exec(&state.db).await {
         tracing::error!(error = ?e, "error insert t_position");
         return Err(ApiErr::ErrSystem(None));
     }
@@ -62,96 +69,105 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
 }
 
 /** 封装返回数据对象 */
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = position::RespInfo)]
 pub struct RespInfo {
     pub postid: i64,
     pub postname: String,
     pub level: String,
-    pub sort: i64,
+    pub sort: i32,
     pub remark: String,
     pub create_time: i64,
     pub create_time_str: String,
 }
 
-/** 返回列表数据对象 */
-#[derive(Debug, Serialize)]
-pub struct RespList {
-    pub total: i64,
-    pub list: Vec<RespInfo>,
-}
-
 
-/** 获取列表 */
-pub async fn list(query: HashMap<String, String>) -> Result<ApiOK<RespList>> {
-    /** 查询条件 */
-    let mut builder = TPosition::find();
-    if let Some(postname) = query.get("postname") {
-        if !postname.is_empty() {
-            builder = builder.filter(t_position::Column::PositionName.contains(postname));
-        }
-    }
 
-    let mut total: i64 = 0;
-    let (offset, limit) = util::query_page(&query);
-    // 仅在第一页计算数量
-    if offset == 0 {
-        total = builder
-            .clone()
-            .select_only()
-            .column_as(t_position::Column::PositionId.count(), "count")
-            .into_tuple::<i64>()
-            .one(db::conn())
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "error count t_position");
-                ApiErr::ErrSystem(None)
-            })?
-            .unwrap_or_default();
-    }
+/** 列表查询条件 */
+const LIST: Spec<t_position::Column> = Spec {
+    filters: &[
+        Filter::like("postname", t_position::Column::PositionName),
+        Filter::eq("level", t_position::Column::Level, Kind::Str),
+        Filter::range("create_time", t_position::Column::CreateTime, Kind::Time),
+    ],
+    sorts: &[
+        ("postid", t_position::Column::PositionId),
+        ("postname", t_position::Column::PositionName),
+        ("sort", t_position::Column::Sort),
+        ("create_time", t_position::Column::CreateTime),
+    ],
+    default_sort: &[(t_position::Column::PositionId, Order::Desc)],
+    cursor: None,
+};
 
-    let models = builder
-        .order_by(t_position::Column::PositionId, Order::Desc)
-        .offset(offset)
-        .limit(limit)
-        .all(db::conn())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = ?e, "error find t_position");
-            ApiErr::ErrSystem(None)
-        })?;
-    let mut resp = RespList {
-        total,
-        list: (Vec::with_capacity(models.len())),
-    };
-    for model in models {
-        let info = RespInfo {
-            postid: model.position_id,
-            postname: model.position_name,
-            level: model.level,
-            sort: model.sort,
-            remark: model.remark,
-            create_time: model.create_time,
-            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+/** 获取列表 */
+pub async fn list(state: &AppState, query: ListQuery) -> Result<ApiOK<Page<RespInfo>>> {
+    /* 查询条件 */
+    let query = LIST.compile(&query)?;
+    let page = query.fetch(&state.db, TPosition::find_alive()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find t_position");
+        ApiErr::ErrSystem(None)
+    })?;
+    let resp = page.map(|model| RespInfo {
+        postid: model.position_id,
+        postname: model.position_name,
+        level: model.level,
+        sort: model.sort,
+        remark: model.remark,
+        create_time: model.create_time,
+        create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
             .unwrap_or_default(),
-        };
-        resp.list.push(info);
-    }
+    });
 
     Ok(ApiOK(Some(resp)))
 }
 
-/** 获取详情 */
-pub async fn info(postid: u64) -> Result<ApiOK<RespInfo>> {
-    let model = TPosition::find_by_id(postid as i64)
-        .one(db::conn())
+/** 导出表头 */
+const EXPORT_HEADERS: &[&str] = &["职务ID", "职务名称", "职级", "排序", "备注", "创建时间"];
+
+/** 导出：查询条件同列表，`format` 为 csv 或 xlsx */
+pub async fn export(state: &AppState, mut query: ListQuery) -> Result<Response> {
+    let format = export::Format::parse(query.take("format").as_deref())?;
+    let query = LIST.compile(&query)?;
+    let total = query.total(&state.db, TPosition::find_alive()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error count t_position");
+        ApiErr::ErrSystem(None)
+    })?;
+    export::check_rows(total, &state.config)?;
+
+    let batches = query.batches(TPosition::find_alive(), t_position::Column::PositionId, export::BATCH_SIZE);
+    let response = export::response(state.db.clone(), batches, format, "positions", EXPORT_HEADERS, |model| {
+        vec![
+            model.position_id.to_string(),
+            model.position_name,
+            model.level,
+            model.sort.to_string(),
+            model.remark,
+            xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8)).unwrap_or_default(),
+        ]
+    });
+
+    Ok(response)
+}
+
+// 查询未删除的职务
+async fn find_position(state: &AppState, postid: i64) -> Result<t_position::Model> {
+    TPosition::find_alive()
+        .filter(t_position::Column::PositionId.eq(postid))
+        .one(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_position");
             ApiErr::ErrSystem(None)
         })?
-        .ok_or(ApiErr::ErrNotFound(Some("职务信息不存在".to_string())))?;
+        .ok_or(ApiErr::ErrNotFound(Some("职务信息不存在".to_string())))
+}
+
+/** 获取详情 */
+pub async fn info(state: &AppState, postid: u64) -> Result<ApiOK<RespInfo>> {
+    let model = find_position(state, postid as i64).await?;
 
-   let mut resp = RespInfo {
+   let resp = RespInfo {
         postid: model.position_id,
         postname: model.position_name,
         level: model.level,
@@ -166,22 +182,25 @@ pub async fn info(postid: u64) -> Result<ApiOK<RespInfo>> {
 
 
 /** 封装修改数据对象 */
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = position::UpdateInfo)]
 pub struct UpdateInfo {
     pub postid: i64,
     #[validate(length(min = 1, message = "角色名称必填"))]
     pub postname: String,
     pub level: String,
-    pub sort: i64,
+    pub sort: i32,
     pub remark: String,
     pub create_time: i64,
     pub create_time_str: String,
 }
 /** 修改方法 */
-pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
-   
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+pub async fn update(state: &AppState, req: UpdateInfo) -> Result<ApiOK<()>> {
+    find_position(state, req.postid).await?;
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_position::ActiveModel {
+        position_id: Set(req.postid),
         position_name: Set(req.postname),
         level: Set(req.level),
         sort: Set(req.sort),
@@ -190,17 +209,31 @@ pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
         ..Default::default()
     };
 
-    if let Err(e) = TPosition::update(model).exec(db::conn()).await {
+    if let Err(e) = TPosition::update(model).exec(&state.db).await {
         tracing::error!(error = ?e, "error update t_position");
         return Err(ApiErr::ErrSystem(None));
     }
     Ok(ApiOK(None))
 }
 
-/** 删除 */
-pub async fn delete(postid: u64) -> Result<ApiOK<()>> {
+/** 删除（逻辑删除，可在回收站中恢复）：已分配员工的职务不能删除 */
+pub async fn delete(state: &AppState, postid: u64) -> Result<ApiOK<()>> {
+    find_position(state, postid as i64).await?;
+
+    let employee_count = TEmployee::find_alive()
+        .filter(t_employee::Column::PositionId.eq(postid as i64))
+        .count(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_employee");
+            ApiErr::ErrSystem(None)
+        })?;
+    if employee_count > 0 {
+        return Err(ApiErr::ErrPerm(Some("该职务下已分配员工，无法删除".to_string())));
+    }
 
-    if let Err(e) = TPosition::delete_by_id(postid as i64).exec(db::conn()).await {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    if let Err(e) = TPosition::soft_delete(&state.db, vec![postid as i64], now).await {
         tracing::error!(error = ?e, "error delete t_position");
         return Err(ApiErr::ErrSystem(None));
     }
@@ -208,16 +241,17 @@ pub async fn delete(postid: u64) -> Result<ApiOK<()>> {
 }
 
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[schema(as = position::RespSelect)]
 pub struct RespSelect {
     pub postid: i64,
     pub postname: String,
 }
 
-pub async fn select_list() -> Result<ApiOK<Vec<RespSelect>>> {
+pub async fn select_list(state: &AppState) -> Result<ApiOK<Vec<RespSelect>>> {
 
-    let models = TPosition::find()
-            .all(db::conn())
+    let models = TPosition::find_alive()
+            .all(&state.db)
             .await
             .map_err(|e| {
                 tracing::error!(error = ?e, "error find t_position");
@@ -0,0 +1,387 @@
+use sea_orm::{ColumnTrait, DatabaseTransaction, DbErr, EntityTrait, Order, PaginatorTrait, QueryFilter, TransactionTrait};
+use serde::Serialize;
+use utoipa::ToSchema;
+use time::macros::offset;
+
+use pkg::{
+    query::{Filter, Kind, ListQuery, Spec},
+    result::{
+        page::Page,
+        response::{ApiErr, ApiOK, Result},
+    },
+    soft_delete::SoftDelete,
+    state::AppState,
+    xtime,
+};
+
+use crate::api::perm;
+use crate::ent::{
+    prelude::{
+        TDepartment, TEmployee, TEmployeeTotp, TLoginSession, TMenu, TPasswordHistory, TPosition, TRefreshToken,
+        TRole, TRoleDepartment, TRoleEmployee, TRoleMenu,
+    },
+    t_department, t_employee, t_employee_totp, t_login_session, t_menu, t_password_history, t_position,
+    t_refresh_token, t_role, t_role_department, t_role_employee, t_role_menu,
+};
+
+/** 回收站数据类型 */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Employee,
+    Department,
+    Position,
+    Role,
+    Menu,
+}
+
+impl Type {
+    fn parse(v: Option<&str>) -> Result<Self> {
+        match v.map(|v| v.trim()) {
+            Some("employee") => Ok(Type::Employee),
+            Some("department") => Ok(Type::Department),
+            Some("position") => Ok(Type::Position),
+            Some("role") => Ok(Type::Role),
+            Some("menu") => Ok(Type::Menu),
+            _ => Err(ApiErr::ErrParams(Some(
+                "数据类型应为 employee、department、position、role 或 menu".to_string(),
+            ))),
+        }
+    }
+}
+
+/** 封装返回数据对象 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = recycle_bin::RespInfo)]
+pub struct RespInfo {
+    /// 数据ID
+    pub id: i64,
+    /// 名称：员工为姓名（登录名），其它为名称
+    pub name: String,
+    /// 删除时间
+    pub delete_time: i64,
+    pub delete_time_str: String,
+}
+
+const EMPLOYEE: Spec<t_employee::Column> = Spec {
+    filters: &[
+        Filter::like("name", t_employee::Column::Realname),
+        Filter::range("delete_time", t_employee::Column::UpdateTime, Kind::Time),
+    ],
+    sorts: &[("delete_time", t_employee::Column::UpdateTime)],
+    default_sort: &[(t_employee::Column::UpdateTime, Order::Desc), (t_employee::Column::EmployeeId, Order::Desc)],
+    cursor: None,
+};
+
+const DEPARTMENT: Spec<t_department::Column> = Spec {
+    filters: &[
+        Filter::like("name", t_department::Column::DepartmentName),
+        Filter::range("delete_time", t_department::Column::UpdateTime, Kind::Time),
+    ],
+    sorts: &[("delete_time", t_department::Column::UpdateTime)],
+    default_sort: &[(t_department::Column::UpdateTime, Order::Desc), (t_department::Column::DepartmentId, Order::Desc)],
+    cursor: None,
+};
+
+const POSITION: Spec<t_position::Column> = Spec {
+    filters: &[
+        Filter::like("name", t_position::Column::PositionName),
+        Filter::range("delete_time", t_position::Column::UpdateTime, Kind::Time),
+    ],
+    sorts: &[("delete_time", t_position::Column::UpdateTime)],
+    default_sort: &[(t_position::Column::UpdateTime, Order::Desc), (t_position::Column::PositionId, Order::Desc)],
+    cursor: None,
+};
+
+const ROLE: Spec<t_role::Column> = Spec {
+    filters: &[
+        Filter::like("name", t_role::Column::RoleName),
+        Filter::range("delete_time", t_role::Column::UpdateTime, Kind::Time),
+    ],
+    sorts: &[("delete_time", t_role::Column::UpdateTime)],
+    default_sort: &[(t_role::Column::UpdateTime, Order::Desc), (t_role::Column::RoleId, Order::Desc)],
+    cursor: None,
+};
+
+const MENU: Spec<t_menu::Column> = Spec {
+    filters: &[
+        Filter::like("name", t_menu::Column::MenuName),
+        Filter::range("delete_time", t_menu::Column::UpdateTime, Kind::Time),
+    ],
+    sorts: &[("delete_time", t_menu::Column::UpdateTime)],
+    default_sort: &[(t_menu::Column::UpdateTime, Order::Desc), (t_menu::Column::MenuId, Order::Desc)],
+    cursor: None,
+};
+
+/** 回收站列表：`type` 为数据类型，按删除时间倒序 */
+pub async fn list(state: &AppState, mut query: ListQuery) -> Result<ApiOK<Page<RespInfo>>> {
+    let page = match Type::parse(query.take("type").as_deref())? {
+        Type::Employee => fetch::<TEmployee, _>(state, &EMPLOYEE, &query, |m| {
+            (m.employee_id, format!("{}（{}）", m.realname, m.login_name), m.update_time)
+        })
+        .await?,
+        Type::Department => fetch::<TDepartment, _>(state, &DEPARTMENT, &query, |m| {
+            (m.department_id, m.department_name, m.update_time)
+        })
+        .await?,
+        Type::Position => fetch::<TPosition, _>(state, &POSITION, &query, |m| {
+            (m.position_id, m.position_name, m.update_time)
+        })
+        .await?,
+        Type::Role => fetch::<TRole, _>(state, &ROLE, &query, |m| (m.role_id, m.role_name, m.update_time)).await?,
+        Type::Menu => fetch::<TMenu, _>(state, &MENU, &query, |m| (m.menu_id, m.menu_name, m.update_time)).await?,
+    };
+    Ok(ApiOK(Some(page)))
+}
+
+// 查询回收站中的一页数据，`f` 返回 (ID, 名称, 删除时间)
+async fn fetch<E, F>(state: &AppState, spec: &Spec<E::Column>, query: &ListQuery, f: F) -> Result<Page<RespInfo>>
+where
+    E: SoftDelete,
+    E::Model: Sync,
+    F: Fn(E::Model) -> (i64, String, i64),
+{
+    let query = spec.compile(query)?;
+    let page = query.fetch(&state.db, E::find_deleted()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find recycle bin");
+        ApiErr::ErrSystem(None)
+    })?;
+    Ok(page.map(|model| {
+        let (id, name, delete_time) = f(model);
+        RespInfo {
+            id,
+            name,
+            delete_time,
+            delete_time_str: xtime::to_string(xtime::DATETIME, delete_time, offset!(+8)).unwrap_or_default(),
+        }
+    }))
+}
+
+/** 恢复：员工所在部门及职务、部门及菜单的上级须未删除 */
+pub async fn restore(state: &AppState, kind: String, id: i64) -> Result<ApiOK<()>> {
+    let kind = Type::parse(Some(&kind))?;
+    match kind {
+        Type::Employee => {
+            let model = find_deleted::<TEmployee>(state, id).await?;
+            if !alive::<TDepartment>(state, model.department_id).await? {
+                return Err(ApiErr::ErrPerm(Some("员工所在部门已删除，请先恢复部门".to_string())));
+            }
+            if model.position_id != 0 && !alive::<TPosition>(state, model.position_id).await? {
+                return Err(ApiErr::ErrPerm(Some("员工的职务已删除，请先恢复职务".to_string())));
+            }
+        }
+        Type::Department => {
+            let model = find_deleted::<TDepartment>(state, id).await?;
+            if model.parent_id != 0 && !alive::<TDepartment>(state, model.parent_id).await? {
+                return Err(ApiErr::ErrPerm(Some("上级部门已删除，请先恢复上级部门".to_string())));
+            }
+        }
+        Type::Position => {
+            find_deleted::<TPosition>(state, id).await?;
+        }
+        Type::Role => {
+            find_deleted::<TRole>(state, id).await?;
+        }
+        Type::Menu => {
+            let model = find_deleted::<TMenu>(state, id).await?;
+            if model.parent_id != 0 && !alive::<TMenu>(state, model.parent_id).await? {
+                return Err(ApiErr::ErrPerm(Some("上级菜单已删除，请先恢复上级菜单".to_string())));
+            }
+        }
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let ret = match kind {
+        Type::Employee => TEmployee::restore(&state.db, vec![id], now).await,
+        Type::Department => TDepartment::restore(&state.db, vec![id], now).await,
+        Type::Position => TPosition::restore(&state.db, vec![id], now).await,
+        Type::Role => TRole::restore(&state.db, vec![id], now).await,
+        Type::Menu => TMenu::restore(&state.db, vec![id], now).await,
+    };
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error restore recycle bin");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    // 角色、菜单影响已分配员工的权限
+    if matches!(kind, Type::Role | Type::Menu) {
+        perm::invalidate_all(state);
+    }
+    Ok(ApiOK(None))
+}
+
+/** 永久删除：同时清除关联数据，不可恢复；部门、职务、菜单存在已删除的下级数据或员工时须先永久删除 */
+pub async fn purge(state: &AppState, kind: String, id: i64) -> Result<ApiOK<()>> {
+    let kind = Type::parse(Some(&kind))?;
+
+    let txn = state.db.begin().await.map_err(|e| {
+        tracing::error!(error = ?e, "error begin transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+    let rows = purge_in(&txn, kind, id).await.map_err(|e| {
+        tracing::error!(error = ?e, "error purge recycle bin");
+        ApiErr::ErrSystem(None)
+    })?;
+    if rows == 0 {
+        return Err(not_found());
+    }
+    // 未提交的事务在返回时回滚
+    let msg = dependent(&txn, kind, id).await.map_err(|e| {
+        tracing::error!(error = ?e, "error count recycle bin");
+        ApiErr::ErrSystem(None)
+    })?;
+    if let Some(v) = msg {
+        return Err(ApiErr::ErrPerm(Some(v.to_string())));
+    }
+    txn.commit().await.map_err(|e| {
+        tracing::error!(error = ?e, "error commit transaction");
+        ApiErr::ErrSystem(None)
+    })?;
+
+    if matches!(kind, Type::Role | Type::Menu) {
+        perm::invalidate_all(state);
+    }
+    Ok(ApiOK(None))
+}
+
+// 删除回收站中的数据及其关联数据，返回删除的行数
+async fn purge_in(txn: &DatabaseTransaction, kind: Type, id: i64) -> std::result::Result<u64, DbErr> {
+    let rows = match kind {
+        Type::Employee => TEmployee::purge(txn, vec![id]).await?,
+        Type::Department => TDepartment::purge(txn, vec![id]).await?,
+        Type::Position => TPosition::purge(txn, vec![id]).await?,
+        Type::Role => TRole::purge(txn, vec![id]).await?,
+        Type::Menu => TMenu::purge(txn, vec![id]).await?,
+    };
+    if rows == 0 {
+        return Ok(0);
+    }
+
+    match kind {
+        Type::Employee => {
+            TRoleEmployee::delete_many()
+                .filter(t_role_employee::Column::EmployeeId.eq(id))
+                .exec(txn)
+                .await?;
+            TPasswordHistory::delete_many()
+                .filter(t_password_history::Column::EmployeeId.eq(id))
+                .exec(txn)
+                .await?;
+            TLoginSession::delete_many()
+                .filter(t_login_session::Column::EmployeeId.eq(id))
+                .exec(txn)
+                .await?;
+            TRefreshToken::delete_many()
+                .filter(t_refresh_token::Column::EmployeeId.eq(id))
+                .exec(txn)
+                .await?;
+            TEmployeeTotp::delete_many()
+                .filter(t_employee_totp::Column::EmployeeId.eq(id))
+                .exec(txn)
+                .await?;
+        }
+        Type::Department => {
+            TRoleDepartment::delete_many()
+                .filter(t_role_department::Column::DepartmentId.eq(id))
+                .exec(txn)
+                .await?;
+        }
+        Type::Position => {}
+        Type::Role => {
+            TRoleEmployee::delete_many()
+                .filter(t_role_employee::Column::RoleId.eq(id))
+                .exec(txn)
+                .await?;
+            TRoleMenu::delete_many()
+                .filter(t_role_menu::Column::RoleId.eq(id))
+                .exec(txn)
+                .await?;
+            TRoleDepartment::delete_many()
+                .filter(t_role_department::Column::RoleId.eq(id))
+                .exec(txn)
+                .await?;
+        }
+        Type::Menu => {
+            TRoleMenu::delete_many()
+                .filter(t_role_menu::Column::MenuId.eq(id))
+                .exec(txn)
+                .await?;
+        }
+    }
+    Ok(rows)
+}
+
+// 仍引用该数据的下级部门、菜单或员工（含已删除），存在时返回提示信息
+async fn dependent(txn: &DatabaseTransaction, kind: Type, id: i64) -> std::result::Result<Option<&'static str>, DbErr> {
+    match kind {
+        Type::Department => {
+            let count = TDepartment::find()
+                .filter(t_department::Column::ParentId.eq(id))
+                .count(txn)
+                .await?;
+            if count > 0 {
+                return Ok(Some("回收站中存在其下级部门，请先永久删除下级部门"));
+            }
+            let count = TEmployee::find()
+                .filter(t_employee::Column::DepartmentId.eq(id))
+                .count(txn)
+                .await?;
+            if count > 0 {
+                return Ok(Some("回收站中存在该部门的员工，请先永久删除员工"));
+            }
+        }
+        Type::Menu => {
+            let count = TMenu::find()
+                .filter(t_menu::Column::ParentId.eq(id))
+                .count(txn)
+                .await?;
+            if count > 0 {
+                return Ok(Some("回收站中存在其下级菜单，请先永久删除下级菜单"));
+            }
+        }
+        Type::Position => {
+            let count = TEmployee::find()
+                .filter(t_employee::Column::PositionId.eq(id))
+                .count(txn)
+                .await?;
+            if count > 0 {
+                return Ok(Some("回收站中存在该职务的员工，请先永久删除员工"));
+            }
+        }
+        Type::Employee | Type::Role => {}
+    }
+    Ok(None)
+}
+
+fn not_found() -> ApiErr {
+    ApiErr::ErrNotFound(Some("回收站中不存在该数据".to_string()))
+}
+
+// 查询回收站中的数据
+async fn find_deleted<E: SoftDelete>(state: &AppState, id: i64) -> Result<E::Model> {
+    E::find_deleted()
+        .filter(E::id_column().eq(id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find recycle bin");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or_else(not_found)
+}
+
+// 数据是否存在且未删除
+async fn alive<E>(state: &AppState, id: i64) -> Result<bool>
+where
+    E: SoftDelete,
+    E::Model: Sync,
+{
+    let count = E::find_alive()
+        .filter(E::id_column().eq(id))
+        .count(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count recycle bin");
+            ApiErr::ErrSystem(None)
+        })?;
+    Ok(count > 0)
+}
@@ -1,42 +1,65 @@
-use std::collections::HashMap;
-
+use axum::response::Response;
 use sea_orm::{
-    ColumnTrait, Condition, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set
+    ColumnTrait, Condition, DbErr, EntityTrait, Order, PaginatorTrait, QueryFilter, QuerySelect, Set,
+    SqlErr, TransactionTrait
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use time::macros::offset;
 use validator::Validate;
 
 use pkg::{
-    db,
-    result::response::{ApiErr, ApiOK, Result},
-    util, xtime,
+    export,
+    identity::Identity,
+    query::{Filter, Kind, ListQuery, Spec},
+    soft_delete::SoftDelete,
+    result::{
+        page::Page,
+        response::{ApiErr, ApiOK, Result},
+    },
+    state::AppState,
+    xtime,
     tree,
 };
 
+use crate::api::{perm, scope};
 use crate::ent::{
         t_role, prelude::TRole,
         t_role_employee, prelude::TRoleEmployee, 
         t_employee, prelude::TEmployee, 
         t_menu, prelude::TMenu, 
-        t_role_menu, prelude::TRoleMenu
+        t_role_menu, prelude::TRoleMenu,
+        t_department, prelude::TDepartment,
+        t_role_department, prelude::TRoleDepartment
 };
 
 /** 封装添加数据对象 */
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = role::ReqCreate)]
 pub struct ReqCreate {
     #[validate(length(min = 1, message = "角色名称必填"))]
     pub rolename: String,
     #[validate(length(min = 1, message = "角色编码必填"))]
     pub rolecode: String,
     pub remark: String,
+    // 是否要求两步验证
+    #[serde(default)]
+    pub mfa_flag: i16,
+    // 数据范围 1全部 2自定义部门 3本部门 4本部门及以下 5仅本人
+    #[serde(default = "default_data_scope")]
+    #[validate(range(min = 1, max = 5, message = "数据范围不正确"))]
+    pub data_scope: i16,
+}
+
+fn default_data_scope() -> i16 {
+    scope::ALL
 }
 
 /** 添加方法 */
-pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
+pub async fn create(state: &AppState, req: ReqCreate) -> Result<ApiOK<()>> {
     let count= TRole::find()
         .filter(Condition::any().add(t_role::Column::RoleName.eq(req.rolename.clone())).add(t_role::Column::RoleCode.eq(req.rolecode.clone())))
-        .count(db::conn())
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error count t_role");
@@ -47,17 +70,19 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
         return Err(ApiErr::ErrPerm(Some("角色名称或角色编码重复".to_string())));
     }
 
-    /** 创建数据对象 */
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+    /* 创建数据对象 */
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_role::ActiveModel {
         role_name: Set(req.rolename),
         role_code: Set(req.rolecode),
         remark: Set(req.remark),
+        mfa_flag: Set(req.mfa_flag),
+        data_scope: Set(req.data_scope),
         create_time: Set(now),
         ..Default::default()
     };
     /* 插入数据 */
-    if let Err(e) = TRole::insert(model).exec(db::conn()).await {
+    if let Err(e) = TRole::insert(model).exec(&state.db).await {
         tracing::error!(error = ?e, "error insert t_role");
         return Err(ApiErr::ErrSystem(None));
     }
@@ -67,97 +92,101 @@ pub async fn create(req: ReqCreate) -> Result<ApiOK<()>> {
 
 
 /** 封装返回数据对象 */
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = role::RespInfo)]
 pub struct RespInfo {
     pub roleid: i64,
     pub rolename: String,
     pub rolecode: String,
     pub remark: String,
+    pub mfa_flag: i16,
+    pub data_scope: i16,
     pub create_time: i64,
     pub create_time_str: String,
 }
 
-/** 返回列表数据对象 */
-#[derive(Debug, Serialize)]
-pub struct RespList {
-    pub total: i64,
-    pub list: Vec<RespInfo>,
-}
-
-/** 获取列表 */
-pub async fn list(query: HashMap<String, String>) -> Result<ApiOK<RespList>> {
-    /** 封装查询条件 */
-    let mut builder = TRole::find();
-    if let Some(rolename) = query.get("rolename") {
-        if !rolename.is_empty() {
-            builder = builder.filter(t_role::Column::RoleName.contains(rolename));
-        }
-    }
 
-    let mut total: i64 = 0;
-    let (offset, limit) = util::query_page(&query);
-    // 仅在第一页计算数量
-    if offset == 0 {
-        total = builder
-            .clone()
-            .select_only()
-            .column_as(t_role::Column::RoleId.count(), "count")
-            .into_tuple::<i64>()
-            .one(db::conn())
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "error count t_role");
-                ApiErr::ErrSystem(None)
-            })?
-            .unwrap_or_default();
-    }
+/** 列表查询条件 */
+const LIST: Spec<t_role::Column> = Spec {
+    filters: &[
+        Filter::like("rolename", t_role::Column::RoleName),
+        Filter::eq("rolecode", t_role::Column::RoleCode, Kind::Str),
+        Filter::eq("data_scope", t_role::Column::DataScope, Kind::Int),
+        Filter::range("create_time", t_role::Column::CreateTime, Kind::Time),
+    ],
+    sorts: &[
+        ("roleid", t_role::Column::RoleId),
+        ("rolename", t_role::Column::RoleName),
+        ("rolecode", t_role::Column::RoleCode),
+        ("create_time", t_role::Column::CreateTime),
+    ],
+    default_sort: &[(t_role::Column::RoleId, Order::Desc)],
+    cursor: None,
+};
 
-    let models = builder
-        .order_by(t_role::Column::RoleId, Order::Desc)
-        .offset(offset)
-        .limit(limit)
-        .all(db::conn())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = ?e, "error find t_role");
-            ApiErr::ErrSystem(None)
-        })?;
-    let mut resp = RespList {
-        total,
-        list: (Vec::with_capacity(models.len())),
-    };
-    for model in models {
-        let info = RespInfo {
-            roleid: model.role_id,
-            rolename: model.role_name,
-            rolecode: model.role_code,
-            remark: model.remark,
-            create_time: model.create_time,
-            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+/** 获取列表 */
+pub async fn list(state: &AppState, query: ListQuery) -> Result<ApiOK<Page<RespInfo>>> {
+    /* 封装查询条件 */
+    let query = LIST.compile(&query)?;
+    let page = query.fetch(&state.db, TRole::find_alive()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find t_role");
+        ApiErr::ErrSystem(None)
+    })?;
+    let resp = page.map(|model| RespInfo {
+        roleid: model.role_id,
+        rolename: model.role_name,
+        rolecode: model.role_code,
+        remark: model.remark,
+        mfa_flag: model.mfa_flag,
+        data_scope: model.data_scope,
+        create_time: model.create_time,
+        create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
             .unwrap_or_default(),
-        };
-        resp.list.push(info);
-    }
+    });
 
     Ok(ApiOK(Some(resp)))
 }
 
+/** 导出表头 */
+const EXPORT_HEADERS: &[&str] = &["角色ID", "角色名称", "角色编码", "数据范围", "二次验证", "备注", "创建时间"];
+
+/** 导出：查询条件同列表，`format` 为 csv 或 xlsx */
+pub async fn export(state: &AppState, mut query: ListQuery) -> Result<Response> {
+    let format = export::Format::parse(query.take("format").as_deref())?;
+    let query = LIST.compile(&query)?;
+    let total = query.total(&state.db, TRole::find_alive()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error count t_role");
+        ApiErr::ErrSystem(None)
+    })?;
+    export::check_rows(total, &state.config)?;
+
+    let batches = query.batches(TRole::find_alive(), t_role::Column::RoleId, export::BATCH_SIZE);
+    let response = export::response(state.db.clone(), batches, format, "roles", EXPORT_HEADERS, |model| {
+        vec![
+            model.role_id.to_string(),
+            model.role_name,
+            model.role_code,
+            scope::label(model.data_scope).to_string(),
+            if model.mfa_flag == 1 { "是" } else { "否" }.to_string(),
+            model.remark,
+            xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8)).unwrap_or_default(),
+        ]
+    });
+
+    Ok(response)
+}
+
 /** 获取详情 */
-pub async fn info(roleid: u64) -> Result<ApiOK<RespInfo>> {
-    let model = TRole::find_by_id(roleid as i64)
-        .one(db::conn())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = ?e, "error find t_role");
-            ApiErr::ErrSystem(None)
-        })?
-        .ok_or(ApiErr::ErrNotFound(Some("角色信息不存在".to_string())))?;
+pub async fn info(state: &AppState, roleid: u64) -> Result<ApiOK<RespInfo>> {
+    let model = find_role(state, roleid as i64).await?;
 
-   let mut resp = RespInfo {
+   let resp = RespInfo {
        roleid: model.role_id,
        rolename: model.role_name,
        rolecode: model.role_code,
        remark: model.remark,
+       mfa_flag: model.mfa_flag,
+       data_scope: model.data_scope,
        create_time: model.create_time,
        create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
        .unwrap_or_default(),
@@ -167,7 +196,8 @@ pub async fn info(roleid: u64) -> Result<ApiOK<RespInfo>> {
 
 
 /** 封装修改数据对象 */
-#[derive(Debug, Validate, Deserialize, Serialize)]
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = role::UpdateInfo)]
 pub struct UpdateInfo {
     pub roleid: i64,
     #[validate(length(min = 1, message = "角色名称必填"))]
@@ -175,15 +205,23 @@ pub struct UpdateInfo {
     #[validate(length(min = 1, message = "角色编码必填"))]
     pub rolecode: String,
     pub remark: String,
+    #[serde(default)]
+    pub mfa_flag: i16,
+    #[serde(default = "default_data_scope")]
+    #[validate(range(min = 1, max = 5, message = "数据范围不正确"))]
+    pub data_scope: i16,
     pub create_time: i64,
     pub create_time_str: String,
 }
 /** 修改方法 */
-pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
+pub async fn update(state: &AppState, req: UpdateInfo) -> Result<ApiOK<()>> {
+    find_role(state, req.roleid).await?;
+
     /* 判断角色名称或角色编码是否重复*/
     let count = TRole::find()
         .filter(Condition::any().add(t_role::Column::RoleName.eq(req.rolename.clone())).add(t_role::Column::RoleCode.eq(req.rolecode.clone())))
-        .count(db::conn())
+        .filter(t_role::Column::RoleId.ne(req.roleid))
+        .count(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error count t_role");
@@ -194,28 +232,34 @@ pub async fn update(req: UpdateInfo) -> Result<ApiOK<()>> {
         return Err(ApiErr::ErrPerm(Some("角色名称或角色编码重复".to_string())));
     }
 
-    let now = xtime::now(offset!(+8)).unix_timestamp();
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
     let model = t_role::ActiveModel {
+        role_id: Set(req.roleid),
         role_name: Set(req.rolename),
         role_code: Set(req.rolecode),
         remark: Set(req.remark),
+        mfa_flag: Set(req.mfa_flag),
+        data_scope: Set(req.data_scope),
         update_time: Set(now),
         ..Default::default()
     };
 
-    if let Err(e) = TRole::update(model).exec(db::conn()).await {
+    if let Err(e) = TRole::update(model).exec(&state.db).await {
         tracing::error!(error = ?e, "error update t_role");
         return Err(ApiErr::ErrSystem(None));
     }
     Ok(ApiOK(None))
 }
 
-/** 删除 */
-pub async fn delete(roleid: u64) -> Result<ApiOK<()>> {
-    /* 判断是否已分配人员 */
+/** 删除（逻辑删除，可在回收站中恢复；角色的功能权限及数据范围保留，永久删除时清除） */
+pub async fn delete(state: &AppState, roleid: u64) -> Result<ApiOK<()>> {
+    find_role(state, roleid as i64).await?;
+
+    /* 判断是否已分配人员（已删除的员工不计） */
     let count = TRoleEmployee::find()
     .filter(t_role_employee::Column::RoleId.eq(roleid))
-    .count(db::conn())
+    .filter(t_role_employee::Column::EmployeeId.in_subquery(TEmployee::alive_ids()))
+    .count(&state.db)
     .await
     .map_err(|e| {
         tracing::error!(error = ?e, "error find t_role_employee");
@@ -225,7 +269,8 @@ pub async fn delete(roleid: u64) -> Result<ApiOK<()>> {
     if count > 0 {
         return Err(ApiErr::ErrPerm(Some("该角色下存在员工，无法删除".to_string())));
     } 
-    if let Err(e) = TRole::delete_by_id(roleid as i64).exec(db::conn()).await {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    if let Err(e) = TRole::soft_delete(&state.db, vec![roleid as i64], now).await {
         tracing::error!(error = ?e, "error delete t_role");
         return Err(ApiErr::ErrSystem(None));
     }
@@ -234,16 +279,17 @@ pub async fn delete(roleid: u64) -> Result<ApiOK<()>> {
 
 
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[schema(as = role::RespSelect)]
 pub struct RespSelect {
     pub roleid: i64,
     pub rolename: String,
 }
 
 
-pub async fn select_list() -> Result<ApiOK<Vec<RespSelect>>> {
-    let models = TRole::find()
-        .all(db::conn())
+pub async fn select_list(state: &AppState) -> Result<ApiOK<Vec<RespSelect>>> {
+    let models = TRole::find_alive()
+        .all(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_role");
@@ -260,7 +306,8 @@ pub async fn select_list() -> Result<ApiOK<Vec<RespSelect>>> {
 }
 
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = role::RespEmpInfo)]
 pub struct RespEmpInfo{
     pub employee_id:i64,
     pub realname:String,
@@ -268,113 +315,85 @@ pub struct RespEmpInfo{
     pub department_id:i64,
     pub login_name: String,
     pub email: String,
-    pub gender:u8,
-    pub disabled_flag:u8,
+    pub gender:i16,
+    pub disabled_flag:i16,
     pub position_id:i64,
     pub create_time:i64,
     pub create_time_str:String,
 }
 
-/** 返回列表数据对象 */
-#[derive(Debug, Serialize)]
-pub struct RespEmpList {
-    pub total: i64,
-    pub list: Vec<RespEmpInfo>,
-}
+
+/** 角色成员列表查询条件，角色ID `roleid` 单独处理 */
+const EMP_LIST: Spec<t_employee::Column> = Spec {
+    filters: &[
+        Filter::like("realname", t_employee::Column::Realname),
+        Filter::like("login_name", t_employee::Column::LoginName),
+        Filter::like("phone", t_employee::Column::Phone),
+    ],
+    sorts: &[
+        ("employee_id", t_employee::Column::EmployeeId),
+        ("login_name", t_employee::Column::LoginName),
+        ("realname", t_employee::Column::Realname),
+        ("create_time", t_employee::Column::CreateTime),
+    ],
+    default_sort: &[(t_employee::Column::EmployeeId, Order::Desc)],
+    cursor: None,
+};
+
 // 根据用户点击的角色id获取该角色下的员工列表
-pub async fn role_emp_list(query: HashMap<String, String>) -> Result<ApiOK<RespEmpList>> {
+pub async fn role_emp_list(state: &AppState, identity: Identity, mut query: ListQuery) -> Result<ApiOK<Page<RespEmpInfo>>> {
     // 获取用户参数中的 roleid
-    let roleid = convert_string_to_i64(query.get("roleid"));
-    
-    let default_roleid = 1;
+    let roleid: i64 = query
+        .take("roleid")
+        .ok_or(ApiErr::ErrParams(Some("请选择角色".to_string())))?
+        .parse()
+        .map_err(|_| ApiErr::ErrParams(Some("角色ID错误".to_string())))?;
+    let query = EMP_LIST.compile(&query)?;
+
     // 根据roleid获取角色员工表里的员工ID列表
-    let emp_id_list = TRoleEmployee::find_by_id(roleid.unwrap_or(default_roleid))
+    let emp_id_list = TRoleEmployee::find()
+        .filter(t_role_employee::Column::RoleId.eq(roleid))
         .select_only()
         .column(t_role_employee::Column::EmployeeId)
-        .all(db::conn())
+        .into_tuple::<i64>()
+        .all(&state.db)
         .await
         .map_err(|e| {
             tracing::error!(error = ?e, "error find t_role_employee");
             ApiErr::ErrSystem(None)
         })?;
-
-    // 将获取的员工ID列表转为Vec<i64>
-    let emp_id_list = emp_id_list.iter().map(|i| i.employee_id).collect::<Vec<_>>();
     
-    // 根据员工ID列表封装到查询条件中
-    let mut builder = TEmployee::find();
-    builder = builder.filter(t_employee::Column::EmployeeId.is_in(emp_id_list));
-
-    // 封装查询条件
-    if let Some(realname) = query.get("realname") {
-        if !realname.is_empty() {
-            builder = builder.filter(t_employee::Column::Realname.contains(realname));
-        }
-
-    }
-    if let Some(login_name) = query.get("login_name") {
-        if !login_name.is_empty() {
-            builder = builder.filter(t_employee::Column::LoginName.contains(login_name));
-        }
-    }
-
-    if let Some(phone) = query.get("phone") {
-        if !phone.is_empty() {
-            builder = builder.filter(t_employee::Column::Phone.contains(phone));  
-        }
-    }
-
-
-
-    let mut total: i64 = 0;
-    let (offset, limit) = util::query_page(&query);
-    // 仅在第一页计算数量
-    if offset == 0 {
-        total = builder
-            .clone()
-            .select_only()
-            .column_as(t_employee::Column::EmployeeId.count(), "count")
-            .into_tuple::<i64>()
-            .one(db::conn())
-            .await
-            .map_err(|e| {
-                tracing::error!(error = ?e, "error count t_employee");
-                ApiErr::ErrSystem(None)
-            })?
-            .unwrap_or_default();
-    }
-
-    let models = builder
-        .order_by(t_employee::Column::EmployeeId, Order::Desc)
-        .offset(offset)
-        .limit(limit)
-        .all(db::conn())
-        .await
-        .map_err(|e| {
-            tracing::error!(error = ?e, "error find t_employee");
-            ApiErr::ErrSystem(None)
-        })?;
-    let mut resp = RespEmpList {
-        total,
-        list: (Vec::with_capacity(models.len())),
-    };
-    for model in models {
-        let info = RespEmpInfo {
-            employee_id: model.employee_id,
-            login_name: model.login_name,
-            realname: model.realname,
-            phone: model.phone,
-            email: model.email,
-            gender: model.gender,
-            disabled_flag: model.disabled_flag,
-            position_id: model.position_id,
-            department_id: model.department_id,
-            create_time: model.create_time,
-            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+    // 根据员工ID列表及数据范围封装到查询条件中
+    let scope = scope::resolve(state, identity.id()).await.map_err(|e| {
+        tracing::error!(error = ?e, "error resolve data scope");
+        ApiErr::ErrSystem(None)
+    })?;
+    let builder = scope
+        .apply(
+            TEmployee::find_alive(),
+            t_employee::Column::DepartmentId,
+            Some(t_employee::Column::EmployeeId),
+        )
+        .filter(t_employee::Column::EmployeeId.is_in(emp_id_list));
+
+    let page = query.fetch(&state.db, builder).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find t_employee");
+        ApiErr::ErrSystem(None)
+    })?;
+    let resp = page.map(|model| RespEmpInfo {
+        employee_id: model.employee_id,
+        login_name: model.login_name,
+        realname: model.realname,
+        phone: model.phone,
+        email: model.email,
+        gender: model.gender,
+        disabled_flag: model.disabled_flag,
+        position_id: model.position_id,
+        department_id: model.department_id,
+        create_time: model.create_time,
+        create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
             .unwrap_or_default(),
-        };
-        resp.list.push(info);
-    }
+    });
 
     Ok(ApiOK(Some(resp)))
 
@@ -382,16 +401,9 @@ pub async fn role_emp_list(query: HashMap<String, String>) -> Result<ApiOK<RespE
 
 
 
-fn convert_string_to_i64(opt_str: Option<&String>) -> Option<i64> {
-    match opt_str {
-        Some(s) => Some(s.parse::<i64>().unwrap_or_default()),
-        None => None
-    }
-}
-
-
 /** 返回列表数据对象 */
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = role::RespMenuSelect)]
 pub struct RespMenuSelect {
     pub menu_id: i64,
     pub menu_name: String,
@@ -399,25 +411,26 @@ pub struct RespMenuSelect {
 }
 
 //查询所有访问资源
-pub async fn menu_list() -> Result<ApiOK<Vec<tree::TreeNode>>> {
-    let menu_list = TMenu::find()
+pub async fn menu_list(state: &AppState) -> Result<ApiOK<Vec<tree::TreeNode>>> {
+    let menu_list = TMenu::find_alive()
     .select_only()
     .column(t_menu::Column::MenuId)
     .column(t_menu::Column::MenuName)
     .column(t_menu::Column::ParentId)
-    .all(db::conn())
+    .into_tuple::<(i64, String, i64)>()
+    .all(&state.db)
     .await
     .map_err(|e| {
-        tracing::error!(error = ?e, "error find t_department");
+        tracing::error!(error = ?e, "error find t_menu");
         ApiErr::ErrSystem(None)
     })?;
 
     let mut list = Vec::new();
-    for menu in &menu_list{
+    for (menu_id, menu_name, parent_id) in menu_list {
         list.push(RespMenuSelect{
-            menu_id:menu.menu_id,
-            menu_name: menu.menu_name.clone(),
-            parent_id: menu.parent_id,
+            menu_id,
+            menu_name,
+            parent_id,
         });
     }
 
@@ -427,20 +440,22 @@ pub async fn menu_list() -> Result<ApiOK<Vec<tree::TreeNode>>> {
 }
 
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = role::RespRoleMenu)]
 pub struct RespRoleMenu {
     pub role_id: i64,
     pub menu_id: i64,
 }
 
 //根据角色查询该角色可以访问的资源ID
-pub async fn role_menu(roleid: i64) -> Result<ApiOK<Vec<RespRoleMenu>>> {
+pub async fn role_menu(state: &AppState, roleid: i64) -> Result<ApiOK<Vec<RespRoleMenu>>> {
     let role_menu = TRoleMenu::find()
     .filter(t_role_menu::Column::RoleId.eq(roleid))
     .select_only()
     .column(t_role_menu::Column::MenuId)
     .column(t_role_menu::Column::RoleId)
-    .all(db::conn())
+    .into_tuple::<(i64, i64)>()
+    .all(&state.db)
     .await
     .map_err(|e| {
         tracing::error!(error = ?e, "error find t_role_menu");
@@ -448,11 +463,249 @@ pub async fn role_menu(roleid: i64) -> Result<ApiOK<Vec<RespRoleMenu>>> {
     })?;
 
     let mut list = Vec::new();
-    for menu in &role_menu{
+    for (menu_id, role_id) in role_menu {
         list.push(RespRoleMenu{
-            role_id: menu.role_id,
-            menu_id: menu.menu_id,
+            role_id,
+            menu_id,
         });
     }
     Ok(ApiOK(Some(list)))
-}
\ No newline at end of file
+}
+
+
+/** 角色菜单分配 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = role::ReqRoleMenu)]
+pub struct ReqRoleMenu {
+    pub role_id: i64,
+    pub menu_ids: Vec<i64>,
+}
+
+// 更新角色的功能权限（整体替换）
+pub async fn update_role_menu(state: &AppState, req: ReqRoleMenu) -> Result<ApiOK<()>> {
+    find_role(state, req.role_id).await?;
+
+    let mut menu_ids = req.menu_ids;
+    menu_ids.sort_unstable();
+    menu_ids.dedup();
+
+    let count = TMenu::find_alive()
+        .filter(t_menu::Column::MenuId.is_in(menu_ids.clone()))
+        .count(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_menu");
+            ApiErr::ErrSystem(None)
+        })?;
+    if count != menu_ids.len() as u64 {
+        return Err(ApiErr::ErrParams(Some("菜单不存在或已删除".to_string())));
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let role_id = req.role_id;
+    let ret = state.db
+        .transaction::<_, (), DbErr>(|txn| {
+            Box::pin(async move {
+                TRoleMenu::delete_many()
+                    .filter(t_role_menu::Column::RoleId.eq(role_id))
+                    .exec(txn)
+                    .await?;
+
+                if !menu_ids.is_empty() {
+                    let models = menu_ids.into_iter().map(|menu_id| t_role_menu::ActiveModel {
+                        role_id: Set(role_id),
+                        menu_id: Set(menu_id),
+                        create_time: Set(now),
+                        update_time: Set(now),
+                        ..Default::default()
+                    });
+                    TRoleMenu::insert_many(models).exec(txn).await?;
+                }
+                Ok(())
+            })
+        })
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error update t_role_menu");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    invalidate_role(state, role_id).await;
+
+    Ok(ApiOK(None))
+}
+
+// 获取角色自定义数据范围的部门ID
+pub async fn role_department(state: &AppState, roleid: i64) -> Result<ApiOK<Vec<i64>>> {
+    let ids = TRoleDepartment::find()
+        .select_only()
+        .column(t_role_department::Column::DepartmentId)
+        .filter(t_role_department::Column::RoleId.eq(roleid))
+        .into_tuple::<i64>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_role_department");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    Ok(ApiOK(Some(ids)))
+}
+
+/** 角色自定义数据范围 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = role::ReqRoleDepartment)]
+pub struct ReqRoleDepartment {
+    pub role_id: i64,
+    pub department_ids: Vec<i64>,
+}
+
+// 更新角色自定义数据范围的部门（整体替换）
+pub async fn update_role_department(state: &AppState, req: ReqRoleDepartment) -> Result<ApiOK<()>> {
+    find_role(state, req.role_id).await?;
+
+    let mut department_ids = req.department_ids;
+    department_ids.sort_unstable();
+    department_ids.dedup();
+
+    let count = TDepartment::find_alive()
+        .filter(t_department::Column::DepartmentId.is_in(department_ids.clone()))
+        .count(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_department");
+            ApiErr::ErrSystem(None)
+        })?;
+    if count != department_ids.len() as u64 {
+        return Err(ApiErr::ErrParams(Some("部门不存在".to_string())));
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let role_id = req.role_id;
+    let ret = state.db
+        .transaction::<_, (), DbErr>(|txn| {
+            Box::pin(async move {
+                TRoleDepartment::delete_many()
+                    .filter(t_role_department::Column::RoleId.eq(role_id))
+                    .exec(txn)
+                    .await?;
+
+                if !department_ids.is_empty() {
+                    let models = department_ids.into_iter().map(|department_id| t_role_department::ActiveModel {
+                        role_id: Set(role_id),
+                        department_id: Set(department_id),
+                        create_time: Set(now),
+                        update_time: Set(now),
+                        ..Default::default()
+                    });
+                    TRoleDepartment::insert_many(models).exec(txn).await?;
+                }
+                Ok(())
+            })
+        })
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error update t_role_department");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    Ok(ApiOK(None))
+}
+
+/** 角色员工分配 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = role::ReqRoleEmp)]
+pub struct ReqRoleEmp {
+    pub role_id: i64,
+    #[validate(length(min = 1, message = "请选择员工"))]
+    pub employee_ids: Vec<i64>,
+}
+
+// 批量添加角色员工
+pub async fn add_role_emp(state: &AppState, req: ReqRoleEmp) -> Result<ApiOK<()>> {
+    find_role(state, req.role_id).await?;
+
+    let mut employee_ids = req.employee_ids;
+    employee_ids.sort_unstable();
+    employee_ids.dedup();
+
+    let count = TEmployee::find_alive()
+        .filter(t_employee::Column::EmployeeId.is_in(employee_ids.clone()))
+        .count(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_employee");
+            ApiErr::ErrSystem(None)
+        })?;
+    if count != employee_ids.len() as u64 {
+        return Err(ApiErr::ErrParams(Some("员工不存在或已删除".to_string())));
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let models = employee_ids.iter().map(|employee_id| t_role_employee::ActiveModel {
+        role_id: Set(req.role_id),
+        employee_id: Set(*employee_id),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    });
+    // 单条批量插入语句，任一员工重复则全部不生效
+    if let Err(e) = TRoleEmployee::insert_many(models).exec(&state.db).await {
+        if let Some(SqlErr::UniqueConstraintViolation(_)) = e.sql_err() {
+            return Err(ApiErr::ErrParams(Some("员工已分配该角色，请勿重复添加".to_string())));
+        }
+        tracing::error!(error = ?e, "error insert t_role_employee");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    perm::invalidate(state, &employee_ids);
+
+    Ok(ApiOK(None))
+}
+
+// 批量移除角色员工
+pub async fn remove_role_emp(state: &AppState, req: ReqRoleEmp) -> Result<ApiOK<()>> {
+    let ret = TRoleEmployee::delete_many()
+        .filter(t_role_employee::Column::RoleId.eq(req.role_id))
+        .filter(t_role_employee::Column::EmployeeId.is_in(req.employee_ids.clone()))
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error delete t_role_employee");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    perm::invalidate(state, &req.employee_ids);
+
+    Ok(ApiOK(None))
+}
+
+async fn find_role(state: &AppState, role_id: i64) -> Result<t_role::Model> {
+    TRole::find_alive()
+        .filter(t_role::Column::RoleId.eq(role_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_role");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("角色信息不存在".to_string())))
+}
+
+// 清除角色下所有员工的权限缓存
+async fn invalidate_role(state: &AppState, role_id: i64) {
+    let ret = TRoleEmployee::find()
+        .select_only()
+        .column(t_role_employee::Column::EmployeeId)
+        .filter(t_role_employee::Column::RoleId.eq(role_id))
+        .into_tuple::<i64>()
+        .all(&state.db)
+        .await;
+    match ret {
+        Ok(v) => perm::invalidate(state, &v),
+        Err(e) => {
+            tracing::error!(error = ?e, "error find t_role_employee");
+            perm::invalidate_all(state);
+        }
+    }
+}
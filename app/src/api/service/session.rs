@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect, Set};
+use serde::Serialize;
+use utoipa::ToSchema;
+use time::macros::offset;
+
+use pkg::{
+    identity::{self, Identity},
+    query::{Filter, Kind, ListQuery, Spec},
+    result::{
+        page::Page,
+        response::{ApiErr, ApiOK, Result},
+    },
+    state::AppState,
+    util::ClientInfo,
+    xtime,
+};
+
+use crate::ent::{
+    prelude::{TEmployee, TLoginSession, TRefreshToken},
+    t_employee, t_login_session, t_refresh_token,
+};
+
+// 最近活跃时间的刷新间隔（秒），避免每次请求都写库
+const TOUCH_INTERVAL: i64 = 60;
+
+/** 封装返回数据对象 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = session::RespInfo)]
+pub struct RespInfo {
+    pub session_id: i64,
+    pub employee_id: i64,
+    pub login_name: String,
+    pub realname: String,
+    pub device: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub current: bool,
+    pub last_seen_time: i64,
+    pub last_seen_time_str: String,
+    pub create_time: i64,
+    pub create_time_str: String,
+}
+
+impl From<t_login_session::Model> for RespInfo {
+    fn from(model: t_login_session::Model) -> Self {
+        Self {
+            session_id: model.session_id,
+            employee_id: model.employee_id,
+            login_name: String::new(),
+            realname: String::new(),
+            device: model.device,
+            ip: model.ip,
+            user_agent: model.user_agent,
+            current: false,
+            last_seen_time: model.last_seen_time,
+            last_seen_time_str: xtime::to_string(xtime::DATETIME, model.last_seen_time, offset!(+8))
+                .unwrap_or_default(),
+            create_time: model.create_time,
+            create_time_str: xtime::to_string(xtime::DATETIME, model.create_time, offset!(+8))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/** 在线用户列表查询条件 */
+const LIST: Spec<t_login_session::Column> = Spec {
+    filters: &[
+        Filter::eq("employee_id", t_login_session::Column::EmployeeId, Kind::Int),
+        Filter::like("ip", t_login_session::Column::Ip),
+    ],
+    sorts: &[
+        ("last_seen_time", t_login_session::Column::LastSeenTime),
+        ("create_time", t_login_session::Column::CreateTime),
+    ],
+    default_sort: &[
+        (t_login_session::Column::LastSeenTime, Order::Desc),
+        (t_login_session::Column::SessionId, Order::Desc),
+    ],
+    cursor: None,
+};
+
+/**
+ * 创建登录会话；超出最大会话数时，踢出最早活跃的会话
+ */
+pub async fn create(
+    state: &AppState,
+    employee_id: i64,
+    login_token: &str,
+    device: Option<String>,
+    client: &ClientInfo,
+) -> Result<()> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+
+    // 清理已过期的会话
+    let ret = TLoginSession::delete_many()
+        .filter(t_login_session::Column::EmployeeId.eq(employee_id))
+        .filter(t_login_session::Column::ExpireTime.lte(now))
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error delete t_login_session");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    let max_sessions = identity::max_sessions(&state.config);
+    if max_sessions > 0 {
+        let tokens = TLoginSession::find()
+            .select_only()
+            .column(t_login_session::Column::LoginToken)
+            .filter(t_login_session::Column::EmployeeId.eq(employee_id))
+            .order_by(t_login_session::Column::LastSeenTime, Order::Asc)
+            .order_by(t_login_session::Column::SessionId, Order::Asc)
+            .into_tuple::<String>()
+            .all(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "error find t_login_session");
+                ApiErr::ErrSystem(None)
+            })?;
+        let evict = (tokens.len() as i64 + 1 - max_sessions).max(0) as usize;
+        if evict > 0 {
+            revoke(state, tokens.into_iter().take(evict).collect()).await?;
+        }
+    }
+
+    let user_agent: String = client.user_agent.chars().take(500).collect();
+    let device = match device {
+        Some(v) if !v.trim().is_empty() => v.trim().chars().take(100).collect(),
+        _ => device_name(&user_agent),
+    };
+    let model = t_login_session::ActiveModel {
+        employee_id: Set(employee_id),
+        login_token: Set(login_token.to_string()),
+        device: Set(device),
+        ip: Set(client.ip.clone()),
+        user_agent: Set(user_agent),
+        last_seen_time: Set(now),
+        expire_time: Set(now + identity::refresh_ttl(&state.config)),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    };
+    if let Err(e) = TLoginSession::insert(model).exec(&state.db).await {
+        tracing::error!(error = ?e, "error insert t_login_session");
+        return Err(ApiErr::ErrSystem(None));
+    }
+    Ok(())
+}
+
+/**
+ * 校验请求所属的登录会话，并更新最近活跃时间
+ */
+pub async fn check(state: &AppState, identity: &Identity) -> anyhow::Result<()> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = TLoginSession::find()
+        .filter(t_login_session::Column::LoginToken.eq(identity.token()))
+        .one(&state.db)
+        .await?;
+    let model = match model {
+        Some(v) if v.employee_id == identity.id() && v.expire_time > now => v,
+        _ => return Err(anyhow!("授权已失效")),
+    };
+
+    if now - model.last_seen_time >= TOUCH_INTERVAL {
+        TLoginSession::update_many()
+            .col_expr(t_login_session::Column::LastSeenTime, Expr::value(now))
+            .filter(t_login_session::Column::SessionId.eq(model.session_id))
+            .exec(&state.db)
+            .await?;
+    }
+    Ok(())
+}
+
+/** 根据会话token获取有效的登录会话 */
+pub async fn find(state: &AppState, login_token: &str) -> Result<Option<t_login_session::Model>> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = TLoginSession::find()
+        .filter(t_login_session::Column::LoginToken.eq(login_token))
+        .filter(t_login_session::Column::ExpireTime.gt(now))
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_login_session");
+            ApiErr::ErrSystem(None)
+        })?;
+    Ok(model)
+}
+
+/** 刷新令牌后延长会话有效期 */
+pub async fn renew(state: &AppState, session_id: i64) -> Result<()> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let ret = TLoginSession::update_many()
+        .col_expr(t_login_session::Column::LastSeenTime, Expr::value(now))
+        .col_expr(
+            t_login_session::Column::ExpireTime,
+            Expr::value(now + identity::refresh_ttl(&state.config)),
+        )
+        .col_expr(t_login_session::Column::UpdateTime, Expr::value(now))
+        .filter(t_login_session::Column::SessionId.eq(session_id))
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error update t_login_session");
+        return Err(ApiErr::ErrSystem(None));
+    }
+    Ok(())
+}
+
+/**
+ * 撤销登录会话：删除会话，并撤销其下全部刷新令牌
+ */
+pub async fn revoke(state: &AppState, login_tokens: Vec<String>) -> Result<()> {
+    if login_tokens.is_empty() {
+        return Ok(());
+    }
+
+    let ret = TRefreshToken::update_many()
+        .col_expr(t_refresh_token::Column::RevokedFlag, Expr::value(1))
+        .col_expr(
+            t_refresh_token::Column::UpdateTime,
+            Expr::value(state.clock.now(offset!(+8)).unix_timestamp()),
+        )
+        .filter(t_refresh_token::Column::LoginToken.is_in(login_tokens.clone()))
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error update t_refresh_token");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    let ret = TLoginSession::delete_many()
+        .filter(t_login_session::Column::LoginToken.is_in(login_tokens))
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error delete t_login_session");
+        return Err(ApiErr::ErrSystem(None));
+    }
+    Ok(())
+}
+
+/** 当前用户的登录会话列表 */
+pub async fn my_list(state: &AppState, identity: Identity) -> Result<ApiOK<Vec<RespInfo>>> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let models = TLoginSession::find()
+        .filter(t_login_session::Column::EmployeeId.eq(identity.id()))
+        .filter(t_login_session::Column::ExpireTime.gt(now))
+        .order_by(t_login_session::Column::LastSeenTime, Order::Desc)
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_login_session");
+            ApiErr::ErrSystem(None)
+        })?;
+
+    let resp = models
+        .into_iter()
+        .map(|v| {
+            let current = v.login_token == identity.token();
+            RespInfo {
+                current,
+                ..RespInfo::from(v)
+            }
+        })
+        .collect();
+
+    Ok(ApiOK(Some(resp)))
+}
+
+/** 撤销当前用户的某个登录会话 */
+pub async fn my_revoke(state: &AppState, identity: Identity, session_id: i64) -> Result<ApiOK<()>> {
+    let model = find_session(state, session_id).await?;
+    if model.employee_id != identity.id() {
+        return Err(ApiErr::ErrNotFound(Some("登录会话不存在".to_string())));
+    }
+    revoke(state, vec![model.login_token]).await?;
+
+    Ok(ApiOK(None))
+}
+
+/** 在线用户列表 */
+pub async fn list(state: &AppState, query: ListQuery) -> Result<ApiOK<Page<RespInfo>>> {
+    let query = LIST.compile(&query)?;
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let builder = TLoginSession::find().filter(t_login_session::Column::ExpireTime.gt(now));
+    let page = query.fetch(&state.db, builder).await.map_err(|e| {
+        tracing::error!(error = ?e, "error find t_login_session");
+        ApiErr::ErrSystem(None)
+    })?;
+
+    /* 员工信息 */
+    let employee_ids: Vec<i64> = page.list.iter().map(|v| v.employee_id).collect();
+    let employees: HashMap<i64, t_employee::Model> = TEmployee::find()
+        .filter(t_employee::Column::EmployeeId.is_in(employee_ids))
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .into_iter()
+        .map(|v| (v.employee_id, v))
+        .collect();
+
+    let resp = page.map(|v| {
+        let mut info = RespInfo::from(v);
+        if let Some(employee) = employees.get(&info.employee_id) {
+            info.login_name = employee.login_name.clone();
+            info.realname = employee.realname.clone();
+        }
+        info
+    });
+
+    Ok(ApiOK(Some(resp)))
+}
+
+/** 强制下线某个登录会话 */
+pub async fn kick(state: &AppState, session_id: i64) -> Result<ApiOK<()>> {
+    let model = find_session(state, session_id).await?;
+    revoke(state, vec![model.login_token]).await?;
+
+    Ok(ApiOK(None))
+}
+
+/** 强制下线员工的全部登录会话 */
+pub async fn kick_employee(state: &AppState, employee_id: i64) -> Result<ApiOK<()>> {
+    let tokens = TLoginSession::find()
+        .select_only()
+        .column(t_login_session::Column::LoginToken)
+        .filter(t_login_session::Column::EmployeeId.eq(employee_id))
+        .into_tuple::<String>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_login_session");
+            ApiErr::ErrSystem(None)
+        })?;
+    revoke(state, tokens).await?;
+
+    Ok(ApiOK(None))
+}
+
+async fn find_session(state: &AppState, session_id: i64) -> Result<t_login_session::Model> {
+    TLoginSession::find_by_id(session_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_login_session");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("登录会话不存在".to_string())))
+}
+
+// 根据 User-Agent 粗略识别登录设备
+fn device_name(user_agent: &str) -> String {
+    let os = [
+        ("iPhone", "iPhone"),
+        ("iPad", "iPad"),
+        ("Android", "Android"),
+        ("Windows", "Windows"),
+        ("Mac OS", "macOS"),
+        ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(k, _)| user_agent.contains(k))
+    .map(|(_, v)| *v);
+    let browser = [
+        ("Edg/", "Edge"),
+        ("MicroMessenger", "WeChat"),
+        ("Chrome/", "Chrome"),
+        ("Firefox/", "Firefox"),
+        ("Safari/", "Safari"),
+    ]
+    .iter()
+    .find(|(k, _)| user_agent.contains(k))
+    .map(|(_, v)| *v);
+
+    match (os, browser) {
+        (Some(os), Some(browser)) => format!("{} {}", os, browser),
+        (Some(v), None) | (None, Some(v)) => v.to_string(),
+        (None, None) => String::from("未知设备"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::device_name;
+
+    #[test]
+    fn parse_device() {
+        assert_eq!(
+            device_name("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"),
+            "Windows Chrome"
+        );
+        assert_eq!(
+            device_name("Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1"),
+            "iPhone Safari"
+        );
+        assert_eq!(device_name("curl/8.0"), "未知设备");
+    }
+}
@@ -0,0 +1,356 @@
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use time::macros::offset;
+use validator::Validate;
+
+use pkg::crypto::{hash::sha256, totp};
+use pkg::{
+    identity::Identity,
+    result::response::{ApiErr, ApiOK, Result},
+    soft_delete::SoftDelete,
+    state::AppState,
+    util,
+};
+
+use crate::ent::{
+    prelude::{TEmployee, TEmployeeTotp, TRole, TRoleEmployee},
+    t_employee_totp, t_role, t_role_employee,
+};
+
+// 恢复码数量
+const RECOVERY_CODES: usize = 10;
+
+/** 验证码输入参数 */
+#[derive(Debug, Validate, Deserialize, Serialize, ToSchema)]
+#[schema(as = totp::ReqCode)]
+pub struct ReqCode {
+    #[validate(length(min = 1, message = "验证码必填"))]
+    pub code: String,
+}
+
+/** 两步验证状态 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = totp::RespStatus)]
+pub struct RespStatus {
+    pub enabled: bool,
+    pub required: bool,
+    pub recovery_codes_left: usize,
+}
+
+/** 绑定认证器所需信息 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = totp::RespSetup)]
+pub struct RespSetup {
+    pub secret: String,
+    pub uri: String,
+}
+
+/** 恢复码，仅在生成时返回一次 */
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = totp::RespRecovery)]
+pub struct RespRecovery {
+    pub recovery_codes: Vec<String>,
+}
+
+/** 获取员工的两步验证信息 */
+pub async fn find(state: &AppState, employee_id: i64) -> Result<Option<t_employee_totp::Model>> {
+    TEmployeeTotp::find_by_id(employee_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee_totp");
+            ApiErr::ErrSystem(None)
+        })
+}
+
+/** 员工所属角色是否要求开启两步验证 */
+pub async fn required(state: &AppState, employee_id: i64) -> Result<bool> {
+    let role_ids = TRoleEmployee::find()
+        .select_only()
+        .column(t_role_employee::Column::RoleId)
+        .filter(t_role_employee::Column::EmployeeId.eq(employee_id))
+        .filter(t_role_employee::Column::RoleId.in_subquery(TRole::alive_ids()))
+        .into_tuple::<i64>()
+        .all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_role_employee");
+            ApiErr::ErrSystem(None)
+        })?;
+    if role_ids.is_empty() {
+        return Ok(false);
+    }
+
+    let count = TRole::find()
+        .select_only()
+        .column_as(t_role::Column::RoleId.count(), "count")
+        .filter(t_role::Column::RoleId.is_in(role_ids))
+        .filter(t_role::Column::MfaFlag.eq(1))
+        .into_tuple::<i64>()
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error count t_role");
+            ApiErr::ErrSystem(None)
+        })?
+        .unwrap_or_default();
+    Ok(count > 0)
+}
+
+/** 认证器中显示的绑定信息 */
+pub fn provisioning(state: &AppState, login_name: &str, secret: &str) -> RespSetup {
+    let issuer = state.config
+        .get_string("auth.totp_issuer")
+        .unwrap_or(String::from("rs-admin"));
+    RespSetup {
+        secret: secret.to_string(),
+        uri: totp::provisioning_uri(&issuer, login_name, secret),
+    }
+}
+
+/**
+ * 校验动态验证码（已使用的时间步不可重复使用）或恢复码（使用后作废）
+ */
+pub async fn check_code(state: &AppState, model: &t_employee_totp::Model, code: &str) -> Result<bool> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let step = totp::verify(&model.secret, code, now).map_err(|e| {
+        tracing::error!(error = ?e, "error verify totp");
+        ApiErr::ErrSystem(None)
+    })?;
+    if let Some(step) = step {
+        let ret = TEmployeeTotp::update_many()
+            .col_expr(t_employee_totp::Column::LastStep, Expr::value(step))
+            .col_expr(t_employee_totp::Column::UpdateTime, Expr::value(now))
+            .filter(t_employee_totp::Column::EmployeeId.eq(model.employee_id))
+            .filter(t_employee_totp::Column::LastStep.lt(step))
+            .exec(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = ?e, "error update t_employee_totp");
+                ApiErr::ErrSystem(None)
+            })?;
+        return Ok(ret.rows_affected > 0);
+    }
+
+    // 恢复码
+    let hashed = hash_recovery_code(code);
+    let codes: Vec<&str> = model
+        .recovery_codes
+        .split(',')
+        .filter(|v| !v.is_empty())
+        .collect();
+    if !codes.contains(&hashed.as_str()) {
+        return Ok(false);
+    }
+    let left = codes
+        .into_iter()
+        .filter(|v| *v != hashed)
+        .collect::<Vec<_>>()
+        .join(",");
+    let ret = TEmployeeTotp::update_many()
+        .col_expr(t_employee_totp::Column::RecoveryCodes, Expr::value(left))
+        .col_expr(t_employee_totp::Column::UpdateTime, Expr::value(now))
+        .filter(t_employee_totp::Column::EmployeeId.eq(model.employee_id))
+        .filter(t_employee_totp::Column::RecoveryCodes.eq(model.recovery_codes.as_str()))
+        .exec(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error update t_employee_totp");
+            ApiErr::ErrSystem(None)
+        })?;
+    Ok(ret.rows_affected > 0)
+}
+
+/**
+ * 启用两步验证：保存密钥并生成恢复码
+ */
+pub async fn enable_with(state: &AppState, employee_id: i64, secret: &str, code: &str) -> Result<Vec<String>> {
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let step = totp::verify(secret, code, now)
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error verify totp");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrParams(Some("验证码错误".to_string())))?;
+
+    let (codes, hashed) = recovery_codes();
+    let model = t_employee_totp::ActiveModel {
+        employee_id: Set(employee_id),
+        secret: Set(secret.to_string()),
+        enabled_flag: Set(1),
+        recovery_codes: Set(hashed),
+        last_step: Set(step),
+        create_time: Set(now),
+        update_time: Set(now),
+    };
+    save(state, model).await?;
+
+    Ok(codes)
+}
+
+/** 当前用户的两步验证状态 */
+pub async fn status(state: &AppState, identity: Identity) -> Result<ApiOK<RespStatus>> {
+    let model = find(state, identity.id()).await?;
+    let resp = RespStatus {
+        enabled: model.as_ref().is_some_and(|v| v.enabled_flag == 1),
+        required: required(state, identity.id()).await?,
+        recovery_codes_left: model
+            .filter(|v| v.enabled_flag == 1)
+            .map(|v| v.recovery_codes.split(',').filter(|v| !v.is_empty()).count())
+            .unwrap_or_default(),
+    };
+    Ok(ApiOK(Some(resp)))
+}
+
+/** 生成密钥，待验证后启用 */
+pub async fn setup(state: &AppState, identity: Identity) -> Result<ApiOK<RespSetup>> {
+    if find(state, identity.id()).await?.is_some_and(|v| v.enabled_flag == 1) {
+        return Err(ApiErr::ErrParams(Some("两步验证已开启".to_string())));
+    }
+    let employee = TEmployee::find_by_id(identity.id())
+        .one(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = ?e, "error find t_employee");
+            ApiErr::ErrSystem(None)
+        })?
+        .ok_or(ApiErr::ErrNotFound(Some("员工信息不存在".to_string())))?;
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let secret = totp::generate_secret();
+    let model = t_employee_totp::ActiveModel {
+        employee_id: Set(identity.id()),
+        secret: Set(secret.clone()),
+        enabled_flag: Set(0),
+        recovery_codes: Set(String::new()),
+        last_step: Set(0),
+        create_time: Set(now),
+        update_time: Set(now),
+    };
+    save(state, model).await?;
+
+    Ok(ApiOK(Some(provisioning(state, &employee.login_name, &secret))))
+}
+
+/** 验证动态码并启用 */
+pub async fn enable(state: &AppState, identity: Identity, req: ReqCode) -> Result<ApiOK<RespRecovery>> {
+    let model = find(state, identity.id())
+        .await?
+        .filter(|v| v.enabled_flag == 0)
+        .ok_or(ApiErr::ErrParams(Some("请先获取两步验证密钥".to_string())))?;
+    let recovery_codes = enable_with(state, identity.id(), &model.secret, &req.code).await?;
+
+    Ok(ApiOK(Some(RespRecovery { recovery_codes })))
+}
+
+/** 关闭两步验证 */
+pub async fn disable(state: &AppState, identity: Identity, req: ReqCode) -> Result<ApiOK<()>> {
+    if required(state, identity.id()).await? {
+        return Err(ApiErr::ErrParams(Some("所属角色要求开启两步验证，无法关闭".to_string())));
+    }
+    let model = enabled(state, identity.id()).await?;
+    if !check_code(state, &model, &req.code).await? {
+        return Err(ApiErr::ErrParams(Some("验证码错误".to_string())));
+    }
+    delete(state, identity.id()).await?;
+
+    Ok(ApiOK(None))
+}
+
+/** 重新生成恢复码，原恢复码作废 */
+pub async fn regenerate(state: &AppState, identity: Identity, req: ReqCode) -> Result<ApiOK<RespRecovery>> {
+    let model = enabled(state, identity.id()).await?;
+    if !check_code(state, &model, &req.code).await? {
+        return Err(ApiErr::ErrParams(Some("验证码错误".to_string())));
+    }
+
+    let (recovery_codes, hashed) = recovery_codes();
+    let ret = TEmployeeTotp::update_many()
+        .col_expr(t_employee_totp::Column::RecoveryCodes, Expr::value(hashed))
+        .col_expr(
+            t_employee_totp::Column::UpdateTime,
+            Expr::value(state.clock.now(offset!(+8)).unix_timestamp()),
+        )
+        .filter(t_employee_totp::Column::EmployeeId.eq(identity.id()))
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error update t_employee_totp");
+        return Err(ApiErr::ErrSystem(None));
+    }
+
+    Ok(ApiOK(Some(RespRecovery { recovery_codes })))
+}
+
+/** 重置员工的两步验证（管理员），员工下次登录时重新绑定 */
+pub async fn reset(state: &AppState, employee_id: i64) -> Result<ApiOK<()>> {
+    delete(state, employee_id).await?;
+    Ok(ApiOK(None))
+}
+
+async fn enabled(state: &AppState, employee_id: i64) -> Result<t_employee_totp::Model> {
+    find(state, employee_id)
+        .await?
+        .filter(|v| v.enabled_flag == 1)
+        .ok_or(ApiErr::ErrParams(Some("两步验证未开启".to_string())))
+}
+
+async fn save(state: &AppState, model: t_employee_totp::ActiveModel) -> Result<()> {
+    let ret = TEmployeeTotp::insert(model)
+        .on_conflict(
+            OnConflict::column(t_employee_totp::Column::EmployeeId)
+                .update_columns([
+                    t_employee_totp::Column::Secret,
+                    t_employee_totp::Column::EnabledFlag,
+                    t_employee_totp::Column::RecoveryCodes,
+                    t_employee_totp::Column::LastStep,
+                    t_employee_totp::Column::UpdateTime,
+                ])
+                .to_owned(),
+        )
+        .exec(&state.db)
+        .await;
+    if let Err(e) = ret {
+        tracing::error!(error = ?e, "error save t_employee_totp");
+        return Err(ApiErr::ErrSystem(None));
+    }
+    Ok(())
+}
+
+async fn delete(state: &AppState, employee_id: i64) -> Result<()> {
+    if let Err(e) = TEmployeeTotp::delete_by_id(employee_id)
+        .exec(&state.db)
+        .await
+    {
+        tracing::error!(error = ?e, "error delete t_employee_totp");
+        return Err(ApiErr::ErrSystem(None));
+    }
+    Ok(())
+}
+
+// 生成恢复码，返回明文及其哈希（逗号分隔）
+fn recovery_codes() -> (Vec<String>, String) {
+    let codes: Vec<String> = (0..RECOVERY_CODES)
+        .map(|_| {
+            let v = util::nonce(10).to_lowercase();
+            format!("{}-{}", &v[..5], &v[5..])
+        })
+        .collect();
+    let hashed = codes
+        .iter()
+        .map(|v| hash_recovery_code(v))
+        .collect::<Vec<_>>()
+        .join(",");
+    (codes, hashed)
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let code: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    sha256(code.as_bytes())
+}
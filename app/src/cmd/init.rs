@@ -0,0 +1,330 @@
+use std::io::IsTerminal;
+
+use anyhow::{anyhow, Result};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use time::macros::offset;
+
+use pkg::state::AppState;
+use pkg::soft_delete::SoftDelete;
+
+use crate::api::service::menu::{MENU_TYPE_CATALOG, MENU_TYPE_MENU, MENU_TYPE_POINTS};
+use crate::api::{router, scope};
+use crate::ent::{
+    prelude::{TDepartment, TEmployee, TMenu, TRole, TRoleEmployee, TRoleMenu},
+    t_department, t_employee, t_menu, t_role, t_role_employee, t_role_menu,
+};
+
+// 未通过命令行输入时，从该环境变量读取管理员密码
+const PASSWORD_ENV: &str = "INIT_ADMIN_PASSWORD";
+
+const ROLE_CODE: &str = "admin";
+
+// 内置菜单：(模块, 菜单名称, 路由地址, 组件路径, 图标)，功能点由路由表中 `system:<模块>:*` 权限标识生成
+const MENUS: &[(&str, &str, &str, &str, &str)] = &[
+    ("department", "部门管理", "/system/department", "/system/department/index.vue", "ApartmentOutlined"),
+    ("position", "职务管理", "/system/position", "/system/position/index.vue", "SolutionOutlined"),
+    ("employee", "员工管理", "/system/employee", "/system/employee/index.vue", "UserOutlined"),
+    ("role", "角色管理", "/system/role", "/system/role/index.vue", "TeamOutlined"),
+    ("menu", "菜单管理", "/system/menu", "/system/menu/index.vue", "MenuOutlined"),
+    ("session", "在线用户", "/system/session", "/system/session/index.vue", "WifiOutlined"),
+    ("operate_log", "操作记录", "/system/operate_log", "/system/operate_log/index.vue", "FileSearchOutlined"),
+    ("recycle_bin", "回收站", "/system/recycle_bin", "/system/recycle_bin/index.vue", "DeleteOutlined"),
+];
+
+// 功能点名称
+const POINTS: &[(&str, &str)] = &[
+    ("query", "查询"),
+    ("add", "新增"),
+    ("update", "编辑"),
+    ("delete", "删除"),
+    ("export", "导出"),
+    ("import", "导入"),
+    ("disabled", "启用/禁用"),
+    ("password:reset", "重置密码"),
+    ("unlock", "解除登录锁定"),
+    ("totp:reset", "重置两步验证"),
+    ("department:update", "调整部门"),
+    ("employee:query", "查询成员"),
+    ("employee:update", "分配成员"),
+    ("menu:query", "查询功能权限"),
+    ("menu:update", "分配功能权限"),
+    ("scope:query", "查询数据范围"),
+    ("scope:update", "设置数据范围"),
+    ("kick", "强制下线"),
+    ("restore", "恢复"),
+    ("purge", "永久删除"),
+];
+
+/// 初始化：执行迁移，创建超级管理员、管理员角色及内置菜单（可重复执行，已存在的数据不会修改）
+pub async fn exec(state: &AppState, login_name: String, realname: String) {
+    if let Err(e) = run(state, &login_name, &realname).await {
+        panic!("初始化失败：{}", e);
+    }
+}
+
+async fn run(state: &AppState, login_name: &str, realname: &str) -> Result<()> {
+    Migrator::up(&state.db, None).await?;
+
+    let department_id = department(state).await?;
+    let employee_id = administrator(state, login_name, realname, department_id).await?;
+    let role_id = role(state).await?;
+    let menu_ids = menus(state).await?;
+
+    /* 角色分配菜单 */
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let exists: Vec<i64> = TRoleMenu::find()
+        .filter(t_role_menu::Column::RoleId.eq(role_id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|v| v.menu_id)
+        .collect();
+    let models: Vec<t_role_menu::ActiveModel> = menu_ids
+        .into_iter()
+        .filter(|id| !exists.contains(id))
+        .map(|menu_id| t_role_menu::ActiveModel {
+            role_id: Set(role_id),
+            menu_id: Set(menu_id),
+            create_time: Set(now),
+            update_time: Set(now),
+            ..Default::default()
+        })
+        .collect();
+    if !models.is_empty() {
+        TRoleMenu::insert_many(models).exec(&state.db).await?;
+    }
+
+    /* 管理员分配角色 */
+    let linked = TRoleEmployee::find()
+        .filter(t_role_employee::Column::RoleId.eq(role_id))
+        .filter(t_role_employee::Column::EmployeeId.eq(employee_id))
+        .one(&state.db)
+        .await?;
+    if linked.is_none() {
+        let model = t_role_employee::ActiveModel {
+            role_id: Set(role_id),
+            employee_id: Set(employee_id),
+            create_time: Set(now),
+            update_time: Set(now),
+            ..Default::default()
+        };
+        TRoleEmployee::insert(model).exec(&state.db).await?;
+    }
+
+    println!("初始化完成");
+    Ok(())
+}
+
+// 顶级部门，不存在时创建
+async fn department(state: &AppState) -> Result<i64> {
+    let ret = TDepartment::find()
+        .filter(t_department::Column::ParentId.eq(0))
+        .order_by_asc(t_department::Column::DepartmentId)
+        .one(&state.db)
+        .await?;
+    if let Some(v) = ret {
+        return Ok(v.department_id);
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = t_department::ActiveModel {
+        department_name: Set(String::from("总部")),
+        parent_id: Set(0),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    };
+    let ret = TDepartment::insert(model).exec(&state.db).await?;
+    Ok(ret.last_insert_id)
+}
+
+// 超级管理员，已存在时不修改
+async fn administrator(state: &AppState, login_name: &str, realname: &str, department_id: i64) -> Result<i64> {
+    let ret = TEmployee::find()
+        .filter(t_employee::Column::LoginName.eq(login_name))
+        .one(&state.db)
+        .await?;
+    if let Some(v) = ret {
+        println!("管理员 {} 已存在，跳过", login_name);
+        return Ok(v.employee_id);
+    }
+
+    let (pwd, generated) = read_password(state, login_name)?;
+    let login_pwd = state.hasher.hash(&pwd)?;
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = t_employee::ActiveModel {
+        login_name: Set(login_name.to_string()),
+        login_pwd: Set(login_pwd),
+        realname: Set(realname.to_string()),
+        department_id: Set(department_id),
+        administrator_flag: Set(1),
+        // 自动生成的密码首次登录需修改
+        must_change_password: Set(generated as i16),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    };
+    let ret = TEmployee::insert(model).exec(&state.db).await?;
+
+    if generated {
+        println!("管理员 {} 已创建，初始密码：{}（首次登录需修改）", login_name, pwd);
+    } else {
+        println!("管理员 {} 已创建", login_name);
+    }
+    Ok(ret.last_insert_id)
+}
+
+// 管理员密码：优先读取环境变量，其次在终端中输入，均没有时自动生成
+fn read_password(state: &AppState, login_name: &str) -> Result<(String, bool)> {
+    let pwd = match std::env::var(PASSWORD_ENV) {
+        Ok(v) if !v.is_empty() => v,
+        _ => {
+            if !std::io::stdin().is_terminal() {
+                return Ok((state.policy.generate(), true));
+            }
+            let pwd = rpassword::prompt_password(format!("请输入管理员 {} 的密码（留空自动生成）：", login_name))?;
+            if pwd.is_empty() {
+                return Ok((state.policy.generate(), true));
+            }
+            if rpassword::prompt_password("请再次输入密码：")? != pwd {
+                return Err(anyhow!("两次输入的密码不一致"));
+            }
+            pwd
+        }
+    };
+    state.policy
+        .validate(&pwd, login_name)
+        .map_err(|e| anyhow!(e))?;
+    Ok((pwd, false))
+}
+
+// 管理员角色，不存在时创建
+async fn role(state: &AppState) -> Result<i64> {
+    let ret = TRole::find()
+        .filter(t_role::Column::RoleCode.eq(ROLE_CODE))
+        .one(&state.db)
+        .await?;
+    if let Some(v) = ret {
+        return Ok(v.role_id);
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = t_role::ActiveModel {
+        role_name: Set(String::from("系统管理员")),
+        role_code: Set(String::from(ROLE_CODE)),
+        remark: Set(String::from("系统初始化创建")),
+        data_scope: Set(scope::ALL),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    };
+    let ret = TRole::insert(model).exec(&state.db).await?;
+    Ok(ret.last_insert_id)
+}
+
+// 内置菜单，返回全部菜单ID（含已存在的）
+async fn menus(state: &AppState) -> Result<Vec<i64>> {
+    let mut ids = Vec::new();
+
+    let catalog_id = menu(state, MENU_TYPE_CATALOG, 0, "系统管理", Some("/system"), None, Some("SettingOutlined"), None, 1).await?;
+    ids.push(catalog_id);
+
+    let keys = router::app::perm_keys();
+    for (i, (module, name, path, component, icon)) in MENUS.iter().enumerate() {
+        let menu_id = menu(
+            state,
+            MENU_TYPE_MENU,
+            catalog_id,
+            name,
+            Some(path),
+            Some(component),
+            Some(icon),
+            None,
+            i as i32 + 1,
+        )
+        .await?;
+        ids.push(menu_id);
+
+        let prefix = format!("system:{}:", module);
+        let points = keys.iter().filter_map(|key| key.strip_prefix(&prefix).map(|action| (*key, action)));
+        for (j, (key, action)) in points.enumerate() {
+            let name = POINTS
+                .iter()
+                .find(|(v, _)| *v == action)
+                .map(|(_, v)| *v)
+                .unwrap_or(action);
+            ids.push(menu(state, MENU_TYPE_POINTS, menu_id, name, None, None, None, Some(key), j as i32 + 1).await?);
+        }
+    }
+
+    Ok(ids)
+}
+
+// 查找菜单（目录、菜单按路由地址，功能点按权限标识），不存在时创建
+#[allow(clippy::too_many_arguments)]
+async fn menu(
+    state: &AppState,
+    menu_type: i32,
+    parent_id: i64,
+    name: &str,
+    path: Option<&str>,
+    component: Option<&str>,
+    icon: Option<&str>,
+    perm: Option<&str>,
+    sort: i32,
+) -> Result<i64> {
+    let mut builder = TMenu::find_alive()
+        .filter(t_menu::Column::MenuType.eq(menu_type));
+    builder = match perm {
+        Some(v) => builder
+            .filter(t_menu::Column::ParentId.eq(parent_id))
+            .filter(t_menu::Column::ApiPerms.eq(v)),
+        None => builder.filter(t_menu::Column::Path.eq(path.unwrap_or_default())),
+    };
+    if let Some(v) = builder.one(&state.db).await? {
+        return Ok(v.menu_id);
+    }
+
+    let now = state.clock.now(offset!(+8)).unix_timestamp();
+    let model = t_menu::ActiveModel {
+        menu_name: Set(name.to_string()),
+        menu_type: Set(menu_type),
+        parent_id: Set(parent_id),
+        sort: Set(Some(sort)),
+        path: Set(path.map(String::from)),
+        component: Set(component.map(String::from)),
+        api_perms: Set(perm.map(String::from)),
+        web_perms: Set(perm.map(String::from)),
+        icon: Set(icon.map(String::from)),
+        context_menu_id: Set((menu_type == MENU_TYPE_POINTS).then_some(parent_id)),
+        visible_flag: Set(1),
+        create_user_id: Set(0),
+        create_time: Set(now),
+        update_time: Set(now),
+        ..Default::default()
+    };
+    let ret = TMenu::insert(model).exec(&state.db).await?;
+    Ok(ret.last_insert_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MENUS, POINTS};
+    use crate::api::router;
+
+    #[test]
+    fn points() {
+        // 路由表中的权限标识均有对应的内置菜单及功能点名称
+        for key in router::app::perm_keys() {
+            let point = MENUS
+                .iter()
+                .find_map(|(module, ..)| key.strip_prefix(&format!("system:{}:", module)));
+            let Some(action) = point else {
+                panic!("{} 没有对应的内置菜单", key);
+            };
+            assert!(POINTS.iter().any(|(v, _)| *v == action), "{} 没有功能点名称", key);
+        }
+    }
+}
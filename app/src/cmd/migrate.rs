@@ -0,0 +1,64 @@
+use clap::Subcommand;
+use migration::{Migrator, MigratorTrait};
+use pkg::state::AppState;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Action {
+    /// 执行未应用的迁移
+    Up {
+        /// 执行的数量，默认全部
+        #[arg(short, long)]
+        num: Option<u32>,
+    },
+    /// 回滚已应用的迁移
+    Down {
+        /// 回滚的数量
+        #[arg(short, long, default_value_t = 1)]
+        num: u32,
+    },
+    /// 查看迁移状态
+    Status,
+    /// 删除全部表后重新执行迁移（数据将丢失）
+    Fresh {
+        /// 确认执行
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+pub async fn exec(state: &AppState, action: Action) {
+    let ret = match action {
+        Action::Up { num } => Migrator::up(&state.db, num).await,
+        Action::Down { num } => Migrator::down(&state.db, Some(num)).await,
+        Action::Status => match Migrator::get_migration_with_status(&state.db).await {
+            Ok(v) => {
+                for m in v {
+                    println!("{:<8} {}", m.status(), m.name());
+                }
+                Ok(())
+            }
+            Err(e) => Err(e),
+        },
+        Action::Fresh { yes } => {
+            if !yes {
+                println!("fresh 将删除全部表及数据，确认请加参数 --yes");
+                return;
+            }
+            Migrator::fresh(&state.db).await
+        }
+    };
+    if let Err(e) = ret {
+        panic!("数据库迁移失败：{}", e);
+    }
+}
+
+/// 启用 `db.auto_migrate` 时执行未应用的迁移
+pub async fn auto(state: &AppState) {
+    if !state.config.get_bool("db.auto_migrate").unwrap_or_default() {
+        return;
+    }
+    if let Err(e) = Migrator::up(&state.db, None).await {
+        panic!("数据库迁移失败：{}", e);
+    }
+    tracing::info!("database migrated");
+}
@@ -1,6 +1,9 @@
 use clap::{Parser, Subcommand};
 
 pub mod hello;
+pub mod init;
+pub mod migrate;
+pub mod openapi;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -18,4 +21,24 @@ pub enum Command {
         name: String,
     },
     Serve,
+    /// 初始化：执行迁移，创建超级管理员、管理员角色及内置菜单
+    Init {
+        /// 管理员登录名
+        #[arg(short, long, default_value = "admin")]
+        login_name: String,
+        /// 管理员姓名
+        #[arg(short, long, default_value = "超级管理员")]
+        realname: String,
+    },
+    /// 数据库迁移
+    Migrate {
+        #[command(subcommand)]
+        action: migrate::Action,
+    },
+    /// 导出 OpenAPI 接口文档
+    Openapi {
+        /// 输出文件
+        #[arg(short, long, default_value = "openapi.json")]
+        output: String,
+    },
 }
@@ -0,0 +1,12 @@
+use std::fs;
+
+use crate::api::openapi;
+
+/// 将接口文档写入文件，供前端生成客户端代码
+pub fn exec(output: &str) {
+    let json = openapi::document()
+        .to_pretty_json()
+        .unwrap_or_else(|e| panic!("接口文档生成失败：{}", e));
+    fs::write(output, json).unwrap_or_else(|e| panic!("接口文档写入失败：{} - {}", e, output));
+    println!("接口文档已写入 {}", output);
+}
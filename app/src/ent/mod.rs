@@ -4,9 +4,14 @@ pub mod prelude;
 
 pub mod t_department;
 pub mod t_employee;
+pub mod t_employee_totp;
+pub mod t_login_session;
 pub mod t_menu;
 pub mod t_operate_log;
+pub mod t_password_history;
 pub mod t_position;
+pub mod t_refresh_token;
 pub mod t_role;
+pub mod t_role_department;
 pub mod t_role_employee;
 pub mod t_role_menu;
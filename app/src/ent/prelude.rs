@@ -2,9 +2,14 @@
 
 pub use super::t_department::Entity as TDepartment;
 pub use super::t_employee::Entity as TEmployee;
+pub use super::t_employee_totp::Entity as TEmployeeTotp;
+pub use super::t_login_session::Entity as TLoginSession;
 pub use super::t_menu::Entity as TMenu;
 pub use super::t_operate_log::Entity as TOperateLog;
+pub use super::t_password_history::Entity as TPasswordHistory;
 pub use super::t_position::Entity as TPosition;
+pub use super::t_refresh_token::Entity as TRefreshToken;
 pub use super::t_role::Entity as TRole;
+pub use super::t_role_department::Entity as TRoleDepartment;
 pub use super::t_role_employee::Entity as TRoleEmployee;
 pub use super::t_role_menu::Entity as TRoleMenu;
@@ -11,6 +11,7 @@ pub struct Model {
     pub manager_id: i64,
     pub parent_id: i64,
     pub sort: i32,
+    pub deleted_flag: i16,
     pub update_time: i64,
     pub create_time: i64,
 }
@@ -19,3 +20,8 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl pkg::soft_delete::SoftDelete for Entity {
+    const DELETED_FLAG: Column = Column::DeletedFlag;
+    const UPDATE_TIME: Column = Column::UpdateTime;
+}
@@ -11,14 +11,15 @@ pub struct Model {
     pub login_pwd: String,
     pub realname: String,
     pub avatar: String,
-    pub gender: u8,
+    pub gender: i16,
     pub phone: String,
     pub department_id: i64,
     pub position_id: i64,
     pub email: String,
-    pub disabled_flag: u8,
-    pub deleted_flag: u8,
-    pub administrator_flag: i8,
+    pub disabled_flag: i16,
+    pub deleted_flag: i16,
+    pub administrator_flag: i16,
+    pub must_change_password: i16,
     pub login_token: String,
     pub login_at: i64,
     pub remark: String,
@@ -30,3 +31,8 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl pkg::soft_delete::SoftDelete for Entity {
+    const DELETED_FLAG: Column = Column::DeletedFlag;
+    const UPDATE_TIME: Column = Column::UpdateTime;
+}
@@ -20,13 +20,13 @@ pub struct Model {
     pub web_perms: Option<String>,
     pub icon: Option<String>,
     pub context_menu_id: Option<i64>,
-    pub frame_flag: i8,
+    pub frame_flag: i16,
     #[sea_orm(column_type = "Text", nullable)]
     pub frame_url: Option<String>,
-    pub cache_flag: i8,
-    pub visible_flag: i8,
-    pub disabled_flag: i8,
-    pub deleted_flag: i8,
+    pub cache_flag: i16,
+    pub visible_flag: i16,
+    pub disabled_flag: i16,
+    pub deleted_flag: i16,
     pub create_user_id: i64,
     pub create_time: i64,
     pub update_user_id: Option<i64>,
@@ -37,3 +37,8 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl pkg::soft_delete::SoftDelete for Entity {
+    const DELETED_FLAG: Column = Column::DeletedFlag;
+    const UPDATE_TIME: Column = Column::UpdateTime;
+}
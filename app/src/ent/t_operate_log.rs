@@ -20,8 +20,8 @@ pub struct Model {
     pub ip_region: Option<String>,
     #[sea_orm(column_type = "Text", nullable)]
     pub user_agent: Option<String>,
-    pub success_flag: Option<i8>,
-    #[sea_orm(column_type = "custom(\"LONGTEXT\")", nullable)]
+    pub success_flag: Option<i16>,
+    #[sea_orm(column_type = "Text", nullable)]
     pub fail_reason: Option<String>,
     pub update_time: i64,
     pub create_time: i64,
@@ -9,9 +9,9 @@ pub struct Model {
     pub position_id: i64,
     pub position_name: String,
     pub level: String,
-    pub sort: i64,
+    pub sort: i32,
     pub remark: String,
-    pub deleted_flag: i64,
+    pub deleted_flag: i16,
     pub create_time: i64,
     pub update_time: i64,
 }
@@ -20,3 +20,8 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl pkg::soft_delete::SoftDelete for Entity {
+    const DELETED_FLAG: Column = Column::DeletedFlag;
+    const UPDATE_TIME: Column = Column::UpdateTime;
+}
@@ -10,6 +10,9 @@ pub struct Model {
     pub role_name: String,
     pub role_code: String,
     pub remark:String,
+    pub mfa_flag: i16,
+    pub data_scope: i16,
+    pub deleted_flag: i16,
     pub update_time: i64,
     pub create_time: i64,
 }
@@ -18,3 +21,8 @@ pub struct Model {
 pub enum Relation {}
 
 impl ActiveModelBehavior for ActiveModel {}
+
+impl pkg::soft_delete::SoftDelete for Entity {
+    const DELETED_FLAG: Column = Column::DeletedFlag;
+    const UPDATE_TIME: Column = Column::UpdateTime;
+}
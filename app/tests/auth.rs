@@ -0,0 +1,62 @@
+mod common;
+
+use axum::http::Method;
+use serde_json::json;
+
+use common::{assert_code, get, login_reply, ok, post, request, run};
+
+#[test]
+fn unauthorized() {
+    run(async {
+        assert_code(&request(Method::GET, "/v1/me", None, None).await, 20000);
+        assert_code(&request(Method::GET, "/v1/roles", None, None).await, 20000);
+    });
+}
+
+#[test]
+fn invalid_token() {
+    run(async {
+        assert_code(&get("invalid.token.value", "/v1/me").await, 20002);
+    });
+}
+
+#[test]
+fn must_change_password() {
+    run(async {
+        let common::Fixture { token, employee_id, user, .. } = common::fixture("must_change", None, 5).await;
+
+        // 管理员重置密码后，原会话下线，以新密码登录后仅能修改密码
+        let data = ok(get(&token, &format!("/v1/employees/reset_password/{}", employee_id)).await);
+        let password = data["password"].as_str().unwrap();
+        assert_code(&get(&user, "/v1/me").await, 20000);
+
+        let data = ok(login_reply("must_change", password).await);
+        assert_eq!(data["must_change_password"], true);
+        let user = data["auth_token"].as_str().unwrap();
+        assert_code(&get(user, "/v1/roles").await, 20003);
+
+        let body = json!({ "old_password": password, "new_password": "Changed@123456" });
+        ok(post(user, "/v1/me/password", body).await);
+        ok(get(user, "/v1/me").await);
+    });
+}
+
+#[test]
+fn permission() {
+    run(async {
+        let common::Fixture { token, role_id, user, .. } = common::fixture("permission", None, 1).await;
+
+        // 仅登录即可访问的接口
+        ok(get(&user, "/v1/me").await);
+        // 未分配功能权限
+        assert_code(&get(&user, "/v1/positions").await, 30000);
+
+        let menu_ids = common::menu_ids(&token, &["system:position:query"]).await;
+        let body = json!({ "role_id": role_id, "menu_ids": menu_ids });
+        ok(post(&token, "/v1/roles/role_func_update", body).await);
+
+        ok(get(&user, "/v1/positions").await);
+        let body = json!({ "postname": "permission", "level": "", "sort": 0, "remark": "" });
+        assert_code(&post(&user, "/v1/positions", body).await, 30000);
+    });
+}
@@ -0,0 +1,363 @@
+//! 集成测试支持：SQLite 内存库、测试配置、内置数据，以及直接调用路由的请求方法
+//!
+//! 应用状态（数据库、配置等）由测试创建后传给路由，不依赖全局实例；同一测试文件中的用例
+//! 共享一个运行时和应用状态，用例之间通过不同的名称区分数据，互不依赖执行顺序
+
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::sync::LazyLock;
+
+use axum::body::Body;
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, Method, Request};
+use config::{Config, File, FileFormat};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+use tokio::sync::OnceCell;
+use tower::ServiceExt;
+
+use app::api::router;
+use pkg::state::AppState;
+
+/// 超级管理员（由 `init` 命令创建）
+pub const ADMIN: &str = "admin";
+pub const PASSWORD: &str = "Admin@123456";
+
+const CONFIG: &str = r#"
+[app]
+env = "test"
+debug = false
+secret = "8c1b7e0c5d2f4a6b9e3d7f1a2c4e6b8d"
+
+[auth]
+access_ttl = 1800
+refresh_ttl = 604800
+max_sessions = 0
+
+[login]
+max_attempts = 5
+ip_max_attempts = 0
+window = 900
+lock_time = 900
+
+[captcha]
+mode = "off"
+
+[cache]
+driver = "memory"
+
+[db]
+# 内存库仅存在于单个连接中，连接池只保留一个连接
+dsn = "sqlite::memory:"
+options = { min_conns = 1, max_conns = 1, conn_timeout = 10, idle_timeout = 3600, max_lifetime = 3600 }
+
+[password]
+algorithm = "bcrypt"
+bcrypt = { cost = 4 }
+
+[password.policy]
+min_length = 8
+max_length = 64
+require_upper = true
+require_lower = true
+require_digit = true
+require_special = false
+history = 3
+"#;
+
+// 数据库连接池、操作日志写入任务等绑定在创建时的运行时上，所有用例共用一个运行时
+static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("测试运行时创建失败")
+});
+
+static STATE: OnceCell<AppState> = OnceCell::const_new();
+
+/// 在共享运行时中执行用例，首次执行时完成初始化
+pub fn run<F: Future>(f: F) -> F::Output {
+    RUNTIME.block_on(async {
+        STATE.get_or_init(setup).await;
+        f.await
+    })
+}
+
+/// 测试共用的应用状态
+pub fn state() -> &'static AppState {
+    STATE.get().expect("应用状态未初始化")
+}
+
+async fn setup() -> AppState {
+    // 错误日志输出到测试结果，仅在用例失败时显示
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_test_writer()
+        .try_init();
+
+    let cfg = Config::builder()
+        .add_source(File::from_str(CONFIG, FileFormat::Toml))
+        .build()
+        .expect("测试配置加载失败");
+
+    let mut state = AppState::from_config(cfg).await;
+    app::api::middleware::audit::start(&mut state);
+
+    // 执行迁移并创建超级管理员、管理员角色及内置菜单
+    std::env::set_var("INIT_ADMIN_PASSWORD", PASSWORD);
+    app::cmd::init::exec(&state, ADMIN.to_string(), String::from("超级管理员")).await;
+    state
+}
+
+/// 发送请求，返回响应的 `Reply` 数据
+pub async fn request(method: Method, uri: &str, token: Option<&str>, body: Option<Value>) -> Value {
+    request_with(state(), method, uri, token, body).await
+}
+
+/// 使用指定的应用状态发送请求
+pub async fn request_with(
+    state: &AppState,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> Value {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(v) = token {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", v));
+    }
+    let body = match body {
+        Some(v) => {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+            Body::from(v.to_string())
+        }
+        None => Body::empty(),
+    };
+
+    let resp = router::app::init(state.clone())
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes)
+        .unwrap_or_else(|e| panic!("响应不是JSON：{} - {}", e, String::from_utf8_lossy(&bytes)))
+}
+
+/// 发送 GET 请求，返回响应头及原始响应体（用于文件下载）
+pub async fn download(state: &AppState, token: &str, uri: &str) -> (HeaderMap, Bytes) {
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let resp = router::app::init(state.clone()).oneshot(req).await.unwrap();
+    let headers = resp.headers().clone();
+    let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+    (headers, bytes)
+}
+
+/// 以 `multipart/form-data` 上传文件（字段 `file`）及其它文本字段，返回响应的 `Reply` 数据
+pub async fn upload(token: &str, uri: &str, file: &[u8], fields: &[(&str, &str)]) -> Value {
+    const BOUNDARY: &str = "----rs-axum-admin-test";
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", BOUNDARY, name, value).as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"data\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+            BOUNDARY
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(file);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))
+        .unwrap();
+    let resp = router::app::init(state().clone()).oneshot(req).await.unwrap();
+    let bytes = resp.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&bytes)
+        .unwrap_or_else(|e| panic!("响应不是JSON：{} - {}", e, String::from_utf8_lossy(&bytes)))
+}
+
+pub async fn get(token: &str, uri: &str) -> Value {
+    request(Method::GET, uri, Some(token), None).await
+}
+
+pub async fn post(token: &str, uri: &str, body: Value) -> Value {
+    request(Method::POST, uri, Some(token), Some(body)).await
+}
+
+pub async fn delete(token: &str, uri: &str) -> Value {
+    request(Method::DELETE, uri, Some(token), None).await
+}
+
+/// 断言响应成功，返回 `data`（无数据时为 `null`）
+pub fn ok(reply: Value) -> Value {
+    assert_eq!(reply["code"], 0, "请求失败：{}", reply);
+    reply.get("data").cloned().unwrap_or(Value::Null)
+}
+
+/// 断言响应的错误码
+pub fn assert_code(reply: &Value, code: i64) {
+    assert_eq!(reply["code"], code, "错误码不符：{}", reply);
+    assert_eq!(reply["err"], true, "{}", reply);
+}
+
+/// 登录，返回完整的登录结果
+pub async fn login_reply(username: &str, password: &str) -> Value {
+    let body = json!({ "username": username, "password": password });
+    request(Method::POST, "/v1/login", None, Some(body)).await
+}
+
+/// 登录，返回访问令牌
+pub async fn login(username: &str, password: &str) -> String {
+    let data = ok(login_reply(username, password).await);
+    data["auth_token"].as_str().unwrap().to_string()
+}
+
+/// 超级管理员的访问令牌
+pub async fn admin() -> String {
+    login(ADMIN, PASSWORD).await
+}
+
+/// 列表中按字段查找一条数据
+pub fn find<'a>(list: &'a Value, key: &str, value: &str) -> Option<&'a Value> {
+    list.as_array()?.iter().find(|v| v[key] == value)
+}
+
+/// 创建角色，返回角色ID
+pub async fn role(token: &str, code: &str, data_scope: i64) -> i64 {
+    let body = json!({ "rolename": code, "rolecode": code, "remark": "", "data_scope": data_scope });
+    ok(post(token, "/v1/roles", body).await);
+
+    let data = ok(get(token, &format!("/v1/roles?rolename={}", code)).await);
+    find(&data["list"], "rolecode", code).expect("角色不存在")["roleid"]
+        .as_i64()
+        .unwrap()
+}
+
+/// 创建员工并分配角色，以初始密码登录后修改密码，返回 (员工ID, 访问令牌)
+pub async fn employee(token: &str, login_name: &str, department_id: i64, role_id: i64) -> (i64, String) {
+    let body = json!({
+        "realname": login_name,
+        "phone": phone(login_name),
+        "department_id": department_id,
+        "login_name": login_name,
+        "email": format!("{}@example.com", login_name),
+        "gender": 1,
+        "disabled_flag": 0,
+        "position_id": 0,
+    });
+    let data = ok(post(token, "/v1/employees", body).await);
+    let password = data["password"].as_str().unwrap().to_string();
+
+    let data = ok(get(token, &format!("/v1/employees?login_name={}", login_name)).await);
+    let employee_id = find(&data["list"], "login_name", login_name).expect("员工不存在")["employee_id"]
+        .as_i64()
+        .unwrap();
+
+    let body = json!({ "role_id": role_id, "employee_ids": [employee_id] });
+    ok(post(token, "/v1/roles/role_emp_add", body).await);
+
+    let data = ok(login_reply(login_name, &password).await);
+    assert_eq!(data["must_change_password"], true);
+    let auth_token = data["auth_token"].as_str().unwrap().to_string();
+    let body = json!({ "old_password": password, "new_password": PASSWORD });
+    ok(post(&auth_token, "/v1/me/password", body).await);
+
+    (employee_id, auth_token)
+}
+
+/// 用例共用的测试数据
+pub struct Fixture {
+    /// 超级管理员的访问令牌
+    pub token: String,
+    /// 顶级部门ID
+    pub root: i64,
+    /// 员工所在部门ID
+    pub department_id: i64,
+    pub role_id: i64,
+    pub employee_id: i64,
+    /// 员工的访问令牌
+    pub user: String,
+}
+
+/// 以超级管理员登录，创建编码为 `name` 的角色及登录名为 `name` 的员工；
+/// `department` 不为空时在顶级部门下创建该部门作为员工所在部门，否则为顶级部门
+pub async fn fixture(name: &str, department: Option<&str>, data_scope: i64) -> Fixture {
+    let token = admin().await;
+    let root = root_department(&token).await;
+    let department_id = match department {
+        Some(v) => {
+            let body = json!({ "deptname": v, "sort": 1, "managerid": 0, "parentid": root });
+            ok(post(&token, "/v1/departments", body).await);
+            let data = ok(get(&token, &format!("/v1/departments?deptname={}", v)).await);
+            find(&data["list"], "department_name", v).expect("部门不存在")["department_id"]
+                .as_i64()
+                .unwrap()
+        }
+        None => root,
+    };
+    let role_id = role(&token, name, data_scope).await;
+    let (employee_id, user) = employee(&token, name, department_id, role_id).await;
+
+    Fixture {
+        token,
+        root,
+        department_id,
+        role_id,
+        employee_id,
+        user,
+    }
+}
+
+/// 顶级部门ID（由 `init` 命令创建）
+pub async fn root_department(token: &str) -> i64 {
+    let data = ok(get(token, "/v1/departments?size=100").await);
+    data["list"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|v| v["parent_id"] == 0)
+        .expect("顶级部门不存在")["department_id"]
+        .as_i64()
+        .unwrap()
+}
+
+// 按登录名生成不重复的手机号码
+fn phone(login_name: &str) -> String {
+    let n = login_name
+        .bytes()
+        .fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
+    format!("139{:08}", n % 100_000_000)
+}
+
+/// 按权限标识查找功能点菜单ID
+pub async fn menu_ids(token: &str, keys: &[&str]) -> Vec<i64> {
+    fn walk(nodes: &Value, keys: &[&str], ids: &mut Vec<i64>) {
+        for node in nodes.as_array().into_iter().flatten() {
+            if keys.iter().any(|k| node["api_perms"] == *k) {
+                ids.push(node["menu_id"].as_i64().unwrap());
+            }
+            walk(&node["children"], keys, ids);
+        }
+    }
+
+    let tree = ok(get(token, "/v1/menus").await);
+    let mut ids = Vec::new();
+    walk(&tree, keys, &mut ids);
+    assert_eq!(ids.len(), keys.len(), "功能点不存在：{:?}", keys);
+    ids
+}
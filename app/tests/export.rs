@@ -0,0 +1,60 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::header;
+use config::Config;
+use serde_json::Value;
+
+use common::{assert_code, download, get, run};
+
+#[test]
+fn export() {
+    run(async {
+        let common::Fixture { token, department_id, role_id, .. } =
+            common::fixture("export_a", Some("导出部"), 1).await;
+        common::employee(&token, "export_b", department_id, role_id).await;
+
+        // CSV：表头、名称及状态文字，查询条件同列表
+        let uri = format!("/v1/employees/export?department_id={}", department_id);
+        let (headers, bytes) = download(common::state(), &token, &uri).await;
+        assert_eq!(headers[header::CONTENT_TYPE], "text/csv; charset=utf-8");
+        assert_eq!(headers[header::CONTENT_DISPOSITION], "attachment; filename=\"employees.csv\"");
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        let lines: Vec<&str> = text.trim_start_matches('\u{feff}').lines().collect();
+        assert_eq!(lines.len(), 3, "{}", text);
+        assert!(lines[0].starts_with("员工ID,登录名,姓名,性别"));
+        assert!(lines[1].contains(",export_b,") && lines[1].contains(",男,") && lines[1].contains(",导出部,"));
+        assert!(lines[1].contains(",启用,"));
+        assert!(lines[2].contains(",export_a,"));
+
+        // XLSX
+        let uri = format!("/v1/employees/export?department_id={}&format=xlsx", department_id);
+        let (headers, bytes) = download(common::state(), &token, &uri).await;
+        assert_eq!(headers[header::CONTENT_DISPOSITION], "attachment; filename=\"employees.xlsx\"");
+        assert!(bytes.starts_with(b"PK"));
+
+        let (_, bytes) = download(common::state(), &token, "/v1/roles/export?rolecode=export_a").await;
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(text.contains(",export_a,export_a,全部数据,否,"), "{}", text);
+        let (_, bytes) = download(common::state(), &token, "/v1/departments/export?deptname=导出部").await;
+        assert_eq!(String::from_utf8(bytes.to_vec()).unwrap().lines().count(), 2);
+
+        // 错误的格式及查询条件
+        assert_code(&get(&token, "/v1/employees/export?format=pdf").await, 10000);
+        assert_code(&get(&token, "/v1/positions/export?unknown=1").await, 10000);
+
+        // 超出行数上限
+        let mut state = common::state().clone();
+        let cfg = Config::builder()
+            .add_source((*state.config).clone())
+            .set_override("export.max_rows", 1)
+            .unwrap()
+            .build()
+            .unwrap();
+        state.config = Arc::new(cfg);
+        let (_, bytes) = download(&state, &token, &uri).await;
+        let reply: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_code(&reply, 10000);
+    });
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{assert_code, download, get, login_reply, ok, post, run, upload};
+
+const CSV: &str = "\
+登录名,姓名,性别,手机号码,邮箱,部门,职务,状态
+import_a,张三,男,13800000001,a@example.com,导入部,导入岗,启用
+import_b,李四,女,13800000002,b@example.com,导入部,,禁用
+import_exist,王五,,13800000003,c@example.com,导入部,,
+import_a,赵六,,13800000001,d@example.com,导入部,,
+
+import_c,孙七,x,13800000004,,不存在的部门,,
+";
+
+#[test]
+fn import() {
+    run(async {
+        let common::Fixture { token, department_id, role_id, .. } = common::fixture("import_exist", Some("导入部"), 1).await;
+        let body = json!({ "postname": "导入岗", "level": "P5", "sort": 1, "remark": "" });
+        ok(post(&token, "/v1/positions", body).await);
+
+        // 默认仅校验
+        let data = ok(upload(&token, "/v1/employees/import", CSV.as_bytes(), &[]).await);
+        assert_eq!((data["dry_run"].as_bool(), data["total"].as_i64()), (Some(true), Some(5)));
+        assert_eq!((data["valid"].as_i64(), data["imported"].as_i64()), (Some(2), Some(0)));
+        let errors = |data: &Value, i: usize| -> Vec<String> {
+            let row = &data["rows"][i];
+            row["errors"].as_array().unwrap().iter().map(|v| v.as_str().unwrap().to_string()).collect()
+        };
+        assert!(errors(&data, 0).is_empty());
+        assert_eq!(errors(&data, 2), ["登录名已重复"]);
+        assert_eq!(errors(&data, 3), ["登录名与第 2 行重复", "手机号码与第 2 行重复"]);
+        assert_eq!(data["rows"][4]["line"], 7);
+        assert_eq!(
+            errors(&data, 4),
+            ["邮箱必填", "部门不存在：不存在的部门", "性别应为 男、女 或 未知：x"]
+        );
+        let data = ok(get(&token, "/v1/employees?login_name=import_a").await);
+        assert_eq!(data["total"], 0);
+
+        // 导入校验通过的行
+        let data = ok(upload(&token, "/v1/employees/import", CSV.as_bytes(), &[("dry_run", "false")]).await);
+        assert_eq!((data["valid"].as_i64(), data["imported"].as_i64()), (Some(2), Some(2)));
+        let password = data["rows"][0]["password"].as_str().unwrap().to_string();
+        assert!(data["rows"][2].get("password").is_none());
+
+        let data = ok(get(&token, "/v1/employees?login_name=import_&sort=login_name").await);
+        assert_eq!(data["total"], 3);
+        let a = &data["list"][0];
+        assert_eq!((a["login_name"].as_str(), a["department_id"].as_i64()), (Some("import_a"), Some(department_id)));
+        assert_eq!((a["gender"].as_i64(), a["disabled_flag"].as_i64()), (Some(1), Some(0)));
+        assert_ne!(a["position_id"], 0);
+        assert_eq!((data["list"][1]["gender"].as_i64(), data["list"][1]["disabled_flag"].as_i64()), (Some(2), Some(1)));
+        let body = json!({ "role_id": role_id, "employee_ids": [a["employee_id"]] });
+        ok(post(&token, "/v1/roles/role_emp_add", body).await);
+        let data = ok(login_reply("import_a", &password).await);
+        assert_eq!(data["must_change_password"], true);
+
+        // 导出的文件可直接导入
+        let uri = "/v1/employees/export?login_name=import_&format=xlsx";
+        let (_, bytes) = download(common::state(), &token, uri).await;
+        let data = ok(upload(&token, "/v1/employees/import", &bytes, &[]).await);
+        assert_eq!((data["total"].as_i64(), data["valid"].as_i64()), (Some(3), Some(0)));
+        assert_eq!(errors(&data, 0), ["登录名已重复", "手机号码已重复"]);
+
+        // 缺少必填列、未上传文件、错误的参数
+        let reply = upload(&token, "/v1/employees/import", "登录名,姓名\nx,x\n".as_bytes(), &[]).await;
+        assert_code(&reply, 10000);
+        let reply = upload(&token, "/v1/employees/import", CSV.as_bytes(), &[("dry_run", "no")]).await;
+        assert_code(&reply, 10000);
+        assert_code(&post(&token, "/v1/employees/import", json!({})).await, 10000);
+    });
+}
@@ -0,0 +1,239 @@
+mod common;
+
+use std::sync::Arc;
+
+use axum::http::Method;
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::json;
+use time::{OffsetDateTime, UtcOffset};
+
+use app::ent::{prelude::TEmployee, t_employee};
+use common::{assert_code, get, login, login_reply, ok, post, run, ADMIN, PASSWORD};
+use pkg::state::AppState;
+use pkg::xtime::Clock;
+
+// 固定时间的时钟
+struct FixedClock(OffsetDateTime);
+
+impl Clock for FixedClock {
+    fn now(&self, offset: UtcOffset) -> OffsetDateTime {
+        self.0.to_offset(offset)
+    }
+}
+
+#[test]
+fn login_ok() {
+    run(async {
+        let data = ok(login_reply(ADMIN, PASSWORD).await);
+        assert_eq!(data["must_change_password"], false);
+        assert!(data["expires_in"].as_i64().unwrap() > 0);
+        assert!(!data["refresh_token"].as_str().unwrap().is_empty());
+
+        let token = data["auth_token"].as_str().unwrap();
+        let me = ok(get(token, "/v1/me").await);
+        assert_eq!(me["login_name"], ADMIN);
+        assert_eq!(me["administrator_flag"], 1);
+    });
+}
+
+#[test]
+fn login_failed() {
+    run(async {
+        assert_code(&login_reply(ADMIN, "Wrong@123456").await, 20000);
+        assert_code(&login_reply("nobody", PASSWORD).await, 20000);
+        assert_code(&login_reply("", "").await, 10000);
+    });
+}
+
+#[test]
+fn login_lockout() {
+    run(async {
+        common::fixture("login_lockout", None, 5).await;
+
+        // 连续失败达到上限后，正确的密码也无法登录
+        for _ in 0..5 {
+            assert_code(&login_reply("login_lockout", "Wrong@123456").await, 20000);
+        }
+        let reply = login_reply("login_lockout", PASSWORD).await;
+        assert_code(&reply, 20000);
+        assert!(reply["msg"].as_str().unwrap().contains("失败次数过多"), "{}", reply);
+    });
+}
+
+#[test]
+fn login_totp_lockout() {
+    run(async {
+        let common::Fixture { token, role_id, .. } = common::fixture("login_totp_lockout", None, 5).await;
+        let body = json!({
+            "roleid": role_id, "rolename": "login_totp_lockout", "rolecode": "login_totp_lockout", "remark": "",
+            "mfa_flag": 1, "data_scope": 5, "create_time": 0, "create_time_str": "",
+        });
+        ok(post(&token, "/v1/roles/update", body).await);
+
+        let challenge = || async {
+            let data = ok(login_reply("login_totp_lockout", PASSWORD).await);
+            let challenge_token = data["challenge"]["challenge_token"].as_str().unwrap().to_string();
+            let body = json!({ "challenge_token": challenge_token });
+            ok(common::request(Method::POST, "/v1/login/totp/setup", None, Some(body)).await);
+            challenge_token
+        };
+        let verify = |challenge_token: String| async move {
+            let body = json!({ "challenge_token": challenge_token, "code": "wrong" });
+            common::request(Method::POST, "/v1/login/totp", None, Some(body)).await
+        };
+
+        // 密码正确不清除失败次数，两步验证失败计入账号的失败次数
+        let challenge_token = challenge().await;
+        for _ in 0..3 {
+            assert_code(&verify(challenge_token.clone()).await, 10000);
+        }
+        let challenge_token = challenge().await;
+        for _ in 0..2 {
+            assert_code(&verify(challenge_token.clone()).await, 10000);
+        }
+        let reply = verify(challenge_token).await;
+        assert_code(&reply, 20000);
+        assert!(reply["msg"].as_str().unwrap().contains("失败次数过多"), "{}", reply);
+        let reply = login_reply("login_totp_lockout", PASSWORD).await;
+        assert_code(&reply, 20000);
+        assert!(reply["msg"].as_str().unwrap().contains("失败次数过多"), "{}", reply);
+    });
+}
+
+#[test]
+fn login_totp_disabled() {
+    run(async {
+        let common::Fixture { token, role_id, employee_id, .. } =
+            common::fixture("login_totp_disabled", None, 5).await;
+        let body = json!({
+            "roleid": role_id, "rolename": "login_totp_disabled", "rolecode": "login_totp_disabled", "remark": "",
+            "mfa_flag": 1, "data_scope": 5, "create_time": 0, "create_time_str": "",
+        });
+        ok(post(&token, "/v1/roles/update", body).await);
+
+        let data = ok(login_reply("login_totp_disabled", PASSWORD).await);
+        let challenge_token = data["challenge"]["challenge_token"].as_str().unwrap().to_string();
+        let body = json!({ "challenge_token": challenge_token });
+        let setup = ok(common::request(Method::POST, "/v1/login/totp/setup", None, Some(body)).await);
+        let secret = setup["secret"].as_str().unwrap();
+
+        // 两步验证期间账号被禁用，验证码正确也不签发令牌
+        TEmployee::update_many()
+            .col_expr(t_employee::Column::DisabledFlag, Expr::value(1))
+            .filter(t_employee::Column::EmployeeId.eq(employee_id))
+            .exec(&common::state().db)
+            .await
+            .unwrap();
+        let step = OffsetDateTime::now_utc().unix_timestamp() / pkg::crypto::totp::STEP;
+        let code = pkg::crypto::totp::code_at(secret, step).unwrap();
+        let body = json!({ "challenge_token": challenge_token, "code": code });
+        let reply = common::request(Method::POST, "/v1/login/totp", None, Some(body)).await;
+        assert_code(&reply, 20000);
+        assert!(reply["msg"].as_str().unwrap().contains("禁用"), "{}", reply);
+    });
+}
+
+#[test]
+fn logout() {
+    run(async {
+        let token = login(ADMIN, PASSWORD).await;
+        ok(get(&token, "/v1/me").await);
+        ok(get(&token, "/v1/logout").await);
+        assert_code(&get(&token, "/v1/me").await, 20000);
+    });
+}
+
+#[test]
+fn refresh() {
+    run(async {
+        let data = ok(login_reply(ADMIN, PASSWORD).await);
+        let refresh_token = data["refresh_token"].as_str().unwrap();
+
+        let reply = common::request(
+            axum::http::Method::POST,
+            "/v1/token/refresh",
+            None,
+            Some(json!({ "refresh_token": refresh_token })),
+        )
+        .await;
+        let data = ok(reply);
+        let token = data["auth_token"].as_str().unwrap();
+        ok(get(token, "/v1/me").await);
+
+        // 刷新令牌只能使用一次
+        let reply = common::request(
+            axum::http::Method::POST,
+            "/v1/token/refresh",
+            None,
+            Some(json!({ "refresh_token": refresh_token })),
+        )
+        .await;
+        assert!(reply["err"] == true, "{}", reply);
+
+        // 员工已禁用时不能刷新
+        let employee_id = common::fixture("refresh_user", None, 5).await.employee_id;
+        let data = ok(login_reply("refresh_user", PASSWORD).await);
+        let refresh_token = data["refresh_token"].as_str().unwrap();
+        TEmployee::update_many()
+            .col_expr(t_employee::Column::DisabledFlag, Expr::value(1))
+            .filter(t_employee::Column::EmployeeId.eq(employee_id))
+            .exec(&common::state().db)
+            .await
+            .unwrap();
+        let reply = common::request(
+            axum::http::Method::POST,
+            "/v1/token/refresh",
+            None,
+            Some(json!({ "refresh_token": refresh_token })),
+        )
+        .await;
+        assert_code(&reply, 20000);
+    });
+}
+
+#[test]
+fn change_password() {
+    run(async {
+        let user = common::fixture("change_password", None, 5).await.user;
+
+        // 不符合密码策略
+        let body = json!({ "old_password": PASSWORD, "new_password": "short" });
+        assert_code(&post(&user, "/v1/me/password", body).await, 10000);
+        // 原密码错误
+        let body = json!({ "old_password": "Wrong@123456", "new_password": "Changed@123456" });
+        assert!(post(&user, "/v1/me/password", body).await["err"] == true);
+
+        let body = json!({ "old_password": PASSWORD, "new_password": "Changed@123456" });
+        ok(post(&user, "/v1/me/password", body).await);
+        assert_code(&login_reply("change_password", PASSWORD).await, 20000);
+        login("change_password", "Changed@123456").await;
+    });
+}
+
+#[test]
+fn injected_clock() {
+    run(async {
+        // 2023-07-12 13:45:13 +08:00
+        let login_time = 1689140713;
+        let state = AppState {
+            clock: Arc::new(FixedClock(OffsetDateTime::from_unix_timestamp(login_time).unwrap())),
+            ..common::state().clone()
+        };
+
+        let body = json!({ "username": ADMIN, "password": PASSWORD });
+        let data = ok(common::request_with(&state, Method::POST, "/v1/login", None, Some(body)).await);
+        let token = data["auth_token"].as_str().unwrap();
+        let sessions = ok(common::request_with(&state, Method::GET, "/v1/me/sessions", Some(token), None).await);
+        let current = sessions
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|v| v["current"] == true)
+            .unwrap();
+        assert_eq!(current["create_time"], login_time);
+        assert_eq!(current["create_time_str"], "2023-07-12 13:45:13");
+
+        // 按系统时钟，该登录会话早已过期
+        assert_code(&get(token, "/v1/me").await, 20000);
+    });
+}
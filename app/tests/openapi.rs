@@ -0,0 +1,17 @@
+mod common;
+
+use axum::http::Method;
+
+use common::{request, run};
+
+#[test]
+fn spec() {
+    run(async {
+        // 接口文档无需登录
+        let doc = request(Method::GET, "/openapi.json", None, None).await;
+        assert_eq!(doc["openapi"], "3.1.0");
+        assert!(doc["paths"]["/v1/login"]["post"]["security"].is_array());
+        assert!(doc["paths"]["/v1/employees"]["get"].is_object());
+        assert!(doc["components"]["schemas"]["Reply_Page_employee.RespInfo"].is_object());
+    });
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::time::Duration;
+
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde_json::{json, Value};
+
+use app::ent::{prelude::TOperateLog, t_operate_log};
+use common::{admin, assert_code, get, ok, post, run};
+
+#[test]
+fn record() {
+    run(async {
+        let token = admin().await;
+        let body = json!({ "postname": "audit_worker", "level": "P1", "sort": 1, "remark": "" });
+        ok(post(&token, "/v1/positions", body).await);
+
+        // 由启动时创建的写入任务异步写入
+        let mut model = None;
+        for _ in 0..50 {
+            model = TOperateLog::find()
+                .filter(t_operate_log::Column::Param.contains("audit_worker"))
+                .one(&common::state().db)
+                .await
+                .unwrap();
+            if model.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let model = model.expect("操作日志未写入");
+        assert_eq!(model.module.as_deref(), Some("岗位管理"));
+        assert_eq!(model.method.as_deref(), Some("POST"));
+        assert_eq!(model.success_flag, Some(1));
+    });
+}
+
+#[test]
+fn cursor() {
+    run(async {
+        let token = admin().await;
+        let models = (1..=5).map(|i| t_operate_log::ActiveModel {
+            operate_user_id: Set(0),
+            operate_user_type: Set(1),
+            operate_user_name: Set("cursor".to_string()),
+            module: Set(Some("cursor_test".to_string())),
+            create_time: Set(i),
+            update_time: Set(i),
+            ..Default::default()
+        });
+        TOperateLog::insert_many(models).exec(&common::state().db).await.unwrap();
+
+        // 页码分页：每页均返回总数
+        let data = ok(get(&token, "/v1/operate_logs?module=cursor_test&size=2&page=3").await);
+        assert_eq!(data["total"], 5);
+        assert_eq!(data["page"], 3);
+        assert_eq!(data["has_more"], false);
+        assert_eq!(data["list"].as_array().unwrap().len(), 1);
+        let data = ok(get(&token, "/v1/operate_logs?module=cursor_test&size=2&count=0").await);
+        assert!(data.get("total").is_none());
+        assert_eq!(data["has_more"], true);
+
+        // 游标分页：按记录ID倒序，依次取到全部记录
+        let mut ids: Vec<i64> = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let uri = format!("/v1/operate_logs?module=cursor_test&size=2&cursor={}", cursor);
+            let data = ok(get(&token, &uri).await);
+            assert!(data.get("total").is_none() && data.get("page").is_none());
+            ids.extend(data["list"].as_array().unwrap().iter().map(|v| v["operate_log_id"].as_i64().unwrap()));
+            match &data["next_cursor"] {
+                Value::String(v) => cursor = v.clone(),
+                _ => break,
+            }
+        }
+        assert_eq!(ids.len(), 5);
+        assert!(ids.windows(2).all(|v| v[0] > v[1]));
+
+        assert_code(&get(&token, "/v1/operate_logs?cursor=invalid!").await, 10000);
+        assert_code(&get(&token, "/v1/operate_logs?cursor=&page=2").await, 10000);
+        assert_code(&get(&token, "/v1/employees?cursor=").await, 10000);
+    });
+}
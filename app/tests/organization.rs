@@ -0,0 +1,187 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{admin, assert_code, delete, find, get, ok, post, run};
+
+#[test]
+fn department() {
+    run(async {
+        let token = admin().await;
+        let root = common::root_department(&token).await;
+
+        let body = json!({ "deptname": "研发部", "sort": 1, "managerid": 0, "parentid": root });
+        ok(post(&token, "/v1/departments", body.clone()).await);
+        // 名称重复
+        assert!(post(&token, "/v1/departments", body).await["err"] == true);
+
+        let data = ok(get(&token, "/v1/departments?deptname=研发部").await);
+        assert_eq!(data["total"], 1);
+        let department = find(&data["list"], "department_name", "研发部").unwrap();
+        let department_id = department["department_id"].as_i64().unwrap();
+        assert_eq!(department["parent_id"], root);
+        // 过滤参数与排序字段、返回字段同名
+        let data = ok(get(&token, "/v1/departments?department_name=研发部&sort=department_name").await);
+        assert_eq!(data["total"], 1);
+
+        let body = json!({
+            "deptid": department_id,
+            "deptname": "研发中心",
+            "sort": 2,
+            "managerid": 0,
+            "parentid": root,
+            "create_time": 0,
+            "create_time_str": "",
+        });
+        ok(post(&token, "/v1/departments/update", body).await);
+        let data = ok(get(&token, &format!("/v1/departments/{}", department_id)).await);
+        assert_eq!(data["department_name"], "研发中心");
+        assert_eq!(data["sort"], 2);
+
+        let tree = ok(get(&token, "/v1/departments/select_list").await);
+        assert!(tree.to_string().contains("研发中心"));
+
+        // 有子部门时不能删除
+        assert!(delete(&token, &format!("/v1/departments/{}", root)).await["err"] == true);
+        ok(delete(&token, &format!("/v1/departments/{}", department_id)).await);
+        let data = ok(get(&token, "/v1/departments?deptname=研发中心").await);
+        assert_eq!(data["total"], 0);
+    });
+}
+
+#[test]
+fn position() {
+    run(async {
+        let token = admin().await;
+
+        let body = json!({ "postname": "工程师", "level": "P5", "sort": 1, "remark": "" });
+        ok(post(&token, "/v1/positions", body.clone()).await);
+        assert!(post(&token, "/v1/positions", body).await["err"] == true);
+        // 名称必填
+        let body = json!({ "postname": "", "level": "", "sort": 0, "remark": "" });
+        assert_code(&post(&token, "/v1/positions", body).await, 10000);
+
+        let data = ok(get(&token, "/v1/positions?postname=工程师").await);
+        assert_eq!(data["total"], 1);
+        let postid = find(&data["list"], "postname", "工程师").unwrap()["postid"].as_i64().unwrap();
+
+        let body = json!({
+            "postid": postid,
+            "postname": "高级工程师",
+            "level": "P6",
+            "sort": 2,
+            "remark": "",
+            "create_time": 0,
+            "create_time_str": "",
+        });
+        ok(post(&token, "/v1/positions/update", body).await);
+        let data = ok(get(&token, &format!("/v1/positions/{}", postid)).await);
+        assert_eq!(data["postname"], "高级工程师");
+        assert_eq!(data["level"], "P6");
+
+        let list = ok(get(&token, "/v1/positions/select_list").await);
+        assert!(find(&list, "postname", "高级工程师").is_some());
+
+        ok(delete(&token, &format!("/v1/positions/{}", postid)).await);
+        let data = ok(get(&token, "/v1/positions?postname=工程师").await);
+        assert_eq!(data["total"], 0);
+    });
+}
+
+#[test]
+fn employee() {
+    run(async {
+        let common::Fixture { token, root, employee_id, user, .. } = common::fixture("employee", None, 1).await;
+
+        // 登录名重复
+        let body = json!({
+            "realname": "employee",
+            "phone": "13700000000",
+            "department_id": root,
+            "login_name": "employee",
+            "email": "employee@example.com",
+            "gender": 1,
+            "disabled_flag": 0,
+            "position_id": 0,
+        });
+        assert!(post(&token, "/v1/employees", body).await["err"] == true);
+
+        let data = ok(get(&token, &format!("/v1/employees/{}", employee_id)).await);
+        assert_eq!(data["login_name"], "employee");
+        assert_eq!(data["department_id"], root);
+
+        let body = json!({
+            "employee_id": employee_id,
+            "realname": "员工",
+            "phone": "13700000001",
+            "department_id": root,
+            "login_name": "employee",
+            "email": "employee@example.com",
+            "gender": 2,
+            "disabled_flag": 0,
+            "position_id": 0,
+        });
+        ok(post(&token, "/v1/employees/update", body).await);
+        let data = ok(get(&token, "/v1/employees?login_name=employee").await);
+        let employee = find(&data["list"], "login_name", "employee").unwrap();
+        assert_eq!(employee["realname"], "员工");
+        assert_eq!(employee["gender"], 2);
+
+        let me = ok(get(&user, "/v1/me").await);
+        assert_eq!(me["realname"], "员工");
+
+        let list = ok(get(&token, "/v1/employees/employee_select_list").await);
+        assert!(list.to_string().contains("员工"));
+
+        // 修改为禁用后已登录的会话失效
+        let body = json!({
+            "employee_id": employee_id,
+            "realname": "员工",
+            "phone": "13700000001",
+            "department_id": root,
+            "login_name": "employee",
+            "email": "employee@example.com",
+            "gender": 2,
+            "disabled_flag": 1,
+            "position_id": 0,
+        });
+        ok(post(&token, "/v1/employees/update", body).await);
+        assert_code(&get(&user, "/v1/me").await, 20000);
+    });
+}
+
+#[test]
+fn list_query() {
+    run(async {
+        let common::Fixture { token, root, role_id, .. } = common::fixture("list_query_a", None, 1).await;
+        common::employee(&token, "list_query_b", root, role_id).await;
+
+        // 过滤及排序
+        let names = |data: Value| -> Vec<String> {
+            data["list"].as_array().unwrap().iter().map(|v| v["login_name"].as_str().unwrap().to_string()).collect()
+        };
+        let uri = format!("/v1/employees?login_name=list_query_&department_id={}&sort=login_name", root);
+        let data = ok(get(&token, &uri).await);
+        assert_eq!(data["total"], 2);
+        assert_eq!(names(data), ["list_query_a", "list_query_b"]);
+        let data = ok(get(&token, "/v1/employees?login_name=list_query_&sort=-login_name&size=1").await);
+        assert_eq!(names(data), ["list_query_b"]);
+        let data = ok(get(&token, "/v1/employees?login_name=list_query_&sort=-login_name&size=1&page=2").await);
+        assert_eq!(data["total"], 2);
+        assert_eq!(data["has_more"], false);
+        assert_eq!(names(data), ["list_query_a"]);
+        let data = ok(get(&token, "/v1/employees?login_name=list_query_&disabled_flag=1").await);
+        assert_eq!(data["total"], 0);
+
+        // 未声明的字段、排序字段，错误的值及分页参数
+        assert_code(&get(&token, "/v1/employees?unknown=1").await, 10000);
+        assert_code(&get(&token, "/v1/employees?sort=login_pwd").await, 10000);
+        assert_code(&get(&token, "/v1/employees?department_id=a").await, 10000);
+        assert_code(&get(&token, "/v1/employees?size=101").await, 10000);
+        assert_code(&get(&token, "/v1/roles?postname=x").await, 10000);
+        assert_code(&get(&token, "/v1/roles/role_emp_list").await, 10000);
+
+        let uri = format!("/v1/roles/role_emp_list?roleid={}&login_name=list_query_&sort=login_name", role_id);
+        assert_eq!(names(ok(get(&token, &uri).await)), ["list_query_a", "list_query_b"]);
+    });
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{assert_code, delete, find, get, login_reply, ok, post, run, PASSWORD};
+
+#[test]
+fn recycle_bin() {
+    run(async {
+        let common::Fixture { token, department_id, role_id, employee_id, .. } =
+            common::fixture("recycle_a", Some("回收部"), 1).await;
+
+        let bin = |kind: &str| format!("/v1/recycle_bin?type={}", kind);
+        let ids = |data: &Value| -> Vec<i64> {
+            data["list"].as_array().unwrap().iter().map(|v| v["id"].as_i64().unwrap()).collect()
+        };
+
+        // 已分配员工的部门、角色不能删除
+        assert_code(&delete(&token, &format!("/v1/departments/{}", department_id)).await, 30000);
+        assert_code(&delete(&token, &format!("/v1/roles/{}", role_id)).await, 30000);
+
+        // 禁用后不能登录，已登录的会话失效
+        let auth_token = common::login("recycle_a", PASSWORD).await;
+        ok(get(&token, &format!("/v1/employees/disabled_flag/{}/1", employee_id)).await);
+        let data = ok(get(&token, &format!("/v1/employees/{}", employee_id)).await);
+        assert_eq!(data["disabled_flag"], 1);
+        assert_code(&login_reply("recycle_a", PASSWORD).await, 20000);
+        assert_code(&get(&auth_token, "/v1/me").await, 20000);
+        assert_code(&get(&token, &format!("/v1/employees/disabled_flag/{}/2", employee_id)).await, 10000);
+        ok(get(&token, &format!("/v1/employees/disabled_flag/{}/0", employee_id)).await);
+        common::login("recycle_a", PASSWORD).await;
+
+        // 删除员工：列表中不再显示，进入回收站，不能登录
+        ok(delete(&token, &format!("/v1/employees/{}", employee_id)).await);
+        let data = ok(get(&token, "/v1/employees?login_name=recycle_a").await);
+        assert_eq!(data["total"], 0);
+        assert_code(&get(&token, &format!("/v1/employees/{}", employee_id)).await, 40000);
+        let data = ok(get(&token, &bin("employee")).await);
+        let item = find(&data["list"], "name", "recycle_a（recycle_a）").unwrap();
+        assert_eq!(item["id"], employee_id);
+        assert_code(&login_reply("recycle_a", PASSWORD).await, 20000);
+        // 不能删除自己
+        let data = ok(get(&token, "/v1/me").await);
+        let admin_id = data["employee_id"].as_i64().unwrap();
+        assert_code(&delete(&token, &format!("/v1/employees/{}", admin_id)).await, 30000);
+
+        // 员工已删除，可删除角色及部门
+        ok(delete(&token, &format!("/v1/roles/{}", role_id)).await);
+        let data = ok(get(&token, "/v1/roles?rolename=recycle").await);
+        assert_eq!(data["total"], 0);
+        assert!(ids(&ok(get(&token, &bin("role")).await)).contains(&role_id));
+        ok(delete(&token, &format!("/v1/departments/{}", department_id)).await);
+        let data = ok(get(&token, "/v1/departments?deptname=回收部").await);
+        assert_eq!(data["total"], 0);
+        assert!(!ok(get(&token, "/v1/departments/select_list").await).to_string().contains("回收部"));
+
+        // 部门已删除时不能恢复员工；先恢复部门、角色
+        let uri = format!("/v1/recycle_bin/employee/{}/restore", employee_id);
+        assert_code(&post(&token, &uri, json!({})).await, 30000);
+        ok(post(&token, &format!("/v1/recycle_bin/department/{}/restore", department_id), json!({})).await);
+        ok(post(&token, &format!("/v1/recycle_bin/role/{}/restore", role_id), json!({})).await);
+        ok(post(&token, &uri, json!({})).await);
+        assert!(!ids(&ok(get(&token, &bin("employee")).await)).contains(&employee_id));
+        let data = ok(get(&token, "/v1/employees?login_name=recycle_a").await);
+        assert_eq!(data["total"], 1);
+        common::login("recycle_a", PASSWORD).await;
+        // 不在回收站中
+        assert_code(&post(&token, &uri, json!({})).await, 40000);
+        assert_code(&delete(&token, &format!("/v1/recycle_bin/employee/{}", employee_id)).await, 40000);
+
+        // 永久删除职务
+        let body = json!({ "postname": "回收岗", "level": "P1", "sort": 1, "remark": "" });
+        ok(post(&token, "/v1/positions", body).await);
+        let data = ok(get(&token, "/v1/positions?postname=回收岗").await);
+        let postid = data["list"][0]["postid"].as_i64().unwrap();
+        ok(delete(&token, &format!("/v1/positions/{}", postid)).await);
+        assert_code(&get(&token, &format!("/v1/positions/{}", postid)).await, 40000);
+        let data = ok(get(&token, &format!("{}&name=回收", bin("position"))).await);
+        assert_eq!(ids(&data), [postid]);
+        ok(delete(&token, &format!("/v1/recycle_bin/position/{}", postid)).await);
+        assert!(!ids(&ok(get(&token, &bin("position")).await)).contains(&postid));
+        assert_code(&post(&token, &format!("/v1/recycle_bin/position/{}/restore", postid), json!({})).await, 40000);
+
+        // 永久删除员工后可删除并永久删除角色，角色关联一并清除
+        ok(delete(&token, &format!("/v1/employees/{}", employee_id)).await);
+        ok(delete(&token, &format!("/v1/recycle_bin/employee/{}", employee_id)).await);
+        ok(delete(&token, &format!("/v1/roles/{}", role_id)).await);
+        ok(delete(&token, &format!("/v1/recycle_bin/role/{}", role_id)).await);
+        assert!(!ids(&ok(get(&token, &bin("role")).await)).contains(&role_id));
+
+        // 部门下存在已删除的员工时不能永久删除部门，员工永久删除后可以
+        let role_id = common::role(&token, "recycle_b", 1).await;
+        let (employee_id, _) = common::employee(&token, "recycle_b", department_id, role_id).await;
+        ok(delete(&token, &format!("/v1/employees/{}", employee_id)).await);
+        ok(delete(&token, &format!("/v1/departments/{}", department_id)).await);
+        // 已删除的部门不能用作角色的数据范围
+        let body = json!({ "role_id": role_id, "department_ids": [department_id] });
+        assert_code(&post(&token, "/v1/roles/role_dept_update", body).await, 10000);
+        let uri = format!("/v1/recycle_bin/department/{}", department_id);
+        assert_code(&delete(&token, &uri).await, 30000);
+        assert!(ids(&ok(get(&token, &bin("department")).await)).contains(&department_id));
+        ok(delete(&token, &format!("/v1/recycle_bin/employee/{}", employee_id)).await);
+        ok(delete(&token, &uri).await);
+        assert!(!ids(&ok(get(&token, &bin("department")).await)).contains(&department_id));
+
+        // 错误的数据类型
+        assert_code(&get(&token, "/v1/recycle_bin").await, 10000);
+        assert_code(&get(&token, &bin("user")).await, 10000);
+        assert_code(&delete(&token, "/v1/recycle_bin/user/1").await, 10000);
+    });
+}
+
+#[test]
+fn position() {
+    run(async {
+        let common::Fixture { token, root, employee_id, .. } = common::fixture("bin_position", None, 1).await;
+        let body = json!({ "postname": "回收职务", "level": "P1", "sort": 1, "remark": "" });
+        ok(post(&token, "/v1/positions", body).await);
+        let data = ok(get(&token, "/v1/positions?postname=回收职务").await);
+        let postid = data["list"][0]["postid"].as_i64().unwrap();
+        let data = ok(get(&token, &format!("/v1/employees/{}", employee_id)).await);
+        let body = json!({
+            "employee_id": employee_id,
+            "realname": "bin_position",
+            "phone": data["phone"],
+            "department_id": root,
+            "login_name": "bin_position",
+            "email": data["email"],
+            "gender": 1,
+            "disabled_flag": 0,
+            "position_id": postid,
+        });
+        ok(post(&token, "/v1/employees/update", body).await);
+
+        // 已分配员工的职务不能删除
+        assert_code(&delete(&token, &format!("/v1/positions/{}", postid)).await, 30000);
+
+        // 回收站中存在该职务的员工时不能永久删除职务，员工永久删除后可以
+        ok(delete(&token, &format!("/v1/employees/{}", employee_id)).await);
+        // 回收站中的员工不能重置密码
+        assert_code(&get(&token, &format!("/v1/employees/reset_password/{}", employee_id)).await, 40000);
+        ok(delete(&token, &format!("/v1/positions/{}", postid)).await);
+        let uri = format!("/v1/recycle_bin/position/{}", postid);
+        assert_code(&delete(&token, &uri).await, 30000);
+
+        // 职务已删除时不能恢复员工；先恢复职务
+        let restore = format!("/v1/recycle_bin/employee/{}/restore", employee_id);
+        assert_code(&post(&token, &restore, json!({})).await, 30000);
+        ok(post(&token, &format!("/v1/recycle_bin/position/{}/restore", postid), json!({})).await);
+        ok(post(&token, &restore, json!({})).await);
+        ok(delete(&token, &format!("/v1/employees/{}", employee_id)).await);
+        ok(delete(&token, &format!("/v1/positions/{}", postid)).await);
+        ok(delete(&token, &format!("/v1/recycle_bin/employee/{}", employee_id)).await);
+        ok(delete(&token, &uri).await);
+    });
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use serde_json::{json, Value};
+
+use common::{admin, assert_code, delete, find, get, ok, post, run};
+
+#[test]
+fn role() {
+    run(async {
+        let token = admin().await;
+        let root = common::root_department(&token).await;
+        let role_id = common::role(&token, "role", 5).await;
+
+        // 编码重复、数据范围不正确
+        let body = json!({ "rolename": "role2", "rolecode": "role", "remark": "" });
+        assert!(post(&token, "/v1/roles", body).await["err"] == true);
+        let body = json!({ "rolename": "role2", "rolecode": "role2", "remark": "", "data_scope": 9 });
+        assert_code(&post(&token, "/v1/roles", body).await, 10000);
+
+        let body = json!({
+            "roleid": role_id,
+            "rolename": "角色",
+            "rolecode": "role",
+            "remark": "备注",
+            "data_scope": 2,
+            "create_time": 0,
+            "create_time_str": "",
+        });
+        ok(post(&token, "/v1/roles/update", body).await);
+        let data = ok(get(&token, &format!("/v1/roles/{}", role_id)).await);
+        assert_eq!(data["rolename"], "角色");
+        assert_eq!(data["data_scope"], 2);
+
+        let list = ok(get(&token, "/v1/roles/select_list").await);
+        assert!(list.to_string().contains("角色"));
+
+        /* 功能权限 */
+        let menu_ids = common::menu_ids(&token, &["system:role:query", "system:role:update"]).await;
+        let body = json!({ "role_id": role_id, "menu_ids": menu_ids });
+        ok(post(&token, "/v1/roles/role_func_update", body).await);
+        let data = ok(get(&token, &format!("/v1/roles/role_func_id/{}", role_id)).await);
+        assert_eq!(data.as_array().unwrap().len(), 2);
+        let body = json!({ "role_id": role_id, "menu_ids": [0] });
+        assert_code(&post(&token, "/v1/roles/role_func_update", body).await, 10000);
+
+        /* 数据范围 */
+        let body = json!({ "role_id": role_id, "department_ids": [root] });
+        ok(post(&token, "/v1/roles/role_dept_update", body).await);
+        let data = ok(get(&token, &format!("/v1/roles/role_dept_id/{}", role_id)).await);
+        assert_eq!(data, json!([root]));
+
+        /* 成员 */
+        let (employee_id, _) = common::employee(&token, "role_member", root, role_id).await;
+        let data = ok(get(&token, &format!("/v1/roles/role_emp_list?roleid={}", role_id)).await);
+        assert_eq!(data["total"], 1);
+        let body = json!({ "role_id": role_id, "employee_ids": [employee_id] });
+        assert_code(&post(&token, "/v1/roles/role_emp_add", body.clone()).await, 10000);
+
+        // 存在成员时不能删除
+        assert!(delete(&token, &format!("/v1/roles/{}", role_id)).await["err"] == true);
+        ok(post(&token, "/v1/roles/role_emp_remove", body).await);
+        ok(delete(&token, &format!("/v1/roles/{}", role_id)).await);
+        let data = ok(get(&token, "/v1/roles?rolename=角色").await);
+        assert!(find(&data["list"], "rolecode", "role").is_none());
+    });
+}
+
+#[test]
+fn menu() {
+    run(async {
+        let token = admin().await;
+
+        let body = json!({ "menu_name": "测试目录", "menu_type": 1, "path": "/test", "sort": 99 });
+        ok(post(&token, "/v1/menus", body).await);
+        let catalog_id = menu_id(&token, "测试目录").await;
+
+        let body = json!({
+            "menu_name": "测试菜单",
+            "menu_type": 2,
+            "parent_id": catalog_id,
+            "path": "/test/menu",
+            "component": "/test/menu/index.vue",
+        });
+        ok(post(&token, "/v1/menus", body).await);
+        let menu_id = menu_id(&token, "测试菜单").await;
+
+        // 外链菜单需填写外链地址
+        let body = json!({ "menu_name": "外链", "menu_type": 2, "frame_flag": 1 });
+        assert_code(&post(&token, "/v1/menus", body).await, 10000);
+
+        let body = json!({
+            "menu_id": menu_id,
+            "menu_name": "测试菜单2",
+            "menu_type": 2,
+            "parent_id": catalog_id,
+            "path": "/test/menu",
+            "component": "/test/menu/index.vue",
+        });
+        ok(post(&token, "/v1/menus/update", body).await);
+        let data = ok(get(&token, &format!("/v1/menus/{}", menu_id)).await);
+        assert_eq!(data["menu_name"], "测试菜单2");
+        assert_eq!(data["parent_id"], catalog_id);
+
+        ok(get(&token, &format!("/v1/menus/visible_flag/{}/0", menu_id)).await);
+        ok(get(&token, &format!("/v1/menus/disabled_flag/{}/1", menu_id)).await);
+        let data = ok(get(&token, &format!("/v1/menus/{}", menu_id)).await);
+        assert_eq!(data["visible_flag"], 0);
+        assert_eq!(data["disabled_flag"], 1);
+        assert_code(&get(&token, &format!("/v1/menus/disabled_flag/{}/2", menu_id)).await, 10000);
+
+        let body = json!([{ "menu_id": menu_id, "sort": 5 }]);
+        ok(post(&token, "/v1/menus/sort", body).await);
+        assert_eq!(ok(get(&token, &format!("/v1/menus/{}", menu_id)).await)["sort"], 5);
+        // 存在不存在的菜单时全部不生效
+        let body = json!([{ "menu_id": menu_id, "sort": 6 }, { "menu_id": 0, "sort": 7 }]);
+        assert_code(&post(&token, "/v1/menus/sort", body).await, 10000);
+        assert_eq!(ok(get(&token, &format!("/v1/menus/{}", menu_id)).await)["sort"], 5);
+
+        // 存在下级菜单的目录不能修改为菜单
+        let body = json!({
+            "menu_id": catalog_id,
+            "menu_name": "测试目录",
+            "menu_type": 2,
+            "path": "/test",
+            "sort": 99,
+        });
+        let reply = post(&token, "/v1/menus/update", body).await;
+        assert_code(&reply, 10000);
+        assert!(reply["msg"].as_str().unwrap().contains("下级菜单"), "{}", reply);
+        assert_eq!(ok(get(&token, &format!("/v1/menus/{}", catalog_id)).await)["menu_type"], 1);
+
+        // 存在子菜单时不能删除
+        assert!(delete(&token, &format!("/v1/menus/{}", catalog_id)).await["err"] == true);
+        ok(delete(&token, &format!("/v1/menus/{}", menu_id)).await);
+        ok(delete(&token, &format!("/v1/menus/{}", catalog_id)).await);
+        let tree = ok(get(&token, "/v1/menus").await);
+        assert!(!tree.to_string().contains("测试目录"));
+    });
+}
+
+// 按名称在菜单树中查找菜单ID
+async fn menu_id(token: &str, name: &str) -> i64 {
+    fn walk(nodes: &Value, name: &str) -> Option<i64> {
+        nodes.as_array()?.iter().find_map(|node| {
+            if node["menu_name"] == name {
+                return node["menu_id"].as_i64();
+            }
+            walk(&node["children"], name)
+        })
+    }
+
+    let tree = ok(get(token, "/v1/menus").await);
+    walk(&tree, name).expect("菜单不存在")
+}
@@ -0,0 +1,20 @@
+pub use sea_orm_migration::prelude::*;
+
+mod m20240930_000001_init;
+mod m20241008_000001_legacy_schema;
+mod m20241020_000001_signed_flags;
+mod m20241101_000001_soft_delete;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20240930_000001_init::Migration),
+            Box::new(m20241008_000001_legacy_schema::Migration),
+            Box::new(m20241020_000001_signed_flags::Migration),
+            Box::new(m20241101_000001_soft_delete::Migration),
+        ]
+    }
+}
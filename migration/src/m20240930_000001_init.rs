@@ -0,0 +1,657 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+/// 初始表结构
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 部门
+        if !exists(manager, TDepartment::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TDepartment::Table)
+                        .comment("部门")
+                        .col(id(TDepartment::DepartmentId, "部门主键id"))
+                        .col(string(TDepartment::DepartmentName, 50, "部门名称"))
+                        .col(big(TDepartment::ManagerId, "部门负责人id"))
+                        .col(big(TDepartment::ParentId, "部门的父级id"))
+                        .col(int(TDepartment::Sort, "部门排序"))
+                        .col(big(TDepartment::UpdateTime, "更新时间"))
+                        .col(big(TDepartment::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TDepartment::Table, "idx_department_parent_id", [TDepartment::ParentId], false).await?;
+        }
+
+        // 员工
+        if !exists(manager, TEmployee::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TEmployee::Table)
+                        .comment("员工表")
+                        .col(id(TEmployee::EmployeeId, "主键"))
+                        .col(string(TEmployee::LoginName, 30, "登录帐号"))
+                        .col(string(TEmployee::LoginPwd, 255, "登录密码(PHC格式哈希)"))
+                        .col(string(TEmployee::Realname, 30, "员工名称"))
+                        .col(string(TEmployee::Avatar, 200, "头像"))
+                        .col(tiny(TEmployee::Gender, 0, "性别"))
+                        .col(string(TEmployee::Phone, 15, "手机号码"))
+                        .col(big(TEmployee::DepartmentId, "部门id"))
+                        .col(big(TEmployee::PositionId, "职务ID"))
+                        .col(string(TEmployee::Email, 100, "邮箱"))
+                        .col(tiny(TEmployee::DisabledFlag, 0, "是否被禁用 0否1是"))
+                        .col(tiny(TEmployee::DeletedFlag, 0, "是否删除0否 1是"))
+                        .col(tiny(TEmployee::AdministratorFlag, 0, "是否为超级管理员: 0 不是，1是"))
+                        .col(tiny(TEmployee::MustChangePassword, 0, "是否需要修改密码 0否 1是"))
+                        .col(string(TEmployee::LoginToken, 32, "最近一次登录的会话token"))
+                        .col(big(TEmployee::LoginAt, "最近一次登录时间"))
+                        .col(string(TEmployee::Remark, 200, "备注"))
+                        .col(big(TEmployee::UpdateTime, "更新时间"))
+                        .col(big(TEmployee::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TEmployee::Table, "uk_employee_login_name", [TEmployee::LoginName], true).await?;
+            index(manager, TEmployee::Table, "idx_employee_department_id", [TEmployee::DepartmentId], false).await?;
+        }
+
+        // 员工两步验证
+        if !exists(manager, TEmployeeTotp::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TEmployeeTotp::Table)
+                        .comment("员工两步验证")
+                        .col(
+                            ColumnDef::new(TEmployeeTotp::EmployeeId)
+                                .big_integer()
+                                .not_null()
+                                .primary_key()
+                                .comment("员工id"),
+                        )
+                        .col(string(TEmployeeTotp::Secret, 64, "TOTP密钥(Base32)"))
+                        .col(tiny(TEmployeeTotp::EnabledFlag, 0, "是否已启用 0否 1是"))
+                        .col(text(TEmployeeTotp::RecoveryCodes, "恢复码哈希(sha256)，逗号分隔").not_null())
+                        .col(big(TEmployeeTotp::LastStep, "最近一次使用的时间步，防止重放"))
+                        .col(big(TEmployeeTotp::UpdateTime, "更新时间"))
+                        .col(big(TEmployeeTotp::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 登录会话
+        if !exists(manager, TLoginSession::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TLoginSession::Table)
+                        .comment("登录会话")
+                        .col(id(TLoginSession::SessionId, "主键"))
+                        .col(big(TLoginSession::EmployeeId, "员工id"))
+                        .col(string(TLoginSession::LoginToken, 32, "会话token"))
+                        .col(string(TLoginSession::Device, 100, "登录设备"))
+                        .col(string(TLoginSession::Ip, 50, "登录IP"))
+                        .col(string(TLoginSession::UserAgent, 500, "user-agent"))
+                        .col(big(TLoginSession::LastSeenTime, "最近活跃时间"))
+                        .col(big(TLoginSession::ExpireTime, "过期时间"))
+                        .col(big(TLoginSession::UpdateTime, "更新时间"))
+                        .col(big(TLoginSession::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TLoginSession::Table, "uk_login_session_login_token", [TLoginSession::LoginToken], true).await?;
+            index(manager, TLoginSession::Table, "idx_login_session_employee_id", [TLoginSession::EmployeeId], false).await?;
+        }
+
+        // 菜单
+        if !exists(manager, TMenu::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TMenu::Table)
+                        .comment("菜单表")
+                        .col(id(TMenu::MenuId, "菜单ID"))
+                        .col(string(TMenu::MenuName, 200, "菜单名称"))
+                        .col(int(TMenu::MenuType, "类型"))
+                        .col(big(TMenu::ParentId, "父菜单ID"))
+                        .col(ColumnDef::new(TMenu::Sort).integer().null().comment("显示顺序"))
+                        .col(ColumnDef::new(TMenu::Path).string_len(255).null().comment("路由地址"))
+                        .col(ColumnDef::new(TMenu::Component).string_len(255).null().comment("组件路径"))
+                        .col(ColumnDef::new(TMenu::PermsType).integer().null().comment("权限类型"))
+                        .col(text(TMenu::ApiPerms, "后端权限字符串").null())
+                        .col(text(TMenu::WebPerms, "前端权限字符串").null())
+                        .col(ColumnDef::new(TMenu::Icon).string_len(100).null().comment("菜单图标"))
+                        .col(ColumnDef::new(TMenu::ContextMenuId).big_integer().null().comment("功能点关联菜单ID"))
+                        .col(tiny(TMenu::FrameFlag, 0, "是否为外链"))
+                        .col(text(TMenu::FrameUrl, "外链地址").null())
+                        .col(tiny(TMenu::CacheFlag, 0, "是否缓存"))
+                        .col(tiny(TMenu::VisibleFlag, 1, "显示状态"))
+                        .col(tiny(TMenu::DisabledFlag, 0, "禁用状态"))
+                        .col(tiny(TMenu::DeletedFlag, 0, "删除状态"))
+                        .col(big(TMenu::CreateUserId, "创建人"))
+                        .col(big(TMenu::CreateTime, "创建时间"))
+                        .col(ColumnDef::new(TMenu::UpdateUserId).big_integer().null().comment("更新人"))
+                        .col(big(TMenu::UpdateTime, "更新时间"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 操作记录
+        if !exists(manager, TOperateLog::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TOperateLog::Table)
+                        .comment("操作记录")
+                        .col(id(TOperateLog::OperateLogId, "主键"))
+                        .col(big(TOperateLog::OperateUserId, "用户id"))
+                        .col(int(TOperateLog::OperateUserType, "用户类型"))
+                        .col(string(TOperateLog::OperateUserName, 50, "用户名称"))
+                        .col(ColumnDef::new(TOperateLog::Module).string_len(50).null().comment("操作模块"))
+                        .col(ColumnDef::new(TOperateLog::Content).string_len(500).null().comment("操作内容"))
+                        .col(ColumnDef::new(TOperateLog::Url).string_len(100).null().comment("请求路径"))
+                        .col(ColumnDef::new(TOperateLog::Method).string_len(100).null().comment("请求方法"))
+                        .col(text(TOperateLog::Param, "请求参数").null())
+                        .col(ColumnDef::new(TOperateLog::Ip).string_len(255).null().comment("请求ip"))
+                        .col(ColumnDef::new(TOperateLog::IpRegion).string_len(1000).null().comment("请求ip地区"))
+                        .col(text(TOperateLog::UserAgent, "请求user-agent").null())
+                        .col(ColumnDef::new(TOperateLog::SuccessFlag).tiny_integer().null().comment("请求结果 0失败 1成功"))
+                        .col(long_text(manager, TOperateLog::FailReason, "失败原因").null())
+                        .col(big(TOperateLog::UpdateTime, "更新时间"))
+                        .col(big(TOperateLog::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TOperateLog::Table, "idx_operate_log_operate_user_id", [TOperateLog::OperateUserId], false).await?;
+            index(manager, TOperateLog::Table, "idx_operate_log_create_time", [TOperateLog::CreateTime], false).await?;
+        }
+
+        // 历史密码
+        if !exists(manager, TPasswordHistory::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TPasswordHistory::Table)
+                        .comment("历史密码")
+                        .col(id(TPasswordHistory::Id, "主键"))
+                        .col(big(TPasswordHistory::EmployeeId, "员工id"))
+                        .col(string(TPasswordHistory::LoginPwd, 255, "历史密码(PHC格式哈希)"))
+                        .col(big(TPasswordHistory::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TPasswordHistory::Table, "idx_password_history_employee_id", [TPasswordHistory::EmployeeId], false).await?;
+        }
+
+        // 职务
+        if !exists(manager, TPosition::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TPosition::Table)
+                        .comment("职务表")
+                        .col(id(TPosition::PositionId, "职务ID"))
+                        .col(string(TPosition::PositionName, 200, "职务名称"))
+                        .col(string(TPosition::Level, 200, "职级"))
+                        .col(int(TPosition::Sort, "排序"))
+                        .col(string(TPosition::Remark, 200, "备注"))
+                        .col(tiny(TPosition::DeletedFlag, 0, "是否删除 0否 1是"))
+                        .col(big(TPosition::CreateTime, "创建时间"))
+                        .col(big(TPosition::UpdateTime, "更新时间"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 刷新令牌
+        if !exists(manager, TRefreshToken::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TRefreshToken::Table)
+                        .comment("刷新令牌")
+                        .col(id(TRefreshToken::Id, "主键"))
+                        .col(big(TRefreshToken::EmployeeId, "员工id"))
+                        .col(string(TRefreshToken::LoginToken, 32, "所属登录会话"))
+                        .col(string(TRefreshToken::TokenHash, 64, "刷新令牌哈希(sha256)"))
+                        .col(big(TRefreshToken::ParentId, "轮换前的令牌id"))
+                        .col(tiny(TRefreshToken::UsedFlag, 0, "是否已轮换 0否 1是"))
+                        .col(tiny(TRefreshToken::RevokedFlag, 0, "是否已撤销 0否 1是"))
+                        .col(big(TRefreshToken::ExpireTime, "过期时间"))
+                        .col(big(TRefreshToken::UpdateTime, "更新时间"))
+                        .col(big(TRefreshToken::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TRefreshToken::Table, "uk_refresh_token_token_hash", [TRefreshToken::TokenHash], true).await?;
+            index(manager, TRefreshToken::Table, "idx_refresh_token_login_token", [TRefreshToken::LoginToken], false).await?;
+        }
+
+        // 角色
+        if !exists(manager, TRole::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TRole::Table)
+                        .comment("角色表")
+                        .col(id(TRole::RoleId, "主键"))
+                        .col(string(TRole::RoleName, 20, "角色名称"))
+                        .col(string(TRole::RoleCode, 500, "角色编码"))
+                        .col(string(TRole::Remark, 255, "角色描述"))
+                        .col(tiny(TRole::MfaFlag, 0, "是否要求两步验证 0否 1是"))
+                        .col(tiny(TRole::DataScope, 1, "数据范围 1全部 2自定义部门 3本部门 4本部门及以下 5仅本人"))
+                        .col(big(TRole::UpdateTime, "更新时间"))
+                        .col(big(TRole::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TRole::Table, "uk_role_code", [TRole::RoleCode], true).await?;
+        }
+
+        // 角色-自定义数据范围部门
+        if !exists(manager, TRoleDepartment::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TRoleDepartment::Table)
+                        .comment("角色-自定义数据范围部门")
+                        .col(id(TRoleDepartment::RoleDepartmentId, "主键id"))
+                        .col(big(TRoleDepartment::RoleId, "角色id"))
+                        .col(big(TRoleDepartment::DepartmentId, "部门id"))
+                        .col(big(TRoleDepartment::UpdateTime, "更新时间"))
+                        .col(big(TRoleDepartment::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(
+                manager,
+                TRoleDepartment::Table,
+                "uk_role_department",
+                [TRoleDepartment::RoleId, TRoleDepartment::DepartmentId],
+                true,
+            )
+            .await?;
+        }
+
+        // 角色-员工
+        if !exists(manager, TRoleEmployee::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TRoleEmployee::Table)
+                        .comment("角色员工功能表")
+                        .col(id(TRoleEmployee::Id, "主键"))
+                        .col(big(TRoleEmployee::RoleId, "角色id"))
+                        .col(big(TRoleEmployee::EmployeeId, "员工id"))
+                        .col(big(TRoleEmployee::UpdateTime, "更新时间"))
+                        .col(big(TRoleEmployee::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(
+                manager,
+                TRoleEmployee::Table,
+                "uk_role_employee",
+                [TRoleEmployee::RoleId, TRoleEmployee::EmployeeId],
+                true,
+            )
+            .await?;
+        }
+
+        // 角色-菜单
+        if !exists(manager, TRoleMenu::Table).await? {
+            manager
+                .create_table(
+                    Table::create()
+                        .table(TRoleMenu::Table)
+                        .comment("角色-菜单")
+                        .col(id(TRoleMenu::RoleMenuId, "主键id"))
+                        .col(big(TRoleMenu::RoleId, "角色id"))
+                        .col(big(TRoleMenu::MenuId, "菜单id"))
+                        .col(big(TRoleMenu::UpdateTime, "更新时间"))
+                        .col(big(TRoleMenu::CreateTime, "创建时间"))
+                        .to_owned(),
+                )
+                .await?;
+            index(manager, TRoleMenu::Table, "idx_role_menu_role_id", [TRoleMenu::RoleId], false).await?;
+            index(manager, TRoleMenu::Table, "idx_role_menu_menu_id", [TRoleMenu::MenuId], false).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let tables = [
+            TDepartment::Table.into_iden(),
+            TEmployee::Table.into_iden(),
+            TEmployeeTotp::Table.into_iden(),
+            TLoginSession::Table.into_iden(),
+            TMenu::Table.into_iden(),
+            TOperateLog::Table.into_iden(),
+            TPasswordHistory::Table.into_iden(),
+            TPosition::Table.into_iden(),
+            TRefreshToken::Table.into_iden(),
+            TRole::Table.into_iden(),
+            TRoleDepartment::Table.into_iden(),
+            TRoleEmployee::Table.into_iden(),
+            TRoleMenu::Table.into_iden(),
+        ];
+        for table in tables {
+            manager
+                .drop_table(Table::drop().table(table).if_exists().to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+// bigint 自增主键
+pub(crate) fn id<T: IntoIden>(col: T, comment: &str) -> ColumnDef {
+    ColumnDef::new(col)
+        .big_integer()
+        .not_null()
+        .auto_increment()
+        .primary_key()
+        .comment(comment)
+        .to_owned()
+}
+
+// bigint NOT NULL DEFAULT 0
+pub(crate) fn big<T: IntoIden>(col: T, comment: &str) -> ColumnDef {
+    ColumnDef::new(col)
+        .big_integer()
+        .not_null()
+        .default(0)
+        .comment(comment)
+        .to_owned()
+}
+
+// int NOT NULL DEFAULT 0
+pub(crate) fn int<T: IntoIden>(col: T, comment: &str) -> ColumnDef {
+    ColumnDef::new(col)
+        .integer()
+        .not_null()
+        .default(0)
+        .comment(comment)
+        .to_owned()
+}
+
+// tinyint NOT NULL
+pub(crate) fn tiny<T: IntoIden>(col: T, default: i8, comment: &str) -> ColumnDef {
+    ColumnDef::new(col)
+        .tiny_integer()
+        .not_null()
+        .default(default)
+        .comment(comment)
+        .to_owned()
+}
+
+// varchar NOT NULL DEFAULT ''
+pub(crate) fn string<T: IntoIden>(col: T, len: u32, comment: &str) -> ColumnDef {
+    ColumnDef::new(col)
+        .string_len(len)
+        .not_null()
+        .default("")
+        .comment(comment)
+        .to_owned()
+}
+
+// text，是否可空由调用方指定（TEXT 列不支持默认值）
+pub(crate) fn text<T: IntoIden>(col: T, comment: &str) -> ColumnDef {
+    ColumnDef::new(col).text().comment(comment).to_owned()
+}
+
+// 字段不存在时添加，已存在则跳过（兼容旧版 SQL 创建的库及重复执行）
+pub(crate) async fn add_column<T: IntoIden>(
+    manager: &SchemaManager<'_>,
+    table: T,
+    mut col: ColumnDef,
+) -> Result<(), DbErr> {
+    let table = table.into_iden();
+    let name = col.get_column_name();
+    if manager.has_column(table.to_string(), &name).await? {
+        return Ok(());
+    }
+    manager
+        .alter_table(Table::alter().table(table).add_column(&mut col).to_owned())
+        .await
+}
+
+// 长文本：MySQL 的 TEXT 仅 64KB，使用 LONGTEXT；其它数据库的 text 不限长度
+fn long_text<T: IntoIden>(manager: &SchemaManager<'_>, col: T, comment: &str) -> ColumnDef {
+    let mut def = ColumnDef::new(col);
+    match manager.get_database_backend() {
+        DbBackend::MySql => def.custom(Alias::new("LONGTEXT")),
+        _ => def.text(),
+    };
+    def.comment(comment).to_owned()
+}
+
+// 表已存在（由旧版 SQL 导入）时跳过，差异由后续迁移修正
+async fn exists<T: Iden>(manager: &SchemaManager<'_>, table: T) -> Result<bool, DbErr> {
+    manager.has_table(table.to_string()).await
+}
+
+async fn index<T, C, I>(
+    manager: &SchemaManager<'_>,
+    table: T,
+    name: &str,
+    cols: I,
+    unique: bool,
+) -> Result<(), DbErr>
+where
+    T: IntoIden + 'static,
+    C: IntoIden,
+    I: IntoIterator<Item = C>,
+{
+    let mut stmt = Index::create();
+    stmt.name(name).table(table);
+    for col in cols {
+        stmt.col(col);
+    }
+    if unique {
+        stmt.unique();
+    }
+    manager.create_index(stmt.to_owned()).await
+}
+
+#[derive(DeriveIden)]
+pub enum TDepartment {
+    Table,
+    DepartmentId,
+    DepartmentName,
+    ManagerId,
+    ParentId,
+    Sort,
+    DeletedFlag,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+pub enum TEmployee {
+    Table,
+    EmployeeId,
+    LoginName,
+    LoginPwd,
+    Realname,
+    Avatar,
+    Gender,
+    Phone,
+    DepartmentId,
+    PositionId,
+    Email,
+    DisabledFlag,
+    DeletedFlag,
+    AdministratorFlag,
+    MustChangePassword,
+    LoginToken,
+    LoginAt,
+    Remark,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum TEmployeeTotp {
+    Table,
+    EmployeeId,
+    Secret,
+    EnabledFlag,
+    RecoveryCodes,
+    LastStep,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum TLoginSession {
+    Table,
+    SessionId,
+    EmployeeId,
+    LoginToken,
+    Device,
+    Ip,
+    UserAgent,
+    LastSeenTime,
+    ExpireTime,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum TMenu {
+    Table,
+    MenuId,
+    MenuName,
+    MenuType,
+    ParentId,
+    Sort,
+    Path,
+    Component,
+    PermsType,
+    ApiPerms,
+    WebPerms,
+    Icon,
+    ContextMenuId,
+    FrameFlag,
+    FrameUrl,
+    CacheFlag,
+    VisibleFlag,
+    DisabledFlag,
+    DeletedFlag,
+    CreateUserId,
+    CreateTime,
+    UpdateUserId,
+    UpdateTime,
+}
+
+#[derive(DeriveIden)]
+enum TOperateLog {
+    Table,
+    OperateLogId,
+    OperateUserId,
+    OperateUserType,
+    OperateUserName,
+    Module,
+    Content,
+    Url,
+    Method,
+    Param,
+    Ip,
+    IpRegion,
+    UserAgent,
+    SuccessFlag,
+    FailReason,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum TPasswordHistory {
+    Table,
+    Id,
+    EmployeeId,
+    LoginPwd,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+pub enum TPosition {
+    Table,
+    PositionId,
+    PositionName,
+    Level,
+    Sort,
+    Remark,
+    DeletedFlag,
+    CreateTime,
+    UpdateTime,
+}
+
+#[derive(DeriveIden)]
+enum TRefreshToken {
+    Table,
+    Id,
+    EmployeeId,
+    LoginToken,
+    TokenHash,
+    ParentId,
+    UsedFlag,
+    RevokedFlag,
+    ExpireTime,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+pub enum TRole {
+    Table,
+    RoleId,
+    RoleName,
+    RoleCode,
+    Remark,
+    MfaFlag,
+    DataScope,
+    DeletedFlag,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum TRoleDepartment {
+    Table,
+    RoleDepartmentId,
+    RoleId,
+    DepartmentId,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum TRoleEmployee {
+    Table,
+    Id,
+    RoleId,
+    EmployeeId,
+    UpdateTime,
+    CreateTime,
+}
+
+#[derive(DeriveIden)]
+enum TRoleMenu {
+    Table,
+    RoleMenuId,
+    RoleId,
+    MenuId,
+    UpdateTime,
+    CreateTime,
+}
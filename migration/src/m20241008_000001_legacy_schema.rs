@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+use crate::m20240930_000001_init::{add_column, big, int, string, tiny, TDepartment, TEmployee, TPosition, TRole};
+
+/// 修正由旧版 `sql/rs_admin.sql`（Navicat 导出）创建的库与实体之间的差异：
+/// 字段改名、补充新增字段、可空字段改为非空
+///
+/// 旧版 SQL 仅支持 MySQL，其它数据库无需处理；对新建的库重复执行也不会产生变化
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::MySql {
+            return Ok(());
+        }
+
+        // t_department.name -> department_name
+        if manager.has_column("t_department", "name").await?
+            && !manager.has_column("t_department", "department_name").await?
+        {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(TDepartment::Table)
+                        .rename_column(Alias::new("name"), TDepartment::DepartmentName)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // 补充新增字段
+        add_column(manager, TEmployee::Table, tiny(TEmployee::MustChangePassword, 0, "是否需要修改密码 0否 1是")).await?;
+        add_column(manager, TRole::Table, tiny(TRole::MfaFlag, 0, "是否要求两步验证 0否 1是")).await?;
+        add_column(
+            manager,
+            TRole::Table,
+            tiny(TRole::DataScope, 1, "数据范围 1全部 2自定义部门 3本部门 4本部门及以下 5仅本人"),
+        )
+        .await?;
+
+        // 可空字段改为非空
+        not_null(manager, TDepartment::Table, big(TDepartment::ManagerId, "部门负责人id"), 0.into()).await?;
+
+        not_null(manager, TEmployee::Table, string(TEmployee::LoginPwd, 255, "登录密码(PHC格式哈希)"), "".into()).await?;
+        not_null(manager, TEmployee::Table, string(TEmployee::Avatar, 200, "头像"), "".into()).await?;
+        not_null(manager, TEmployee::Table, tiny(TEmployee::Gender, 0, "性别"), 0.into()).await?;
+        not_null(manager, TEmployee::Table, string(TEmployee::Phone, 15, "手机号码"), "".into()).await?;
+        not_null(manager, TEmployee::Table, big(TEmployee::DepartmentId, "部门id"), 0.into()).await?;
+        not_null(manager, TEmployee::Table, big(TEmployee::PositionId, "职务ID"), 0.into()).await?;
+        not_null(manager, TEmployee::Table, string(TEmployee::Email, 100, "邮箱"), "".into()).await?;
+        not_null(manager, TEmployee::Table, string(TEmployee::Remark, 200, "备注"), "".into()).await?;
+
+        not_null(manager, TPosition::Table, string(TPosition::Level, 200, "职级"), "".into()).await?;
+        not_null(manager, TPosition::Table, int(TPosition::Sort, "排序"), 0.into()).await?;
+        not_null(manager, TPosition::Table, string(TPosition::Remark, 200, "备注"), "".into()).await?;
+        not_null(manager, TPosition::Table, tiny(TPosition::DeletedFlag, 0, "是否删除 0否 1是"), 0.into()).await?;
+
+        // 角色编码有唯一索引，空值以角色ID补齐
+        not_null(
+            manager,
+            TRole::Table,
+            string(TRole::RoleCode, 500, "角色编码"),
+            Expr::cust("CONCAT('role_', `role_id`)"),
+        )
+        .await?;
+        not_null(manager, TRole::Table, string(TRole::Remark, 255, "角色描述"), "".into()).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // 仅修正旧版数据，无需回滚
+        Ok(())
+    }
+}
+
+// 先以 `fill` 补齐 NULL，再修改字段定义
+async fn not_null<T: IntoIden>(
+    manager: &SchemaManager<'_>,
+    table: T,
+    mut col: ColumnDef,
+    fill: SimpleExpr,
+) -> Result<(), DbErr> {
+    let table = table.into_iden();
+    let name = Alias::new(col.get_column_name());
+    manager
+        .exec_stmt(
+            Query::update()
+                .table(table.clone())
+                .value(name.clone(), fill)
+                .and_where(Expr::col(name).is_null())
+                .to_owned(),
+        )
+        .await?;
+    manager
+        .alter_table(Table::alter().table(table).modify_column(&mut col).to_owned())
+        .await
+}
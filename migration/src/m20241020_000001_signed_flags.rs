@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+use crate::m20240930_000001_init::{tiny, TEmployee};
+
+/// 员工的性别、禁用、删除标记由 `tinyint UNSIGNED` 改为 `tinyint`，
+/// 与其它标记字段一致，使实体可统一映射到 MySQL、PostgreSQL、SQLite
+///
+/// PostgreSQL、SQLite 没有无符号整数，无需处理
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::MySql {
+            return Ok(());
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TEmployee::Table)
+                    .modify_column(tiny(TEmployee::Gender, 0, "性别"))
+                    .modify_column(tiny(TEmployee::DisabledFlag, 0, "是否被禁用 0否1是"))
+                    .modify_column(tiny(TEmployee::DeletedFlag, 0, "是否删除0否 1是"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // 标记值均为 0/1，无需回滚
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240930_000001_init::{add_column, tiny, TDepartment, TRole};
+
+/// 角色、部门改为逻辑删除，补充删除标记字段（员工、菜单、职务已有该字段）
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        add_column(manager, TRole::Table, tiny(TRole::DeletedFlag, 0, "是否删除 0否 1是")).await?;
+        add_column(manager, TDepartment::Table, tiny(TDepartment::DeletedFlag, 0, "是否删除 0否 1是")).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(Table::alter().table(TRole::Table).drop_column(TRole::DeletedFlag).to_owned())
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(TDepartment::Table)
+                    .drop_column(TDepartment::DeletedFlag)
+                    .to_owned(),
+            )
+            .await
+    }
+}
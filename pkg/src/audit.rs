@@ -0,0 +1,42 @@
+use tokio::sync::mpsc;
+
+/// 操作日志记录，由审计中间件生成，经队列交由后台任务写入数据库
+pub struct Record {
+    pub employee_id: i64,
+    pub module: String,
+    pub content: String,
+    pub url: String,
+    pub method: String,
+    pub param: Option<String>,
+    pub ip: String,
+    pub user_agent: Option<String>,
+    pub success: bool,
+    pub fail_reason: Option<String>,
+    pub create_time: i64,
+}
+
+/// 操作日志待写入队列，未启动时丢弃记录
+///
+/// 由应用启动时创建并存入 `AppState`，写入任务持有接收端
+#[derive(Clone, Default)]
+pub struct Queue(Option<mpsc::Sender<Record>>);
+
+impl Queue {
+    /// 创建指定长度的队列，返回队列及其接收端
+    pub fn new(size: usize) -> (Self, mpsc::Receiver<Record>) {
+        let (tx, rx) = mpsc::channel(size);
+        (Self(Some(tx)), rx)
+    }
+
+    /// 放入队列，不等待；队列已满或未启动时丢弃并记录日志
+    pub fn send(&self, record: Record) {
+        match &self.0 {
+            Some(tx) => {
+                if let Err(e) = tx.try_send(record) {
+                    tracing::error!(error = %e, "error send operate log");
+                }
+            }
+            None => tracing::warn!(url = record.url, "operate log queue not started"),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use config::Config;
+
+use crate::state;
+
+static STORE: OnceLock<Box<dyn Store>> = OnceLock::new();
+
+/// 缓存存储，用于计数器、临时状态等带过期时间的数据
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// 获取值
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+
+    /// 设置值及过期时间
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()>;
+
+    /// 删除
+    async fn del(&self, key: &str) -> Result<()>;
+
+    /// 计数加一并返回结果；键不存在时创建并设置过期时间
+    async fn incr(&self, key: &str, ttl: Duration) -> Result<i64>;
+
+    /// 剩余过期时间，键不存在时返回 None
+    async fn ttl(&self, key: &str) -> Result<Option<Duration>>;
+}
+
+/// 不受进程内存储数量上限限制的键前缀，用于登录锁定等不能丢失的状态；
+/// 此类键须由数量受限的键（如失败计数）派生，数量由后者间接限制
+pub const PINNED_PREFIX: &str = "pinned:";
+
+// 进程内存储默认的键数量上限
+const MEMORY_MAX_ENTRIES: usize = 100_000;
+// 进程内存储清理过期键的间隔
+const MEMORY_PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+type Entries = HashMap<String, (String, Instant)>;
+
+/// 进程内存储（默认），多实例部署时各实例独立
+///
+/// 过期的键在写入时按间隔批量清理；键数量达到上限时拒绝写入新的键（返回错误），不淘汰已有的键。
+/// 以 [`PINNED_PREFIX`] 开头的键单独存放，不受数量上限限制
+pub struct MemoryStore {
+    data: Mutex<MemoryData>,
+    max_entries: usize,
+}
+
+struct MemoryData {
+    entries: Entries,
+    pinned: Entries,
+    next_purge: Instant,
+}
+
+impl MemoryData {
+    fn map(&self, key: &str) -> &Entries {
+        if key.starts_with(PINNED_PREFIX) { &self.pinned } else { &self.entries }
+    }
+
+    fn map_mut(&mut self, key: &str) -> &mut Entries {
+        if key.starts_with(PINNED_PREFIX) { &mut self.pinned } else { &mut self.entries }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new(MEMORY_MAX_ENTRIES)
+    }
+}
+
+impl MemoryStore {
+    /// 创建进程内存储，`max_entries` 为键数量上限
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            data: Mutex::new(MemoryData {
+                entries: HashMap::new(),
+                pinned: HashMap::new(),
+                next_purge: Instant::now() + MEMORY_PURGE_INTERVAL,
+            }),
+            max_entries: max_entries.max(1),
+        }
+    }
+
+    // 写入键：到达清理时间时清理过期的键；数量达到上限时拒绝写入新的键
+    fn insert(&self, data: &mut MemoryData, key: &str, value: String, expire: Instant, now: Instant) -> Result<()> {
+        if data.next_purge <= now {
+            data.entries.retain(|_, (_, expire)| *expire > now);
+            data.pinned.retain(|_, (_, expire)| *expire > now);
+            data.next_purge = now + MEMORY_PURGE_INTERVAL;
+        }
+        let full = !key.starts_with(PINNED_PREFIX)
+            && data.entries.len() >= self.max_entries
+            && !data.entries.contains_key(key);
+        if full {
+            tracing::error!(max_entries = self.max_entries, "memory store is full");
+            return Err(anyhow!("缓存存储已满"));
+        }
+        data.map_mut(key).insert(key.to_string(), (value, expire));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let now = Instant::now();
+        let data = self.data.lock().unwrap();
+        Ok(data
+            .map(key)
+            .get(key)
+            .filter(|(_, expire)| *expire > now)
+            .map(|(v, _)| v.clone()))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()> {
+        let now = Instant::now();
+        let mut data = self.data.lock().unwrap();
+        self.insert(&mut data, key, value.to_string(), now + ttl, now)
+    }
+
+    async fn del(&self, key: &str) -> Result<()> {
+        self.data.lock().unwrap().map_mut(key).remove(key);
+        Ok(())
+    }
+
+    async fn incr(&self, key: &str, ttl: Duration) -> Result<i64> {
+        let now = Instant::now();
+        let mut data = self.data.lock().unwrap();
+        let count = match data.map_mut(key).get_mut(key) {
+            Some((v, expire)) if *expire > now => {
+                let count = v.parse::<i64>().unwrap_or_default() + 1;
+                *v = count.to_string();
+                count
+            }
+            _ => {
+                self.insert(&mut data, key, String::from("1"), now + ttl, now)?;
+                1
+            }
+        };
+        Ok(count)
+    }
+
+    async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
+        let now = Instant::now();
+        let data = self.data.lock().unwrap();
+        Ok(data
+            .map(key)
+            .get(key)
+            .filter(|(_, expire)| *expire > now)
+            .map(|(_, expire)| *expire - now))
+    }
+}
+
+/// Redis 存储，多实例部署时共享
+#[cfg(feature = "redis")]
+pub struct RedisStore {
+    conn: redis::aio::ConnectionManager,
+}
+
+#[cfg(feature = "redis")]
+impl RedisStore {
+    pub async fn new(dsn: &str) -> Result<Self> {
+        let client = redis::Client::open(dsn)?;
+        let conn = redis::aio::ConnectionManager::new(client).await?;
+        Ok(Self { conn })
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl Store for RedisStore {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut conn = self.conn.clone();
+        let v: Option<String> = redis::cmd("GET").arg(key).query_async(&mut conn).await?;
+        Ok(v)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let _: () = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn del(&self, key: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let _: () = redis::cmd("DEL").arg(key).query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    async fn incr(&self, key: &str, ttl: Duration) -> Result<i64> {
+        let mut conn = self.conn.clone();
+        let count: i64 = redis::cmd("INCR").arg(key).query_async(&mut conn).await?;
+        if count == 1 {
+            let _: () = redis::cmd("PEXPIRE")
+                .arg(key)
+                .arg(ttl.as_millis().max(1) as u64)
+                .query_async(&mut conn)
+                .await?;
+        }
+        Ok(count)
+    }
+
+    async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
+        let mut conn = self.conn.clone();
+        let ms: i64 = redis::cmd("PTTL").arg(key).query_async(&mut conn).await?;
+        // -2 键不存在；-1 未设置过期时间
+        Ok(match ms {
+            -2 => None,
+            -1 => Some(Duration::MAX),
+            v => Some(Duration::from_millis(v as u64)),
+        })
+    }
+}
+
+/// 按配置创建缓存存储：`cache.driver` 为 `memory`（默认）或 `redis`；
+/// 进程内存储的键数量上限由 `cache.memory.max_entries` 设置
+pub async fn from_config(cfg: &Config) -> Box<dyn Store> {
+    let driver = cfg
+        .get_string("cache.driver")
+        .unwrap_or(String::from("memory"));
+
+    let store: Box<dyn Store> = match driver.as_str() {
+        "memory" => Box::new(MemoryStore::new(
+            cfg.get_int("cache.memory.max_entries")
+                .map(|v| v.max(1) as usize)
+                .unwrap_or(MEMORY_MAX_ENTRIES),
+        )),
+        #[cfg(feature = "redis")]
+        "redis" => Box::new(
+            RedisStore::new(&cfg.get_string("cache.redis.dsn").expect("缺少Redis DSN配置"))
+                .await
+                .unwrap_or_else(|e| panic!("Redis连接失败：{}", e)),
+        ),
+        v => panic!("不支持的缓存存储：{}", v),
+    };
+
+    store
+}
+
+/// 全局应用状态中的缓存存储，未初始化时使用进程内存储；新代码应使用 `AppState::cache`
+pub fn store() -> &'static dyn Store {
+    match state::get() {
+        Some(v) => v.cache.as_ref(),
+        None => STORE.get_or_init(|| Box::new(MemoryStore::default())).as_ref(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{MemoryStore, Store, PINNED_PREFIX};
+
+    #[tokio::test]
+    async fn memory_store() {
+        let store = MemoryStore::default();
+
+        assert_eq!(store.incr("a", Duration::from_secs(60)).await.unwrap(), 1);
+        assert_eq!(store.incr("a", Duration::from_secs(60)).await.unwrap(), 2);
+        assert!(store.ttl("a").await.unwrap().is_some());
+        store.del("a").await.unwrap();
+        assert_eq!(store.ttl("a").await.unwrap(), None);
+
+        store.set("b", "1", Duration::from_millis(10)).await.unwrap();
+        assert_eq!(store.get("b").await.unwrap(), Some(String::from("1")));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(store.get("b").await.unwrap(), None);
+        assert_eq!(store.incr("b", Duration::from_secs(60)).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn memory_store_max_entries() {
+        let store = MemoryStore::new(2);
+
+        store.set("a", "1", Duration::from_secs(60)).await.unwrap();
+        store.set("b", "1", Duration::from_secs(60)).await.unwrap();
+        // 已有的键可以更新，新的键拒绝写入，不淘汰已有的键
+        store.set("b", "2", Duration::from_secs(60)).await.unwrap();
+        assert!(store.set("c", "1", Duration::from_secs(60)).await.is_err());
+        assert!(store.incr("c", Duration::from_secs(60)).await.is_err());
+        assert_eq!(store.get("a").await.unwrap(), Some(String::from("1")));
+        assert_eq!(store.get("b").await.unwrap(), Some(String::from("2")));
+
+        // 固定前缀的键不受上限限制
+        let key = format!("{}lock", PINNED_PREFIX);
+        store.set(&key, "1", Duration::from_secs(60)).await.unwrap();
+        assert!(store.ttl(&key).await.unwrap().is_some());
+        store.del(&key).await.unwrap();
+        assert_eq!(store.get(&key).await.unwrap(), None);
+
+        // 删除后可写入新的键
+        store.del("a").await.unwrap();
+        assert_eq!(store.incr("c", Duration::from_secs(60)).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::RngExt;
+
+// 去除易混淆字符（0/O、1/I/L、G/6 等）
+const CHARSET: &[u8] = b"23456789ABCDEFHJKMNPRSTUVWXY";
+
+const WIDTH: u32 = 120;
+const HEIGHT: u32 = 40;
+// 字形像素大小
+const PIXEL: f64 = 3.6;
+
+/// 图形验证码
+pub struct Captcha {
+    answer: String,
+    svg: String,
+}
+
+impl Captcha {
+    /// 生成指定长度的验证码
+    pub fn new(len: usize) -> Self {
+        let mut rng = rand::rng();
+        let answer: String = (0..len)
+            .map(|_| CHARSET[rng.random_range(0..CHARSET.len())] as char)
+            .collect();
+        let svg = render(&answer);
+        Self { answer, svg }
+    }
+
+    /// 验证码答案
+    pub fn answer(&self) -> &str {
+        &self.answer
+    }
+
+    /// SVG 图片内容
+    pub fn svg(&self) -> &str {
+        &self.svg
+    }
+
+    /// 可直接用于 `<img src>` 的 data URI
+    pub fn data_uri(&self) -> String {
+        format!("data:image/svg+xml;base64,{}", STANDARD.encode(&self.svg))
+    }
+}
+
+/// 校验答案，不区分大小写
+pub fn matches(answer: &str, input: &str) -> bool {
+    !answer.is_empty() && answer.eq_ignore_ascii_case(input.trim())
+}
+
+// 以点阵字形绘制字符（不使用 <text>，避免直接从 SVG 源码读出答案），并加入干扰线和噪点
+fn render(text: &str) -> String {
+    let mut rng = rand::rng();
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="100%" height="100%" fill="rgb({r},{g},{b})"/>"#,
+        w = WIDTH,
+        h = HEIGHT,
+        r = rng.random_range(230..=255),
+        g = rng.random_range(230..=255),
+        b = rng.random_range(230..=255),
+    );
+
+    let cell = (WIDTH as f64 - 12.0) / text.len().max(1) as f64;
+    for (i, c) in text.chars().enumerate() {
+        let Some(rows) = glyph(c) else { continue };
+        let x = 6.0 + cell * i as f64 + (cell - 5.0 * PIXEL) / 2.0 + rng.random_range(-2.0..2.0);
+        let y = (HEIGHT as f64 - 7.0 * PIXEL) / 2.0 + rng.random_range(-3.0..3.0);
+        let angle: f64 = rng.random_range(-18.0..18.0);
+
+        let mut path = String::new();
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) == 0 {
+                    continue;
+                }
+                let px = col as f64 * PIXEL + rng.random_range(-0.5..0.5);
+                let py = row as f64 * PIXEL + rng.random_range(-0.5..0.5);
+                let _ = write!(path, "M{:.1} {:.1}h{p:.1}v{p:.1}h-{p:.1}z", px, py, p = PIXEL);
+            }
+        }
+        let _ = write!(
+            svg,
+            r#"<path transform="translate({:.1} {:.1}) rotate({:.1} {:.1} {:.1})" fill="{}" d="{}"/>"#,
+            x,
+            y,
+            angle,
+            2.5 * PIXEL,
+            3.5 * PIXEL,
+            color(&mut rng),
+            path
+        );
+    }
+
+    // 干扰线
+    for _ in 0..4 {
+        let _ = write!(
+            svg,
+            r#"<path d="M{} {} Q{} {} {} {}" stroke="{}" stroke-width="1.2" fill="none"/>"#,
+            rng.random_range(0..WIDTH / 4),
+            rng.random_range(0..HEIGHT),
+            rng.random_range(0..WIDTH),
+            rng.random_range(0..HEIGHT),
+            rng.random_range(WIDTH * 3 / 4..WIDTH),
+            rng.random_range(0..HEIGHT),
+            color(&mut rng)
+        );
+    }
+    // 噪点
+    for _ in 0..30 {
+        let _ = write!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="1" fill="{}"/>"#,
+            rng.random_range(0..WIDTH),
+            rng.random_range(0..HEIGHT),
+            color(&mut rng)
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn color<R: RngExt>(rng: &mut R) -> String {
+    format!(
+        "rgb({},{},{})",
+        rng.random_range(20..150),
+        rng.random_range(20..150),
+        rng.random_range(20..150)
+    )
+}
+
+// 5x7 点阵字形，每行低5位从左到右
+fn glyph(c: char) -> Option<[u8; 7]> {
+    let rows = match c {
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glyph, matches, Captcha, CHARSET};
+
+    #[test]
+    fn generate() {
+        assert!(CHARSET.iter().all(|c| glyph(*c as char).is_some()));
+
+        let captcha = Captcha::new(4);
+        assert_eq!(captcha.answer().len(), 4);
+        assert!(captcha.svg().starts_with("<svg") && captcha.svg().ends_with("</svg>"));
+        assert!(!captcha.svg().contains("<text"));
+        assert!(captcha.data_uri().starts_with("data:image/svg+xml;base64,"));
+
+        assert!(matches("AB3D", " ab3d "));
+        assert!(!matches("AB3D", "AB3E"));
+        assert!(!matches("", ""));
+    }
+}
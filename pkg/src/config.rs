@@ -1,20 +1,20 @@
 use config::Config;
-use std::{fs, sync::OnceLock};
+use std::fs;
 
-static CFG: OnceLock<Config> = OnceLock::new();
+use crate::state;
 
-pub fn init(cfg_file: &str) {
+/// 加载配置文件
+pub fn load(cfg_file: &str) -> Config {
     let path = fs::canonicalize(cfg_file)
         .unwrap_or_else(|e| panic!("配置文件加载失败：{} - {}", e, cfg_file));
 
-    let cfg = Config::builder()
+    Config::builder()
         .add_source(config::File::with_name(path.to_str().unwrap()))
         .build()
-        .unwrap_or_else(|e| panic!("配置文件加载失败：{}", e));
-
-    let _ = CFG.set(cfg);
+        .unwrap_or_else(|e| panic!("配置文件加载失败：{}", e))
 }
 
+/// 全局应用状态中的配置，新代码应使用 `AppState::config`
 pub fn global() -> &'static Config {
-    CFG.get().unwrap_or_else(|| panic!("配置文件未初始化"))
+    &state::global().config
 }
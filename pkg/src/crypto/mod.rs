@@ -1 +1,4 @@
-pub mod hash;
\ No newline at end of file
+pub mod hash;
+pub mod password;
+pub mod policy;
+pub mod totp;
\ No newline at end of file
@@ -0,0 +1,323 @@
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Result};
+use argon2::password_hash::{PasswordHash, PasswordHasher as _, PasswordVerifier as _, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use config::Config;
+use rand::RngExt;
+use subtle::ConstantTimeEq;
+
+use super::hash::md5;
+use crate::state;
+
+static HASHER: OnceLock<Box<dyn PasswordHasher>> = OnceLock::new();
+
+/// 密码哈希算法
+///
+/// 哈希结果统一以 PHC 字符串格式（`$<id>$...`）存储，便于识别算法并在参数变化时升级
+pub trait PasswordHasher: Send + Sync {
+    /// 算法标识，对应 PHC 字符串中的 `<id>`
+    fn id(&self) -> &'static str;
+
+    /// 计算密码哈希
+    fn hash(&self, password: &str) -> Result<String>;
+
+    /// 校验密码
+    fn verify(&self, password: &str, hashed: &str) -> Result<bool>;
+
+    /// 是否能识别该哈希值
+    fn matches(&self, hashed: &str) -> bool;
+
+    /// 哈希值是否需要以当前参数重新计算
+    fn needs_rehash(&self, hashed: &str) -> bool {
+        !self.matches(hashed)
+    }
+}
+
+/// Argon2id（默认）
+#[derive(Default)]
+pub struct Argon2id {
+    params: Params,
+}
+
+impl Argon2id {
+    pub fn new(m_cost: u32, t_cost: u32, p_cost: u32) -> Result<Self> {
+        let params = Params::new(m_cost, t_cost, p_cost, None).map_err(|e| anyhow!(e))?;
+        Ok(Self { params })
+    }
+
+    fn argon2(&self) -> Argon2<'_> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+impl PasswordHasher for Argon2id {
+    fn id(&self) -> &'static str {
+        "argon2id"
+    }
+
+    fn hash(&self, password: &str) -> Result<String> {
+        let mut salt = [0u8; 16];
+        rand::rng().fill(&mut salt);
+        let salt = SaltString::encode_b64(&salt).map_err(|e| anyhow!(e))?;
+        let v = self
+            .argon2()
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|e| anyhow!(e))?;
+        Ok(v.to_string())
+    }
+
+    fn verify(&self, password: &str, hashed: &str) -> Result<bool> {
+        let parsed = PasswordHash::new(hashed).map_err(|e| anyhow!(e))?;
+        match self.argon2().verify_password(password.as_bytes(), &parsed) {
+            Ok(_) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(anyhow!(e)),
+        }
+    }
+
+    fn matches(&self, hashed: &str) -> bool {
+        hashed.starts_with("$argon2id$")
+    }
+
+    fn needs_rehash(&self, hashed: &str) -> bool {
+        let parsed = match PasswordHash::new(hashed) {
+            Ok(v) => v,
+            Err(_) => return true,
+        };
+        if parsed.algorithm.as_str() != self.id() || parsed.version != Some(Version::V0x13.into()) {
+            return true;
+        }
+        match Params::try_from(&parsed) {
+            Ok(v) => {
+                v.m_cost() != self.params.m_cost()
+                    || v.t_cost() != self.params.t_cost()
+                    || v.p_cost() != self.params.p_cost()
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+/// Bcrypt
+pub struct Bcrypt {
+    cost: u32,
+}
+
+impl Bcrypt {
+    pub fn new(cost: u32) -> Self {
+        Self { cost }
+    }
+}
+
+impl Default for Bcrypt {
+    fn default() -> Self {
+        Self {
+            cost: bcrypt::DEFAULT_COST,
+        }
+    }
+}
+
+impl PasswordHasher for Bcrypt {
+    fn id(&self) -> &'static str {
+        "2b"
+    }
+
+    fn hash(&self, password: &str) -> Result<String> {
+        Ok(bcrypt::hash(password, self.cost)?)
+    }
+
+    fn verify(&self, password: &str, hashed: &str) -> Result<bool> {
+        Ok(bcrypt::verify(password, hashed)?)
+    }
+
+    fn matches(&self, hashed: &str) -> bool {
+        ["$2a$", "$2b$", "$2x$", "$2y$"]
+            .iter()
+            .any(|prefix| hashed.starts_with(prefix))
+    }
+
+    fn needs_rehash(&self, hashed: &str) -> bool {
+        if !hashed.starts_with("$2b$") {
+            return true;
+        }
+        // $2b$<cost>$<salt+hash>
+        match hashed.get(4..6).and_then(|v| v.parse::<u32>().ok()) {
+            Some(cost) => cost != self.cost,
+            None => true,
+        }
+    }
+}
+
+/// 历史遗留的无盐 MD5，仅用于校验旧数据
+pub struct LegacyMd5;
+
+impl PasswordHasher for LegacyMd5 {
+    fn id(&self) -> &'static str {
+        "md5"
+    }
+
+    fn hash(&self, password: &str) -> Result<String> {
+        Ok(md5(password.as_bytes()))
+    }
+
+    fn verify(&self, password: &str, hashed: &str) -> Result<bool> {
+        // 常量时间比较，避免通过响应时间逐字节猜测摘要
+        let hashed = hashed.to_ascii_lowercase();
+        Ok(md5(password.as_bytes()).as_bytes().ct_eq(hashed.as_bytes()).into())
+    }
+
+    fn matches(&self, hashed: &str) -> bool {
+        hashed.len() == 32 && hashed.bytes().all(|b| b.is_ascii_hexdigit())
+    }
+
+    fn needs_rehash(&self, _hashed: &str) -> bool {
+        true
+    }
+}
+
+/// 密码校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verified {
+    /// 密码错误
+    Mismatch,
+    /// 密码正确
+    Match,
+    /// 密码正确，但哈希值需要以当前算法重新计算
+    Rehash,
+}
+
+impl Verified {
+    pub fn is_match(&self) -> bool {
+        *self != Verified::Mismatch
+    }
+}
+
+/// 根据配置创建默认哈希算法
+///
+/// ```toml
+/// [password]
+/// algorithm = "argon2id" # argon2id | bcrypt
+/// argon2 = { m_cost = 19456, t_cost = 2, p_cost = 1 }
+/// bcrypt = { cost = 12 }
+/// ```
+pub fn from_config(cfg: &Config) -> Box<dyn PasswordHasher> {
+    let algorithm = cfg
+        .get_string("password.algorithm")
+        .unwrap_or(String::from("argon2id"));
+
+    let hasher: Box<dyn PasswordHasher> = match algorithm.as_str() {
+        "bcrypt" => Box::new(Bcrypt::new(
+            cfg.get_int("password.bcrypt.cost")
+                .unwrap_or(bcrypt::DEFAULT_COST as i64) as u32,
+        )),
+        "argon2id" => Box::new(
+            Argon2id::new(
+                cfg.get_int("password.argon2.m_cost")
+                    .unwrap_or(Params::DEFAULT_M_COST as i64) as u32,
+                cfg.get_int("password.argon2.t_cost")
+                    .unwrap_or(Params::DEFAULT_T_COST as i64) as u32,
+                cfg.get_int("password.argon2.p_cost")
+                    .unwrap_or(Params::DEFAULT_P_COST as i64) as u32,
+            )
+            .unwrap_or_else(|e| panic!("密码哈希参数错误：{}", e)),
+        ),
+        v => panic!("不支持的密码哈希算法：{}", v),
+    };
+
+    hasher
+}
+
+/// 全局应用状态中的哈希算法，未初始化时使用 Argon2id 默认参数；新代码应使用 `AppState::hasher`
+pub fn hasher() -> &'static dyn PasswordHasher {
+    match state::get() {
+        Some(v) => v.hasher.as_ref(),
+        None => HASHER.get_or_init(|| Box::new(Argon2id::default())).as_ref(),
+    }
+}
+
+/// 使用当前算法计算密码哈希
+pub fn hash(password: &str) -> Result<String> {
+    hasher().hash(password)
+}
+
+/// 校验密码，兼容 Argon2id、Bcrypt 及历史 MD5 哈希
+pub fn verify(password: &str, hashed: &str) -> Result<Verified> {
+    verify_with(hasher(), password, hashed)
+}
+
+pub fn verify_with(current: &dyn PasswordHasher, password: &str, hashed: &str) -> Result<Verified> {
+    let argon2 = Argon2id::default();
+    let bcrypt = Bcrypt::default();
+    let candidates: [&dyn PasswordHasher; 4] = [current, &argon2, &bcrypt, &LegacyMd5];
+
+    let matched = candidates
+        .into_iter()
+        .find(|h| h.matches(hashed))
+        .ok_or(anyhow!("无法识别的密码哈希格式"))?;
+
+    if !matched.verify(password, hashed)? {
+        return Ok(Verified::Mismatch);
+    }
+
+    if matched.id() != current.id() || current.needs_rehash(hashed) {
+        return Ok(Verified::Rehash);
+    }
+
+    Ok(Verified::Match)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::password::{verify_with, Argon2id, Bcrypt, PasswordHasher, Verified};
+
+    fn argon2() -> Argon2id {
+        Argon2id::new(1024, 1, 1).unwrap()
+    }
+
+    #[test]
+    fn argon2id() {
+        let h = argon2();
+        let hashed = h.hash("shenghui").unwrap();
+        assert!(hashed.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+        assert_ne!(hashed, h.hash("shenghui").unwrap());
+        assert!(h.verify("shenghui", &hashed).unwrap());
+        assert!(!h.verify("IIInsomnia", &hashed).unwrap());
+        assert!(!h.needs_rehash(&hashed));
+        assert!(Argon2id::new(2048, 1, 1).unwrap().needs_rehash(&hashed));
+    }
+
+    #[test]
+    fn bcrypt() {
+        let h = Bcrypt::new(4);
+        let hashed = h.hash("shenghui").unwrap();
+        assert!(hashed.starts_with("$2b$04$"));
+        assert!(h.verify("shenghui", &hashed).unwrap());
+        assert!(!h.verify("IIInsomnia", &hashed).unwrap());
+        assert!(!h.needs_rehash(&hashed));
+        assert!(Bcrypt::new(5).needs_rehash(&hashed));
+    }
+
+    #[test]
+    fn verify() {
+        let h = argon2();
+
+        // 当前算法
+        let hashed = h.hash("shenghui").unwrap();
+        assert_eq!(verify_with(&h, "shenghui", &hashed).unwrap(), Verified::Match);
+        assert_eq!(verify_with(&h, "IIInsomnia", &hashed).unwrap(), Verified::Mismatch);
+
+        // 历史 MD5
+        let legacy = "ff7f89cbe5c489ff2825d97c4e7b6f7c";
+        assert_eq!(verify_with(&h, "shenghui", legacy).unwrap(), Verified::Rehash);
+        assert_eq!(verify_with(&h, "IIInsomnia", legacy).unwrap(), Verified::Mismatch);
+        let upper = legacy.to_ascii_uppercase();
+        assert_eq!(verify_with(&h, "shenghui", &upper).unwrap(), Verified::Rehash);
+
+        // 其它算法
+        let hashed = Bcrypt::new(4).hash("shenghui").unwrap();
+        assert_eq!(verify_with(&h, "shenghui", &hashed).unwrap(), Verified::Rehash);
+
+        assert!(verify_with(&h, "shenghui", "").is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use std::sync::OnceLock;
+
+use config::Config;
+use rand::RngExt;
+
+use crate::state;
+
+static POLICY: OnceLock<Policy> = OnceLock::new();
+
+const LOWER: &[u8] = b"abcdefghijkmnpqrstuvwxyz";
+const UPPER: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+const DIGIT: &[u8] = b"23456789";
+const SPECIAL: &[u8] = b"!@#$%^&*-_=+?";
+
+/// 密码策略
+#[derive(Debug, Clone)]
+pub struct Policy {
+    /// 最小长度
+    pub min_length: usize,
+    /// 最大长度
+    pub max_length: usize,
+    /// 需包含大写字母
+    pub require_upper: bool,
+    /// 需包含小写字母
+    pub require_lower: bool,
+    /// 需包含数字
+    pub require_digit: bool,
+    /// 需包含特殊字符
+    pub require_special: bool,
+    /// 不可与最近 N 次使用过的密码相同，0 表示不限制
+    pub history: usize,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 64,
+            require_upper: true,
+            require_lower: true,
+            require_digit: true,
+            require_special: false,
+            history: 3,
+        }
+    }
+}
+
+impl Policy {
+    pub fn from_config(cfg: &Config) -> Self {
+        let default = Self::default();
+        let int = |key: &str, v: usize| {
+            cfg.get_int(&format!("password.policy.{}", key))
+                .map(|v| v.max(0) as usize)
+                .unwrap_or(v)
+        };
+        let bool = |key: &str, v: bool| cfg.get_bool(&format!("password.policy.{}", key)).unwrap_or(v);
+        Self {
+            min_length: int("min_length", default.min_length),
+            max_length: int("max_length", default.max_length),
+            require_upper: bool("require_upper", default.require_upper),
+            require_lower: bool("require_lower", default.require_lower),
+            require_digit: bool("require_digit", default.require_digit),
+            require_special: bool("require_special", default.require_special),
+            history: int("history", default.history),
+        }
+    }
+
+    /// 校验密码是否符合策略，不符合时返回原因
+    pub fn validate(&self, password: &str, login_name: &str) -> Result<(), String> {
+        let len = password.chars().count();
+        if len < self.min_length {
+            return Err(format!("密码长度不能少于{}位", self.min_length));
+        }
+        if len > self.max_length {
+            return Err(format!("密码长度不能超过{}位", self.max_length));
+        }
+        if self.require_upper && !password.chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(String::from("密码需包含大写字母"));
+        }
+        if self.require_lower && !password.chars().any(|c| c.is_ascii_lowercase()) {
+            return Err(String::from("密码需包含小写字母"));
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err(String::from("密码需包含数字"));
+        }
+        if self.require_special && !password.chars().any(|c| !c.is_ascii_alphanumeric()) {
+            return Err(String::from("密码需包含特殊字符"));
+        }
+        if !login_name.is_empty() && password.eq_ignore_ascii_case(login_name) {
+            return Err(String::from("密码不能与登录名相同"));
+        }
+        Ok(())
+    }
+
+    /// 生成符合策略的随机密码
+    pub fn generate(&self) -> String {
+        let mut rng = rand::rng();
+        let len = self.min_length.max(12).min(self.max_length.max(1));
+
+        // 每类必需字符至少一个
+        let mut chars: Vec<u8> = Vec::with_capacity(len);
+        let mut pool: Vec<u8> = Vec::new();
+        for (required, set) in [
+            (self.require_lower, LOWER),
+            (self.require_upper, UPPER),
+            (self.require_digit, DIGIT),
+            (self.require_special, SPECIAL),
+        ] {
+            if required {
+                chars.push(set[rng.random_range(0..set.len())]);
+            }
+            pool.extend_from_slice(set);
+        }
+        while chars.len() < len {
+            chars.push(pool[rng.random_range(0..pool.len())]);
+        }
+
+        // 打乱顺序
+        for i in (1..chars.len()).rev() {
+            let j = rng.random_range(0..=i);
+            chars.swap(i, j);
+        }
+        String::from_utf8(chars).unwrap_or_default()
+    }
+}
+
+/// 全局应用状态中的密码策略，未初始化时使用默认策略；新代码应使用 `AppState::policy`
+pub fn policy() -> &'static Policy {
+    match state::get() {
+        Some(v) => v.policy.as_ref(),
+        None => POLICY.get_or_init(Policy::default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Policy;
+
+    #[test]
+    fn validate() {
+        let policy = Policy::default();
+        assert!(policy.validate("Abcdefg1", "admin").is_ok());
+        assert!(policy.validate("Abc1", "admin").is_err());
+        assert!(policy.validate("abcdefg1", "admin").is_err());
+        assert!(policy.validate("ABCDEFG1", "admin").is_err());
+        assert!(policy.validate("Abcdefgh", "admin").is_err());
+        assert!(policy.validate("Admin123", "admin123").is_err());
+
+        let policy = Policy {
+            require_special: true,
+            ..Policy::default()
+        };
+        assert!(policy.validate("Abcdefg1", "admin").is_err());
+        assert!(policy.validate("Abcdef#1", "admin").is_ok());
+    }
+
+    #[test]
+    fn generate() {
+        let policy = Policy {
+            require_special: true,
+            ..Policy::default()
+        };
+        for _ in 0..20 {
+            let v = policy.generate();
+            assert_eq!(v.len(), 12);
+            assert!(policy.validate(&v, "admin").is_ok());
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! 数据导出：CSV、XLSX
+//!
+//! 查询结果在后台任务中分批读取，数据行经队列交给阻塞线程编码，编码结果按块写入响应体，
+//! 全程只缓存少量数据行；XLSX 使用常量内存模式，数据行暂存于临时文件。
+
+use std::io::{self, Write};
+
+use axum::body::{Body, Bytes};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use config::Config;
+use rust_xlsxwriter::Workbook;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::query::Batches;
+use crate::result::response::ApiErr;
+
+/// 默认最大导出行数
+pub const DEFAULT_MAX_ROWS: u64 = 10000;
+/// 每批查询的行数
+pub const BATCH_SIZE: u64 = 500;
+// 待编码的数据行队列长度
+const ROW_QUEUE: usize = 1000;
+// 响应体分块大小
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Xlsx,
+}
+
+impl Format {
+    /// 解析查询参数 `format`，默认 CSV
+    pub fn parse(v: Option<&str>) -> Result<Self, ApiErr> {
+        match v.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("csv") => Ok(Format::Csv),
+            Some("xlsx") => Ok(Format::Xlsx),
+            Some(_) => Err(ApiErr::ErrParams(Some("导出格式应为 csv 或 xlsx".to_string()))),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Xlsx => "xlsx",
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            Format::Csv => "text/csv; charset=utf-8",
+            Format::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+/// 最大导出行数，配置项 `export.max_rows`
+pub fn max_rows(cfg: &Config) -> u64 {
+    cfg.get_int("export.max_rows")
+        .ok()
+        .filter(|v| *v > 0)
+        .map(|v| v as u64)
+        .unwrap_or(DEFAULT_MAX_ROWS)
+}
+
+/// 校验导出行数，超出上限时返回 `ApiErr::ErrParams`
+pub fn check_rows(total: u64, cfg: &Config) -> Result<(), ApiErr> {
+    let max = max_rows(cfg);
+    if total > max {
+        return Err(ApiErr::ErrParams(Some(format!(
+            "导出数据共 {} 条，超过上限 {} 条，请缩小查询范围",
+            total, max
+        ))));
+    }
+    Ok(())
+}
+
+/// 导出查询结果：在后台任务中分批查询，经 `row` 转换为数据行后写入文件，返回流式响应
+///
+/// `name` 为下载文件名（不含扩展名，仅限 ASCII），`headers` 为表头；
+/// 查询出错或客户端断开时中止，已发送的响应体以错误结束
+pub fn response<E, F>(
+    db: DatabaseConnection,
+    mut batches: Batches<E>,
+    format: Format,
+    name: &str,
+    headers: &'static [&'static str],
+    mut row: F,
+) -> Response
+where
+    E: EntityTrait,
+    F: FnMut(E::Model) -> Vec<String> + Send + 'static,
+{
+    let (row_tx, row_rx) = mpsc::channel::<Message>(ROW_QUEUE);
+    let (body_tx, body_rx) = mpsc::channel::<io::Result<Bytes>>(4);
+
+    tokio::spawn(async move {
+        loop {
+            match batches.next(&db).await {
+                Ok(Some(models)) => {
+                    for model in models {
+                        if row_tx.send(Message::Row(row(model))).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::error!(error = ?e, "error export query");
+                    let _ = row_tx.send(Message::Abort).await;
+                    return;
+                }
+            }
+        }
+    });
+
+    tokio::task::spawn_blocking(move || {
+        let mut out = ChunkWriter {
+            tx: body_tx,
+            buf: Vec::with_capacity(CHUNK_SIZE),
+        };
+        let ret = match format {
+            Format::Csv => write_csv(&mut out, headers, row_rx),
+            Format::Xlsx => write_xlsx(&mut out, headers, row_rx),
+        };
+        if let Err(e) = ret.and_then(|_| out.flush()) {
+            if e.kind() != io::ErrorKind::BrokenPipe {
+                tracing::error!(error = ?e, "error export write");
+            }
+            let _ = out.tx.blocking_send(Err(e));
+        }
+    });
+
+    let disposition = format!("attachment; filename=\"{}.{}\"", name, format.extension());
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(ReceiverStream::new(body_rx)),
+    )
+        .into_response()
+}
+
+enum Message {
+    Row(Vec<String>),
+    Abort,
+}
+
+fn aborted() -> io::Error {
+    io::Error::other("导出查询失败")
+}
+
+fn write_csv(out: &mut ChunkWriter, headers: &[&str], mut rx: mpsc::Receiver<Message>) -> io::Result<()> {
+    // BOM：Excel 按 UTF-8 打开
+    out.write_all("\u{feff}".as_bytes())?;
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(headers)?;
+    while let Some(msg) = rx.blocking_recv() {
+        match msg {
+            Message::Row(row) => {
+                let row: Vec<_> = row.iter().map(|v| escape(v)).collect();
+                writer.write_record(row.iter().map(|v| v.as_bytes()))?
+            }
+            Message::Abort => return Err(aborted()),
+        }
+    }
+    writer.flush()
+}
+
+fn write_xlsx(out: &mut ChunkWriter, headers: &[&str], mut rx: mpsc::Receiver<Message>) -> io::Result<()> {
+    let mut workbook = Workbook::new();
+    {
+        let sheet = workbook.add_worksheet_with_constant_memory();
+        let bold = rust_xlsxwriter::Format::new().set_bold();
+        sheet
+            .set_column_range_width(0, headers.len().saturating_sub(1) as u16, 18)
+            .map_err(io::Error::other)?;
+        for (col, v) in headers.iter().enumerate() {
+            sheet
+                .write_string_with_format(0, col as u16, *v, &bold)
+                .map_err(io::Error::other)?;
+        }
+        let mut row_num: u32 = 1;
+        while let Some(msg) = rx.blocking_recv() {
+            match msg {
+                Message::Row(row) => {
+                    for (col, v) in row.iter().enumerate() {
+                        sheet.write_string(row_num, col as u16, v).map_err(io::Error::other)?;
+                    }
+                    row_num += 1;
+                }
+                Message::Abort => return Err(aborted()),
+            }
+        }
+    }
+    workbook.save_to_writer(out).map_err(|e| match e {
+        rust_xlsxwriter::XlsxError::IoError(e) => e,
+        e => io::Error::other(e),
+    })
+}
+
+// 以公式字符开头的值在表格软件中会被当作公式执行，加单引号前缀转为文本
+fn escape(v: &str) -> std::borrow::Cow<'_, str> {
+    match v.chars().next() {
+        Some('=' | '+' | '-' | '@' | '\t' | '\r') => format!("'{}", v).into(),
+        _ => v.into(),
+    }
+}
+
+// 按块写入响应体，客户端断开时返回 `BrokenPipe`
+struct ChunkWriter {
+    tx: mpsc::Sender<io::Result<Bytes>>,
+    buf: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn send(&mut self) -> io::Result<()> {
+        let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(CHUNK_SIZE));
+        self.tx
+            .blocking_send(Ok(Bytes::from(chunk)))
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buf.is_empty() {
+            self.send()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, Format};
+
+    #[test]
+    fn format() {
+        assert!(matches!(Format::parse(None), Ok(Format::Csv)));
+        assert!(matches!(Format::parse(Some("XLSX")), Ok(Format::Xlsx)));
+        assert!(Format::parse(Some("pdf")).is_err());
+    }
+
+    #[test]
+    fn formula() {
+        assert_eq!(escape("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(escape("@cmd"), "'@cmd");
+        assert_eq!(escape("张三"), "张三");
+    }
+}
@@ -1,11 +1,17 @@
 use anyhow::{Ok, Result};
+use config::Config;
 use chrono::{Duration, Utc};
 use serde::{Serialize, Deserialize};
+use jsonwebtoken::errors::ErrorKind;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use std::fmt;
 use std::fmt::Display;
+use thiserror::Error;
 
-use crate::config;
+use crate::result::response::ApiErr;
+
+// 令牌前缀
+const BEARER: &str = "Bearer ";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Identity {
@@ -14,11 +20,41 @@ pub struct Identity {
 }
 
 
+/// 访问令牌解析错误
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TokenError {
+    /// 令牌已过期，可使用刷新令牌换取新令牌
+    #[error("令牌已过期")]
+    Expired,
+    /// 签名校验失败
+    #[error("令牌签名无效")]
+    BadSignature,
+    /// 令牌格式错误
+    #[error("令牌格式错误")]
+    Malformed,
+    /// 服务端配置错误（如缺少密钥）
+    #[error("令牌配置错误: {0}")]
+    Config(String),
+}
+
+impl From<TokenError> for ApiErr {
+    fn from(e: TokenError) -> Self {
+        match e {
+            TokenError::Expired => ApiErr::ErrAuthExpired(None),
+            TokenError::BadSignature | TokenError::Malformed => ApiErr::ErrAuthInvalid(None),
+            TokenError::Config(_) => ApiErr::ErrSystem(None),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Cliams {
     pub exp: usize,
     pub iat: usize,
     pub id: i64,
+    // 登录会话标识
+    #[serde(default)]
+    pub token: String,
 }
 
 impl Identity {
@@ -36,29 +72,58 @@ impl Identity {
         }
     }
 
-    pub fn from_auth_token(token: String) -> Self {
+    /// 解析访问令牌，支持 `Bearer ` 前缀，密钥取自配置 `app.secret`
+    pub fn from_auth_token(token: &str, cfg: &Config) -> std::result::Result<Self, TokenError> {
+        let secret = cfg
+            .get_string("app.secret")
+            .map_err(|e| TokenError::Config(e.to_string()))?;
+        Self::decode_with(token, &secret)
+    }
+
+    fn decode_with(token: &str, secret: &str) -> std::result::Result<Self, TokenError> {
+        let token = token.trim();
+        let token = token.strip_prefix(BEARER).unwrap_or(token).trim();
+        if token.is_empty() {
+            return Err(TokenError::Malformed);
+        }
+
         // decode 解码  Validation
-        let secret = config::global().get_string("app.secret").unwrap();
-        let token_data: jsonwebtoken::TokenData<Cliams> = 
-            decode::<Cliams>(&token, &DecodingKey::from_secret(secret.as_ref()), &Validation::default()).unwrap();
-        let identity = Identity {
+        let token_data = decode::<Cliams>(
+            token,
+            &DecodingKey::from_secret(secret.as_ref()),
+            &Validation::default(),
+        )
+        .map_err(|e| match e.kind() {
+            ErrorKind::ExpiredSignature => TokenError::Expired,
+            ErrorKind::InvalidSignature | ErrorKind::ImmatureSignature => TokenError::BadSignature,
+            _ => TokenError::Malformed,
+        })?;
+        if token_data.claims.id == 0 || token_data.claims.token.is_empty() {
+            return Err(TokenError::Malformed);
+        }
+
+        std::result::Result::Ok(Identity {
             i: token_data.claims.id,
-            t: token,
-        };
-        identity
+            t: token_data.claims.token,
+        })
     }
 
-    pub fn to_auth_token(&self) -> Result<String> {
+    pub fn to_auth_token(&self, cfg: &Config) -> Result<String> {
         // encode 编码
         //let now = xtime::now(offset!(+8)).unix_timestamp();
         let now = Utc::now();
-        let expire = Duration::hours(24);
+        let expire = Duration::seconds(access_ttl(cfg));
         let exp = (now + expire).timestamp() as usize;
         let iat = now.timestamp() as usize;
 
-        let claim = Cliams { iat, exp, id: self.i };
+        let claim = Cliams {
+            iat,
+            exp,
+            id: self.i,
+            token: self.t.clone(),
+        };
 
-        let secret = config::global().get_string("app.secret").unwrap();
+        let secret = cfg.get_string("app.secret")?;
         let token = 
             encode(&Header::default(), 
                     &claim, 
@@ -71,12 +136,31 @@ impl Identity {
         self.i
     }
 
+    pub fn token(&self) -> &str {
+        &self.t
+    }
+
     pub fn match_token(&self, token: String) -> bool {
         self.t == token
     }
 }
 
 
+/// 访问令牌有效期（秒）
+pub fn access_ttl(cfg: &Config) -> i64 {
+    cfg.get_int("auth.access_ttl").unwrap_or(1800)
+}
+
+/// 刷新令牌有效期（秒）
+pub fn refresh_ttl(cfg: &Config) -> i64 {
+    cfg.get_int("auth.refresh_ttl").unwrap_or(7 * 86400)
+}
+
+/// 每个账号最多同时在线的会话数，0 表示不限制
+pub fn max_sessions(cfg: &Config) -> i64 {
+    cfg.get_int("auth.max_sessions").unwrap_or(5)
+}
+
 impl Display for Identity {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.i == 0 {
@@ -85,4 +169,46 @@ impl Display for Identity {
        
         write!(f, "id:{}|token:{}", self.i, self.t)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    use super::{Cliams, Identity, TokenError};
+
+    const SECRET: &str = "secret";
+
+    fn sign(exp: i64, secret: &str) -> String {
+        let claim = Cliams {
+            iat: Utc::now().timestamp() as usize,
+            exp: exp as usize,
+            id: 1,
+            token: String::from("abc"),
+        };
+        encode(&Header::default(), &claim, &EncodingKey::from_secret(secret.as_ref())).unwrap()
+    }
+
+    #[test]
+    fn decode_token() {
+        let token = sign(Utc::now().timestamp() + 600, SECRET);
+
+        let identity = Identity::decode_with(&token, SECRET).unwrap();
+        assert_eq!(identity.id(), 1);
+        assert_eq!(identity.token(), "abc");
+
+        let identity = Identity::decode_with(&format!("Bearer {}", token), SECRET).unwrap();
+        assert_eq!(identity.id(), 1);
+
+        assert_eq!(
+            Identity::decode_with(&token, "other").unwrap_err(),
+            TokenError::BadSignature
+        );
+        assert_eq!(
+            Identity::decode_with(&sign(Utc::now().timestamp() - 600, SECRET), SECRET).unwrap_err(),
+            TokenError::Expired
+        );
+        assert_eq!(Identity::decode_with("garbage", SECRET).unwrap_err(), TokenError::Malformed);
+        assert_eq!(Identity::decode_with("Bearer ", SECRET).unwrap_err(), TokenError::Malformed);
+    }
+}
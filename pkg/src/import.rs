@@ -0,0 +1,190 @@
+//! 数据导入：CSV、XLSX
+//!
+//! 首行为表头，数据按列名读取，列的顺序不限；空行忽略。导出的文件可直接导入。
+
+use std::io::Cursor;
+
+use calamine::{open_workbook_from_rs, Reader, Xlsx};
+use config::Config;
+
+use crate::result::response::ApiErr;
+
+/// 默认最大导入行数
+pub const DEFAULT_MAX_ROWS: u64 = 1000;
+
+/// 最大导入行数，配置项 `import.max_rows`
+pub fn max_rows(cfg: &Config) -> u64 {
+    cfg.get_int("import.max_rows")
+        .ok()
+        .filter(|v| *v > 0)
+        .map(|v| v as u64)
+        .unwrap_or(DEFAULT_MAX_ROWS)
+}
+
+/// 表格数据
+#[derive(Debug)]
+pub struct Sheet {
+    headers: Vec<String>,
+    rows: Vec<Row>,
+}
+
+/// 数据行
+#[derive(Debug)]
+pub struct Row {
+    /// 在表格中的行号（表头为第 1 行）
+    pub line: usize,
+    values: Vec<String>,
+}
+
+impl Sheet {
+    /// 读取表格：XLSX 按文件头识别（只读取第一个工作表），其它按 UTF-8 编码的 CSV 读取；
+    /// 数据行超出 `max_rows` 时返回 `ApiErr::ErrParams`
+    pub fn read(data: &[u8], max_rows: u64) -> Result<Self, ApiErr> {
+        let mut lines = if data.starts_with(b"PK") { read_xlsx(data)? } else { read_csv(data)? };
+        lines.retain(|(_, values)| values.iter().any(|v| !v.is_empty()));
+
+        let mut lines = lines.into_iter();
+        let headers = match lines.next() {
+            Some((_, v)) => v,
+            None => return Err(ApiErr::ErrParams(Some("文件内容为空".to_string()))),
+        };
+        let rows: Vec<Row> = lines.map(|(line, values)| Row { line, values }).collect();
+        if rows.len() as u64 > max_rows {
+            return Err(ApiErr::ErrParams(Some(format!(
+                "导入数据共 {} 条，超过上限 {} 条，请分批导入",
+                rows.len(),
+                max_rows
+            ))));
+        }
+        Ok(Self { headers, rows })
+    }
+
+    /// 按列名查找列
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.headers.iter().position(|v| v == name)
+    }
+
+    /// 按列名查找必填列，不存在时返回 `ApiErr::ErrParams`
+    pub fn require(&self, name: &str) -> Result<usize, ApiErr> {
+        self.column(name)
+            .ok_or_else(|| ApiErr::ErrParams(Some(format!("缺少列：{}", name))))
+    }
+
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+}
+
+impl Row {
+    /// 指定列的值，列不存在或值为空时返回空字符串
+    pub fn get(&self, column: Option<usize>) -> &str {
+        column
+            .and_then(|i| self.values.get(i))
+            .map(|v| v.as_str())
+            .unwrap_or_default()
+    }
+}
+
+fn format_err() -> ApiErr {
+    ApiErr::ErrParams(Some("文件格式错误，应为 UTF-8 编码的 CSV 或 XLSX 文件".to_string()))
+}
+
+fn read_csv(data: &[u8]) -> Result<Vec<(usize, Vec<String>)>, ApiErr> {
+    let data = data.strip_prefix("\u{feff}".as_bytes()).unwrap_or(data);
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(data);
+
+    let mut lines = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|_| format_err())?;
+        // 记录的起始位置包含其前面跳过的空行
+        let line = match record.position() {
+            Some(pos) => {
+                let blank = data[pos.byte() as usize..]
+                    .iter()
+                    .take_while(|b| matches!(b, b'\r' | b'\n'))
+                    .filter(|b| **b == b'\n')
+                    .count();
+                pos.line() as usize + blank
+            }
+            None => 0,
+        };
+        lines.push((line, record.iter().map(unescape).collect()));
+    }
+    Ok(lines)
+}
+
+fn read_xlsx(data: &[u8]) -> Result<Vec<(usize, Vec<String>)>, ApiErr> {
+    let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(data)).map_err(|_| format_err())?;
+    let range = match workbook.worksheet_range_at(0) {
+        Some(v) => v.map_err(|_| format_err())?,
+        None => return Ok(Vec::new()),
+    };
+    let start = range.start().map(|(row, _)| row as usize).unwrap_or_default();
+    let col = range.start().map(|(_, col)| col as usize).unwrap_or_default();
+
+    let lines = range
+        .rows()
+        .enumerate()
+        .map(|(i, cells)| {
+            // 区域不从第一列开始时补齐前面的空列
+            let values = std::iter::repeat_n(String::new(), col)
+                .chain(cells.iter().map(|v| unescape(&v.to_string())))
+                .collect();
+            (start + i + 1, values)
+        })
+        .collect();
+    Ok(lines)
+}
+
+// 去除首尾空白，以及导出时为防止公式执行添加的单引号前缀
+fn unescape(v: &str) -> String {
+    let v = v.trim();
+    match v.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(['=', '+', '-', '@']) => rest.to_string(),
+        _ => v.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sheet;
+
+    #[test]
+    fn csv() {
+        let data = "\u{feff}姓名,登录名\n张三, zhangsan \n\n\n李四,'=lisi\n";
+        let Ok(sheet) = Sheet::read(data.as_bytes(), 10) else {
+            panic!("读取失败");
+        };
+        assert_eq!(sheet.column("登录名"), Some(1));
+        assert!(sheet.require("手机号码").is_err());
+        let rows = sheet.rows();
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].line, rows[0].get(Some(1))), (2, "zhangsan"));
+        assert_eq!((rows[1].line, rows[1].get(Some(1))), (5, "=lisi"));
+        assert_eq!(rows[1].get(None), "");
+
+        assert!(Sheet::read(data.as_bytes(), 1).is_err());
+        assert!(Sheet::read(b"", 10).is_err());
+    }
+
+    #[test]
+    fn xlsx() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_string(1, 1, "手机号码").unwrap();
+        sheet.write_number(2, 1, 13900000000.0).unwrap();
+        let data = workbook.save_to_buffer().unwrap();
+
+        let Ok(sheet) = Sheet::read(&data, 10) else {
+            panic!("读取失败");
+        };
+        assert_eq!(sheet.column("手机号码"), Some(1));
+        let rows = sheet.rows();
+        assert_eq!((rows[0].line, rows[0].get(Some(1))), (3, "13900000000"));
+
+        assert!(Sheet::read(b"PK\x03\x04", 10).is_err());
+    }
+}
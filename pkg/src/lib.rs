@@ -1,10 +1,19 @@
+pub mod audit;
+pub mod cache;
+pub mod captcha;
 pub mod config;
 pub mod db;
+pub mod export;
+pub mod import;
 pub mod logger;
 pub mod identity;
 pub mod result;
+pub mod state;
 pub mod crypto;
 pub mod util;
 pub mod xtime;
 pub mod middleware;
+pub mod perm;
+pub mod query;
+pub mod soft_delete;
 pub mod tree;
\ No newline at end of file
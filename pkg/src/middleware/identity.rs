@@ -1,20 +1,39 @@
-use axum::{extract::Request, middleware::Next, response::Response};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
 use http::header::AUTHORIZATION;
 
-use crate::identity::Identity;
+use crate::identity::{Identity, TokenError};
+use crate::state::AppState;
 
-pub async fn handle(mut request: Request, next: Next) -> Response {
+/// 解析请求身份；令牌无效时以匿名身份继续，并记录解析错误，由需授权的路由决定如何响应
+pub async fn handle(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
     let token = request.headers().get(AUTHORIZATION);
     let identity = match token {
-        None => Identity::empty(),
+        None => Ok(Identity::empty()),
         Some(v) => match v.to_str() {
-            Ok(v) => Identity::from_auth_token(v.to_string()),
+            Ok(v) => Identity::from_auth_token(v, &state.config),
             Err(e) => {
                 tracing::error!(error = ?e, "error get header(authorization)");
-                Identity::empty()
+                Err(TokenError::Malformed)
             }
         },
     };
-    request.extensions_mut().insert(identity);
+    match identity {
+        Ok(v) => {
+            request.extensions_mut().insert(v);
+        }
+        Err(e) => {
+            if let TokenError::Config(_) = e {
+                tracing::error!(error = %e, "error parse auth token");
+            } else {
+                tracing::debug!(error = %e, "invalid auth token");
+            }
+            request.extensions_mut().insert(Identity::empty());
+            request.extensions_mut().insert(e);
+        }
+    }
     next.run(request).await
 }
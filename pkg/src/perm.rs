@@ -0,0 +1,94 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// 员工的后端权限集合（来源于 `t_menu.api_perms`）
+///
+/// 权限字符串以逗号分隔，支持两种写法：
+/// - 路由：`GET /v1/roles`、`* /v1/roles/{role_id}`、`/v1/roles/*`（省略方法表示任意方法）
+/// - 权限标识：`system:role:add`，对应路由表中声明的权限标识
+#[derive(Debug, Default)]
+pub struct PermSet {
+    perms: HashSet<String>,
+}
+
+impl PermSet {
+    pub fn new<I, S>(api_perms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut perms = HashSet::new();
+        for v in api_perms {
+            for perm in v.as_ref().split([',', '\n']) {
+                let perm = perm.trim();
+                if !perm.is_empty() {
+                    perms.insert(perm.to_string());
+                }
+            }
+        }
+        Self { perms }
+    }
+
+    /// 是否包含指定权限标识
+    pub fn contains(&self, key: &str) -> bool {
+        self.perms.contains(key)
+    }
+
+    /// 是否有路由写法的权限匹配指定路由
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        self.perms.iter().any(|perm| match_route(perm, method, path))
+    }
+}
+
+fn match_route(perm: &str, method: &str, path: &str) -> bool {
+    let (perm_method, perm_path) = match perm.split_once(' ') {
+        Some((m, p)) => (m.trim(), p.trim()),
+        None => ("*", perm),
+    };
+    if !perm_path.starts_with('/') {
+        return false;
+    }
+    if perm_method != "*" && !perm_method.eq_ignore_ascii_case(method) {
+        return false;
+    }
+    match perm_path.strip_suffix("/*") {
+        Some(prefix) => path.starts_with(&format!("{}/", prefix)),
+        None => perm_path == path,
+    }
+}
+
+/// 按员工缓存的权限集合，随 `AppState` 共享；多实例部署时各实例独立
+#[derive(Debug, Default)]
+pub struct Cache {
+    data: RwLock<HashMap<i64, Arc<PermSet>>>,
+}
+
+impl Cache {
+    /// 获取员工的权限集合
+    pub fn get(&self, employee_id: i64) -> Option<Arc<PermSet>> {
+        self.data.read().ok().and_then(|m| m.get(&employee_id).cloned())
+    }
+
+    /// 缓存员工的权限集合
+    pub fn insert(&self, employee_id: i64, perms: Arc<PermSet>) {
+        if let Ok(mut m) = self.data.write() {
+            m.insert(employee_id, perms);
+        }
+    }
+
+    /// 清除指定员工的权限集合
+    pub fn remove(&self, employee_ids: &[i64]) {
+        if let Ok(mut m) = self.data.write() {
+            for id in employee_ids {
+                m.remove(id);
+            }
+        }
+    }
+
+    /// 清除全部权限集合
+    pub fn clear(&self) {
+        if let Ok(mut m) = self.data.write() {
+            m.clear();
+        }
+    }
+}
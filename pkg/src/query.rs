@@ -0,0 +1,539 @@
+//! 列表查询：字段过滤、排序及分页
+//!
+//! 查询参数中 `page`、`size` 为分页，`sort` 为排序（逗号分隔的字段，`-` 前缀表示降序，
+//! 如 `sort=-create_time,sort`），`count` 指定是否统计总数（`0`/`false` 不统计），`cursor` 为游标，
+//! 其余均为字段过滤条件。各列表接口通过 `Spec` 声明可过滤的字段、过滤方式及可排序的字段：
+//!
+//! - `Eq`：等于，`disabled_flag=0`
+//! - `In`：属于，逗号分隔，`department_id=1,2,3`
+//! - `Like`：包含，`login_name=adm`
+//! - `Range`：范围，逗号分隔的起止值，可省略其中一端，`create_time=2024-01-01 00:00:00,`
+//!
+//! 值为空的过滤条件忽略；未声明的字段、排序字段，以及格式错误的值、分页参数返回 `ApiErr::ErrParams`
+//!
+//! 声明了游标列的接口（如只追加的日志表）支持游标分页：请求中带 `cursor` 参数（首页为空值）时，
+//! 按游标列倒序、以上一页返回的 `next_cursor` 为起点查询，不支持 `page`、`sort`，默认不统计总数
+
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sea_orm::sea_query::ValueType;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, ModelTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, Value,
+};
+use serde::Deserialize;
+use time::macros::offset;
+use utoipa::IntoParams;
+
+use crate::result::page::Page;
+use crate::result::response::ApiErr;
+use crate::xtime;
+
+/// 默认每页数量
+pub const DEFAULT_SIZE: u64 = 20;
+/// 最大每页数量
+pub const MAX_SIZE: u64 = 100;
+
+/// 过滤方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    In,
+    Like,
+    Range,
+}
+
+/// 过滤值类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// 整数
+    Int,
+    /// 字符串
+    Str,
+    /// 时间，格式 `yyyy-mm-dd hh:mm:ss`（东八区），按时间戳比较
+    Time,
+}
+
+/// 可过滤字段：查询参数名、对应的列、过滤方式及值类型
+pub struct Filter<C> {
+    pub name: &'static str,
+    pub column: C,
+    pub op: Op,
+    pub kind: Kind,
+}
+
+impl<C> Filter<C> {
+    pub const fn eq(name: &'static str, column: C, kind: Kind) -> Self {
+        Self { name, column, op: Op::Eq, kind }
+    }
+
+    pub const fn is_in(name: &'static str, column: C, kind: Kind) -> Self {
+        Self { name, column, op: Op::In, kind }
+    }
+
+    pub const fn like(name: &'static str, column: C) -> Self {
+        Self { name, column, op: Op::Like, kind: Kind::Str }
+    }
+
+    pub const fn range(name: &'static str, column: C, kind: Kind) -> Self {
+        Self { name, column, op: Op::Range, kind }
+    }
+}
+
+/// 列表接口的查询声明
+pub struct Spec<C: 'static> {
+    /// 可过滤字段
+    pub filters: &'static [Filter<C>],
+    /// 可排序字段：查询参数名及对应的列
+    pub sorts: &'static [(&'static str, C)],
+    /// 默认排序，附加在指定的排序之后以保证分页顺序稳定
+    pub default_sort: &'static [(C, Order)],
+    /// 游标列，须为唯一且随写入递增的整数列（如自增主键），为 `None` 时不支持游标分页
+    pub cursor: Option<C>,
+}
+
+/// 列表查询参数，由查询字符串解析，经 `Spec::compile` 校验后生成查询条件
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(from = "HashMap<String, String>")]
+pub struct ListQuery(HashMap<String, String>);
+
+impl From<HashMap<String, String>> for ListQuery {
+    fn from(args: HashMap<String, String>) -> Self {
+        Self(args)
+    }
+}
+
+impl ListQuery {
+    /// 取出不属于字段过滤条件的参数（如上级资源ID），值为空时返回 `None`
+    pub fn take(&mut self, name: &str) -> Option<String> {
+        self.0.remove(name).filter(|v| !v.is_empty())
+    }
+}
+
+/// 列表查询的分页及排序参数，用于接口文档
+#[derive(IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListParams {
+    /// 页码，从 1 开始
+    pub page: Option<u64>,
+    /// 每页数量，默认 20，最大 100
+    pub size: Option<u64>,
+    /// 排序字段，逗号分隔，`-` 前缀表示降序，如 `-create_time`
+    pub sort: Option<String>,
+    /// 是否统计总数，默认页码分页统计、游标分页不统计
+    pub count: Option<bool>,
+}
+
+/// 游标分页参数，用于接口文档
+#[derive(IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CursorParams {
+    /// 游标，首页传空值，之后传上一页返回的 `next_cursor`；不传时按页码分页
+    pub cursor: Option<String>,
+}
+
+/// 校验后的列表查询
+#[derive(Debug)]
+pub struct Compiled<C> {
+    /// 过滤条件
+    pub cond: Condition,
+    /// 排序
+    pub orders: Vec<(C, Order)>,
+    /// 页码，从 1 开始
+    pub page: u64,
+    /// 每页数量
+    pub size: u64,
+    /// 是否统计总数
+    pub count: bool,
+    /// 游标分页：游标列及上一页最后一条记录的值，首页为 `None`
+    pub cursor: Option<(C, Option<i64>)>,
+}
+
+impl<C: ColumnTrait> Compiled<C> {
+    pub fn offset(&self) -> u64 {
+        (self.page - 1) * self.size
+    }
+
+    /// 附加排序
+    pub fn order<Q: QueryOrder>(&self, mut query: Q) -> Q {
+        for (column, order) in &self.orders {
+            query = query.order_by(*column, order.clone());
+        }
+        query
+    }
+
+    /// 按过滤条件统计总数
+    pub async fn total<E, D>(&self, db: &D, select: Select<E>) -> Result<u64, DbErr>
+    where
+        E: EntityTrait<Column = C>,
+        E::Model: Sync,
+        D: ConnectionTrait,
+    {
+        select.filter(self.cond.clone()).count(db).await
+    }
+
+    /// 按过滤条件分批查询全部结果（忽略排序及分页），用于导出等需遍历全部结果的场景
+    pub fn batches<E>(&self, select: Select<E>, key: C, size: u64) -> Batches<E>
+    where
+        E: EntityTrait<Column = C>,
+    {
+        Batches {
+            select: select.filter(self.cond.clone()),
+            key,
+            after: None,
+            size,
+            done: false,
+        }
+    }
+
+    /// 按过滤条件、排序及分页查询一页数据，多查询一条以判断是否还有下一页
+    pub async fn fetch<E, D>(&self, db: &D, select: Select<E>) -> Result<Page<E::Model>, DbErr>
+    where
+        E: EntityTrait<Column = C>,
+        E::Model: Sync,
+        D: ConnectionTrait,
+    {
+        let select = select.filter(self.cond.clone());
+        let total = match self.count {
+            true => Some(select.clone().count(db).await?),
+            false => None,
+        };
+
+        let mut select = self.order(select);
+        match self.cursor {
+            Some((column, after)) => {
+                if let Some(v) = after {
+                    select = select.filter(column.lt(v));
+                }
+            }
+            None => select = select.offset(self.offset()),
+        }
+        let mut list = select.limit(self.size + 1).all(db).await?;
+        let has_more = list.len() as u64 > self.size;
+        list.truncate(self.size as usize);
+
+        let next_cursor = match self.cursor {
+            Some((column, _)) if has_more => list
+                .last()
+                .and_then(|v| <i64 as ValueType>::try_from(v.get(column)).ok())
+                .map(encode_cursor),
+            _ => None,
+        };
+
+        Ok(Page {
+            list,
+            total,
+            page: self.cursor.is_none().then_some(self.page),
+            size: self.size,
+            has_more,
+            next_cursor,
+        })
+    }
+}
+
+impl<C: ColumnTrait> Spec<C> {
+    /// 校验查询参数，生成过滤条件、排序及分页
+    pub fn compile(&self, query: &ListQuery) -> Result<Compiled<C>, ApiErr> {
+        let args = &query.0;
+        let mut cond = Condition::all();
+
+        // 按参数名排序，出错时提示稳定
+        let mut names: Vec<&String> = args
+            .keys()
+            .filter(|k| !matches!(k.as_str(), "page" | "size" | "sort" | "count" | "cursor"))
+            .collect();
+        names.sort();
+        for name in names {
+            let filter = self
+                .filters
+                .iter()
+                .find(|f| f.name == name)
+                .ok_or_else(|| ApiErr::ErrParams(Some(format!("不支持的查询参数：{}", name))))?;
+            let v = args[name].trim();
+            if v.is_empty() {
+                continue;
+            }
+            let column = filter.column;
+            cond = match filter.op {
+                Op::Eq => cond.add(column.eq(parse(filter, v)?)),
+                Op::Like => cond.add(column.contains(v)),
+                Op::In => {
+                    let values = v
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(|s| parse(filter, s))
+                        .collect::<Result<Vec<Value>, ApiErr>>()?;
+                    cond.add(column.is_in(values))
+                }
+                Op::Range => {
+                    let (start, end) = v
+                        .split_once(',')
+                        .ok_or_else(|| ApiErr::ErrParams(Some(format!("参数 {} 应为逗号分隔的起止值", name))))?;
+                    let (start, end) = (start.trim(), end.trim());
+                    if !start.is_empty() {
+                        cond = cond.add(column.gte(parse(filter, start)?));
+                    }
+                    if !end.is_empty() {
+                        cond = cond.add(column.lte(parse(filter, end)?));
+                    }
+                    cond
+                }
+            };
+        }
+
+        let mut orders: Vec<(C, Order)> = Vec::new();
+        if let Some(v) = args.get("sort") {
+            for field in v.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let (name, order) = match field.strip_prefix('-') {
+                    Some(name) => (name, Order::Desc),
+                    None => (field.strip_prefix('+').unwrap_or(field), Order::Asc),
+                };
+                let column = self
+                    .sorts
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, c)| *c)
+                    .ok_or_else(|| ApiErr::ErrParams(Some(format!("不支持的排序字段：{}", name))))?;
+                if !orders.iter().any(|(c, _)| c.as_str() == column.as_str()) {
+                    orders.push((column, order));
+                }
+            }
+        }
+        for (column, order) in self.default_sort {
+            if !orders.iter().any(|(c, _)| c.as_str() == column.as_str()) {
+                orders.push((*column, order.clone()));
+            }
+        }
+
+        let page = match args.get("page").map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            Some(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|v| *v > 0)
+                .ok_or_else(|| ApiErr::ErrParams(Some("页码错误".to_string())))?,
+            None => 1,
+        };
+        let size = match args.get("size").map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            Some(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|v| (1..=MAX_SIZE).contains(v))
+                .ok_or_else(|| ApiErr::ErrParams(Some(format!("每页数量应为 1-{}", MAX_SIZE))))?,
+            None => DEFAULT_SIZE,
+        };
+        // 偏移量须能以 i64 表示，`Compiled::offset` 不再溢出
+        (page - 1)
+            .checked_mul(size)
+            .filter(|v| *v <= i64::MAX as u64)
+            .ok_or_else(|| ApiErr::ErrParams(Some("页码过大".to_string())))?;
+
+        let cursor = match (args.get("cursor"), self.cursor) {
+            (None, _) => None,
+            (Some(_), None) => return Err(ApiErr::ErrParams(Some("不支持游标分页".to_string()))),
+            (Some(v), Some(column)) => {
+                if args.contains_key("page") || args.contains_key("sort") {
+                    return Err(ApiErr::ErrParams(Some("游标分页不支持 page、sort 参数".to_string())));
+                }
+                orders = vec![(column, Order::Desc)];
+                let v = v.trim();
+                let after = match v.is_empty() {
+                    true => None,
+                    false => Some(decode_cursor(v).ok_or_else(|| ApiErr::ErrParams(Some("游标错误".to_string())))?),
+                };
+                Some((column, after))
+            }
+        };
+
+        let count = match args.get("count").map(|v| v.trim()) {
+            None | Some("") => cursor.is_none(),
+            Some("1" | "true") => true,
+            Some("0" | "false") => false,
+            Some(_) => return Err(ApiErr::ErrParams(Some("参数 count 格式错误".to_string()))),
+        };
+
+        Ok(Compiled {
+            cond,
+            orders,
+            page,
+            size,
+            count,
+            cursor,
+        })
+    }
+}
+
+/// 分批查询：按唯一的整数键列倒序，以上一批最后一条记录的键值为起点，避免深分页
+pub struct Batches<E: EntityTrait> {
+    select: Select<E>,
+    key: E::Column,
+    after: Option<i64>,
+    size: u64,
+    done: bool,
+}
+
+impl<E: EntityTrait> Batches<E> {
+    /// 查询下一批，已无数据时返回 `None`
+    pub async fn next<D: ConnectionTrait>(&mut self, db: &D) -> Result<Option<Vec<E::Model>>, DbErr> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut select = self.select.clone().order_by(self.key, Order::Desc).limit(self.size);
+        if let Some(v) = self.after {
+            select = select.filter(self.key.lt(v));
+        }
+        let list = select.all(db).await?;
+
+        self.after = list.last().and_then(|v| <i64 as ValueType>::try_from(v.get(self.key)).ok());
+        self.done = (list.len() as u64) < self.size || self.after.is_none();
+        Ok((!list.is_empty()).then_some(list))
+    }
+}
+
+// 游标对客户端不透明，内容为游标列的值
+fn encode_cursor(v: i64) -> String {
+    URL_SAFE_NO_PAD.encode(v.to_string())
+}
+
+fn decode_cursor(v: &str) -> Option<i64> {
+    let bytes = URL_SAFE_NO_PAD.decode(v).ok()?;
+    String::from_utf8(bytes).ok()?.parse().ok()
+}
+
+// 按字段的值类型解析过滤值
+fn parse<C>(filter: &Filter<C>, v: &str) -> Result<Value, ApiErr> {
+    let err = || ApiErr::ErrParams(Some(format!("参数 {} 格式错误", filter.name)));
+    match filter.kind {
+        Kind::Int => v.parse::<i64>().map(Value::from).map_err(|_| err()),
+        Kind::Str => Ok(Value::from(v)),
+        Kind::Time => xtime::to_timestamp(xtime::DATETIME, v, offset!(+8))
+            .map(Value::from)
+            .map_err(|_| err()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use sea_orm::{DbBackend, EntityTrait, QueryFilter, QuerySelect, QueryTrait};
+
+    use super::{encode_cursor, Filter, Kind, ListQuery, Spec, DEFAULT_SIZE};
+    use crate::result::response::ApiErr;
+
+    mod user {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+        #[sea_orm(table_name = "user")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i64,
+            pub name: String,
+            pub status: i16,
+            pub create_time: i64,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    const SPEC: Spec<user::Column> = Spec {
+        filters: &[
+            Filter::eq("status", user::Column::Status, Kind::Int),
+            Filter::is_in("id", user::Column::Id, Kind::Int),
+            Filter::like("name", user::Column::Name),
+            Filter::range("create_time", user::Column::CreateTime, Kind::Time),
+        ],
+        sorts: &[("id", user::Column::Id), ("name", user::Column::Name)],
+        default_sort: &[(user::Column::Id, sea_orm::Order::Desc)],
+        cursor: Some(user::Column::Id),
+    };
+
+    fn query(args: &[(&str, &str)]) -> ListQuery {
+        ListQuery::from(args.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>())
+    }
+
+    fn sql(args: &[(&str, &str)]) -> String {
+        let Ok(compiled) = SPEC.compile(&query(args)) else {
+            panic!("查询参数错误：{:?}", args);
+        };
+        compiled
+            .order(user::Entity::find().filter(compiled.cond.clone()))
+            .offset(compiled.offset())
+            .limit(compiled.size)
+            .build(DbBackend::Sqlite)
+            .to_string()
+    }
+
+    fn params_err(args: &[(&str, &str)]) -> bool {
+        matches!(SPEC.compile(&query(args)), Err(ApiErr::ErrParams(_)))
+    }
+
+    #[test]
+    fn compile() {
+        let v = sql(&[]);
+        assert!(v.ends_with(r#"ORDER BY "user"."id" DESC LIMIT 20 OFFSET 0"#), "{}", v);
+
+        let v = sql(&[
+            ("status", "1"),
+            ("id", "1, 2"),
+            ("name", "ad"),
+            ("create_time", "2023-07-12 13:45:13,"),
+            ("sort", "name,-id"),
+            ("page", "3"),
+            ("size", "10"),
+        ]);
+        assert!(v.contains(r#""user"."status" = 1"#), "{}", v);
+        assert!(v.contains(r#""user"."id" IN (1, 2)"#), "{}", v);
+        assert!(v.contains(r#""user"."name" LIKE '%ad%'"#), "{}", v);
+        assert!(v.contains(r#""user"."create_time" >= 1689140713"#), "{}", v);
+        assert!(!v.contains("<="), "{}", v);
+        assert!(v.ends_with(r#"ORDER BY "user"."name" ASC, "user"."id" DESC LIMIT 10 OFFSET 20"#), "{}", v);
+
+        // 空值忽略
+        let v = sql(&[("name", ""), ("status", " ")]);
+        assert!(v.contains("WHERE TRUE ORDER BY"), "{}", v);
+        assert!(matches!(SPEC.compile(&query(&[("page", "")])), Ok(v) if v.page == 1 && v.size == DEFAULT_SIZE));
+    }
+
+    #[test]
+    fn count_and_cursor() {
+        let compile = |args: &[(&str, &str)]| SPEC.compile(&query(args)).ok();
+        assert!(compile(&[]).is_some_and(|v| v.count && v.cursor.is_none()));
+        assert!(compile(&[("count", "0")]).is_some_and(|v| !v.count));
+        assert!(compile(&[("cursor", "")]).is_some_and(|v| !v.count && v.cursor.is_some_and(|(_, after)| after.is_none())));
+        let cursor = encode_cursor(42);
+        let v = compile(&[("cursor", &cursor), ("count", "true"), ("name", "a")]).unwrap();
+        assert!(v.count);
+        assert!(matches!(v.cursor, Some((_, Some(42)))));
+        assert_eq!(v.orders.len(), 1);
+
+        assert!(params_err(&[("cursor", "!")]));
+        assert!(params_err(&[("cursor", ""), ("page", "2")]));
+        assert!(params_err(&[("cursor", ""), ("sort", "name")]));
+        assert!(params_err(&[("count", "x")]));
+    }
+
+    #[test]
+    fn reject() {
+        assert!(params_err(&[("unknown", "1")]));
+        assert!(params_err(&[("status", "a")]));
+        assert!(params_err(&[("id", "1,a")]));
+        assert!(params_err(&[("create_time", "2023-07-12")]));
+        assert!(params_err(&[("create_time", "2023-07-12,")]));
+        assert!(params_err(&[("sort", "status")]));
+        assert!(params_err(&[("page", "0")]));
+        assert!(params_err(&[("page", "18446744073709551615")]));
+        assert!(params_err(&[("page", "92233720368547760"), ("size", "100")]));
+        assert!(params_err(&[("size", "101")]));
+        assert!(params_err(&[("size", "x")]));
+
+        let mut q = query(&[("role_id", "1")]);
+        assert_eq!(q.take("role_id").as_deref(), Some("1"));
+        assert!(SPEC.compile(&q).is_ok());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod page;
 pub mod rejection;
 pub mod response;
-pub mod status;
\ No newline at end of file
+pub mod status;
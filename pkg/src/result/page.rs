@@ -0,0 +1,38 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// 分页数据
+///
+/// 页码分页返回 `page`；游标分页不返回 `page`，有下一页时返回 `next_cursor`，作为下次请求的 `cursor` 参数
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Page<T> {
+    /// 当前页数据
+    pub list: Vec<T>,
+    /// 总数，未统计时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    /// 页码，从 1 开始，游标分页时省略
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
+    /// 每页数量
+    pub size: u64,
+    /// 是否还有下一页
+    pub has_more: bool,
+    /// 下一页游标，仅游标分页且有下一页时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// 转换列表数据，分页信息不变
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            list: self.list.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            size: self.size,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
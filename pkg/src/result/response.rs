@@ -24,7 +24,11 @@ where
 pub enum ApiErr {
     Error(i32, String),
     ErrParams(Option<String>),
+    ErrCaptcha(Option<String>),
     ErrAuth(Option<String>),
+    ErrAuthExpired(Option<String>),
+    ErrAuthInvalid(Option<String>),
+    ErrPasswordChange(Option<String>),
     ErrPerm(Option<String>),
     ErrNotFound(Option<String>),
     ErrSystem(Option<String>),
@@ -34,12 +38,36 @@ pub enum ApiErr {
 
 use ApiErr::*;
 
+/// 错误码及说明，用于接口文档
+pub const CODES: &[(i32, &str)] = &[
+    (0, "成功"),
+    (10000, "参数错误"),
+    (10001, "需要图形验证码或验证码错误，需刷新验证码"),
+    (20000, "未授权，请先登录"),
+    (20001, "访问令牌已过期，可使用刷新令牌换取新令牌"),
+    (20002, "访问令牌无效，需重新登录"),
+    (20003, "需先修改密码（首次登录或密码被重置）"),
+    (30000, "权限不足"),
+    (40000, "数据不存在"),
+    (50000, "内部服务器错误"),
+    (60000, "数据异常"),
+    (70000, "服务异常"),
+];
+
 impl IntoResponse for ApiErr {
     fn into_response(self) -> Response {
         let status: Status<()> = match self {
             Error(code, msg) => Status::Err(code, msg),
             ErrParams(msg) => Status::Err(10000, msg.unwrap_or(String::from("参数错误"))),
+            // 需要图形验证码或验证码错误，前端需刷新验证码
+            ErrCaptcha(msg) => Status::Err(10001, msg.unwrap_or(String::from("验证码错误"))),
             ErrAuth(msg) => Status::Err(20000, msg.unwrap_or(String::from("未授权，请先登录"))),
+            // 访问令牌过期，前端可使用刷新令牌重新获取
+            ErrAuthExpired(msg) => Status::Err(20001, msg.unwrap_or(String::from("登录已过期，请刷新令牌"))),
+            // 访问令牌无效，前端需重新登录
+            ErrAuthInvalid(msg) => Status::Err(20002, msg.unwrap_or(String::from("令牌无效，请重新登录"))),
+            // 需先修改密码（首次登录或密码被重置）
+            ErrPasswordChange(msg) => Status::Err(20003, msg.unwrap_or(String::from("请先修改密码"))),
             ErrPerm(msg) => Status::Err(30000, msg.unwrap_or(String::from("权限不足"))),
             ErrNotFound(msg) => Status::Err(40000, msg.unwrap_or(String::from("数据不存在"))),
             ErrSystem(msg) => Status::Err(
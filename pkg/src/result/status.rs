@@ -1,17 +1,27 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+/// 统一返回结构
+#[derive(Serialize, ToSchema)]
 pub struct Reply<T>
 where
     T: Serialize,
 {
+    /// 错误码，成功时为 0
     pub code: i32,
+    /// 是否失败
     pub err: bool,
+    /// 提示信息
     pub msg: String,
+    /// 返回数据，无数据或失败时省略
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<T>,
 }
 
+/// 无返回数据，仅用于接口文档中的 `Reply<Empty>`
+#[derive(Serialize, ToSchema)]
+pub struct Empty {}
+
 pub enum Status<T>
 where
     T: Serialize,
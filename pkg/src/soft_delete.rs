@@ -0,0 +1,139 @@
+//! 逻辑删除：以 `deleted_flag` 标记删除，删除的数据进入回收站，可恢复或永久删除
+//!
+//! 业务查询统一使用 `find_alive` 排除已删除的数据；回收站使用 `find_deleted`。
+
+use std::future::Future;
+
+use sea_orm::sea_query::{Expr, SelectStatement};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, Iterable, PrimaryKeyToColumn, QueryFilter, QuerySelect,
+    QueryTrait, Select,
+};
+
+/// 未删除
+pub const NORMAL: i16 = 0;
+/// 已删除
+pub const DELETED: i16 = 1;
+
+/// 支持逻辑删除的实体，主键为单个 `i64` 列
+pub trait SoftDelete: EntityTrait {
+    /// 删除标记列
+    const DELETED_FLAG: Self::Column;
+    /// 更新时间列，删除、恢复时更新，回收站中作为删除时间
+    const UPDATE_TIME: Self::Column;
+
+    /// 主键列
+    fn id_column() -> Self::Column {
+        Self::PrimaryKey::iter()
+            .next()
+            .expect("实体没有主键")
+            .into_column()
+    }
+
+    /// 未删除的数据
+    fn find_alive() -> Select<Self> {
+        Self::find().filter(Self::DELETED_FLAG.eq(NORMAL))
+    }
+
+    /// 未删除数据的主键子查询，用于按关联表查询时排除已删除的数据
+    fn alive_ids() -> SelectStatement {
+        Self::find_alive()
+            .select_only()
+            .column(Self::id_column())
+            .into_query()
+    }
+
+    /// 回收站中的数据
+    fn find_deleted() -> Select<Self> {
+        Self::find().filter(Self::DELETED_FLAG.eq(DELETED))
+    }
+
+    /// 逻辑删除，返回删除的行数（已删除的数据不计）
+    fn soft_delete<C: ConnectionTrait>(
+        db: &C,
+        ids: Vec<i64>,
+        now: i64,
+    ) -> impl Future<Output = Result<u64, DbErr>> + Send {
+        set_flag::<Self, C>(db, ids, NORMAL, DELETED, now)
+    }
+
+    /// 从回收站恢复，返回恢复的行数
+    fn restore<C: ConnectionTrait>(
+        db: &C,
+        ids: Vec<i64>,
+        now: i64,
+    ) -> impl Future<Output = Result<u64, DbErr>> + Send {
+        set_flag::<Self, C>(db, ids, DELETED, NORMAL, now)
+    }
+
+    /// 永久删除回收站中的数据，返回删除的行数；关联数据由调用方在同一事务中清理
+    fn purge<C: ConnectionTrait>(db: &C, ids: Vec<i64>) -> impl Future<Output = Result<u64, DbErr>> + Send {
+        async move {
+            let ret = Self::delete_many()
+                .filter(Self::id_column().is_in(ids))
+                .filter(Self::DELETED_FLAG.eq(DELETED))
+                .exec(db)
+                .await?;
+            Ok(ret.rows_affected)
+        }
+    }
+}
+
+async fn set_flag<E: SoftDelete, C: ConnectionTrait>(
+    db: &C,
+    ids: Vec<i64>,
+    from: i16,
+    to: i16,
+    now: i64,
+) -> Result<u64, DbErr> {
+    let ret = E::update_many()
+        .col_expr(E::DELETED_FLAG, Expr::value(to))
+        .col_expr(E::UPDATE_TIME, Expr::value(now))
+        .filter(E::id_column().is_in(ids))
+        .filter(E::DELETED_FLAG.eq(from))
+        .exec(db)
+        .await?;
+    Ok(ret.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DbBackend, QueryTrait};
+
+    use super::SoftDelete;
+
+    mod user {
+        use sea_orm::entity::prelude::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+        #[sea_orm(table_name = "user")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i64,
+            pub deleted_flag: i16,
+            pub update_time: i64,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+
+        impl super::SoftDelete for Entity {
+            const DELETED_FLAG: Column = Column::DeletedFlag;
+            const UPDATE_TIME: Column = Column::UpdateTime;
+        }
+    }
+
+    #[test]
+    fn query() {
+        let sql = user::Entity::find_alive().build(DbBackend::Sqlite).to_string();
+        assert!(sql.ends_with(r#"WHERE "user"."deleted_flag" = 0"#), "{}", sql);
+        let sql = user::Entity::find_deleted().build(DbBackend::Sqlite).to_string();
+        assert!(sql.ends_with(r#"WHERE "user"."deleted_flag" = 1"#), "{}", sql);
+        assert_eq!(
+            DbBackend::Sqlite.build(&user::Entity::alive_ids()).to_string(),
+            r#"SELECT "user"."id" FROM "user" WHERE "user"."deleted_flag" = 0"#
+        );
+    }
+}
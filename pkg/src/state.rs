@@ -0,0 +1,79 @@
+use std::sync::{Arc, OnceLock};
+
+use config::Config;
+use sea_orm::DatabaseConnection;
+
+use crate::audit::Queue;
+use crate::cache::{self, Store};
+use crate::crypto::password::{self, PasswordHasher};
+use crate::crypto::policy::Policy;
+use crate::db;
+use crate::perm;
+use crate::util::{self, TrustedProxies};
+use crate::xtime::{Clock, SystemClock};
+
+// 兼容旧代码的全局应用状态，由 `set` 注入
+static STATE: OnceLock<AppState> = OnceLock::new();
+
+/// 应用状态：数据库连接、配置、缓存、时钟、密码哈希算法、密码策略、受信任的代理、操作日志队列及权限缓存
+///
+/// 通过 axum `State` 传给处理函数及中间件，再由处理函数传给服务；克隆仅增加引用计数。
+/// 字段均为公开，测试中可用结构体更新语法替换其中的部分依赖（如固定时间的时钟）。
+#[derive(Clone)]
+pub struct AppState {
+    pub db: DatabaseConnection,
+    pub config: Arc<Config>,
+    pub cache: Arc<dyn Store>,
+    pub clock: Arc<dyn Clock>,
+    pub hasher: Arc<dyn PasswordHasher>,
+    /// 账号不存在时用于校验密码的哈希值，使校验耗时与账号存在时一致；由 `hasher` 计算，替换 `hasher` 时需一并替换
+    pub dummy_hash: Arc<str>,
+    pub policy: Arc<Policy>,
+    pub proxies: Arc<TrustedProxies>,
+    pub audit: Queue,
+    pub perms: Arc<perm::Cache>,
+}
+
+impl AppState {
+    /// 按配置连接数据库并创建应用状态
+    pub async fn from_config(config: Config) -> Self {
+        let db = db::connect(&config).await;
+        Self::with_db(db, config).await
+    }
+
+    /// 使用已建立的数据库连接创建应用状态，其余依赖按配置创建；操作日志队列需另行启动
+    pub async fn with_db(db: DatabaseConnection, config: Config) -> Self {
+        let hasher: Arc<dyn PasswordHasher> = Arc::from(password::from_config(&config));
+        let dummy_hash = hasher
+            .hash(&util::nonce(16))
+            .unwrap_or_else(|e| panic!("密码哈希计算失败：{}", e));
+        Self {
+            db,
+            cache: Arc::from(cache::from_config(&config).await),
+            clock: Arc::new(SystemClock),
+            hasher,
+            dummy_hash: Arc::from(dummy_hash),
+            policy: Arc::new(Policy::from_config(&config)),
+            proxies: Arc::new(TrustedProxies::from_config(&config)),
+            audit: Queue::default(),
+            perms: Arc::default(),
+            config: Arc::new(config),
+        }
+    }
+}
+
+/// 注入全局应用状态，供仍使用 `db::conn()`、`config::global()` 等全局访问方式的代码使用，
+/// 已注入时忽略
+pub fn set(state: AppState) {
+    let _ = STATE.set(state);
+}
+
+/// 全局应用状态，未注入时返回 `None`
+pub fn get() -> Option<&'static AppState> {
+    STATE.get()
+}
+
+/// 全局应用状态，未注入时 panic
+pub fn global() -> &'static AppState {
+    get().unwrap_or_else(|| panic!("应用状态未初始化"))
+}
@@ -1,10 +1,12 @@
 use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TreeNode {
     pub id: i64,
     pub name: String,
+    #[schema(no_recursion)]
     pub children: Vec<TreeNode>,
 }
 
@@ -25,7 +27,7 @@ pub fn build_tree(nodes: Vec<(i64, String, Option<i64>)>) -> Option<Vec<TreeNode
             children: Vec::new(),
         };
         if let Some(parent_id) = parent_id {
-            children_by_parent.entry(parent_id).or_insert_with(Vec::new).push(node);
+            children_by_parent.entry(parent_id).or_default().push(node);
         } else {
             tree.push(node);
         }
@@ -40,7 +42,7 @@ pub fn build_tree(nodes: Vec<(i64, String, Option<i64>)>) -> Option<Vec<TreeNode
                     node.children = build_subtree(child.id, children_by_parent);
                     vec![node]
                 }).collect()
-            }).unwrap_or_else(Vec::new)
+            }).unwrap_or_default()
     }
 
     // 从根节点开始构建整个树
@@ -52,4 +54,63 @@ pub fn build_tree(nodes: Vec<(i64, String, Option<i64>)>) -> Option<Vec<TreeNode
         // 否则返回 Some 包裹的树
         Some(root)
     }
-}
\ No newline at end of file
+}
+
+/// 携带完整数据的树节点
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Node<T> {
+    #[serde(flatten)]
+    pub data: T,
+    #[schema(no_recursion)]
+    pub children: Vec<Node<T>>,
+}
+
+/// 根据 (id, parent_id, data) 列表构建树，`root` 为根节点的 parent_id，同级节点保持输入顺序
+pub fn build<T>(nodes: Vec<(i64, i64, T)>, root: i64) -> Vec<Node<T>> {
+    let mut children_by_parent: HashMap<i64, Vec<(i64, T)>> = HashMap::new();
+    for (id, parent_id, data) in nodes {
+        children_by_parent.entry(parent_id).or_default().push((id, data));
+    }
+
+    fn build_subtree<T>(parent_id: i64, children_by_parent: &mut HashMap<i64, Vec<(i64, T)>>) -> Vec<Node<T>> {
+        let children = children_by_parent.remove(&parent_id).unwrap_or_default();
+        children
+            .into_iter()
+            .map(|(id, data)| Node {
+                data,
+                children: build_subtree(id, children_by_parent),
+            })
+            .collect()
+    }
+
+    build_subtree(root, &mut children_by_parent)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::build;
+
+    #[test]
+    fn build_tree() {
+        let tree = build(
+            vec![
+                (1, 0, "系统管理"),
+                (2, 1, "员工管理"),
+                (3, 2, "新增员工"),
+                (4, 1, "角色管理"),
+                (5, 0, "首页"),
+                (6, 7, "孤立节点"),
+            ],
+            0,
+        );
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].data, "系统管理");
+        assert_eq!(tree[0].children.len(), 2);
+        assert_eq!(tree[0].children[0].data, "员工管理");
+        assert_eq!(tree[0].children[0].children[0].data, "新增员工");
+        assert_eq!(tree[0].children[1].data, "角色管理");
+        assert_eq!(tree[1].data, "首页");
+        assert!(tree[1].children.is_empty());
+    }
+}
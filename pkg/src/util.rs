@@ -1,43 +1,164 @@
-use rand::distr::{Alphanumeric,Distribution, SampleString};
+use rand::distr::{Alphanumeric, SampleString};
 
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, convert::Infallible, net::{IpAddr, SocketAddr}};
+use axum::extract::{ConnectInfo, FromRequestParts};
+use config::Config;
+use http::{header::USER_AGENT, request::Parts, Extensions, HeaderMap};
 use validator::ValidationError;
 
+use crate::state::AppState;
+
 pub fn nonce(size: usize) -> String {
     let mut rng = rand::rng();
     //Alphanumeric.sample_string(&mut rng, size)
     Alphanumeric.sample_string(&mut rng, size)
 }
 
-pub fn query_page(args: &HashMap<String, String>) -> (u64, u64) {
-    let mut offset: u64 = 0;
-    let mut limit: u64 = 20;
+pub fn new_validation_err(s: String) -> ValidationError {
+    ValidationError {
+        code: Cow::from(""),
+        message: Some(Cow::from(s)),
+        params: HashMap::new(),
+    }
+}
+
+/// 受信任的反向代理地址（IP 或 CIDR），仅当请求来自其中的地址时才读取代理转发的请求头
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<(IpAddr, u8)>);
+
+impl TrustedProxies {
+    /// 配置项 `app.trusted_proxies`，如 `["127.0.0.1", "10.0.0.0/8"]`；未配置时不信任任何代理
+    pub fn from_config(cfg: &Config) -> Self {
+        Self::parse(&cfg.get::<Vec<String>>("app.trusted_proxies").unwrap_or_default())
+    }
 
-    if let Some(v) = args.get("size") {
-        let size: u64 = v.parse().unwrap_or_default();
-        if size > 0 {
-            limit = size
+    /// 解析地址列表，格式错误的项忽略
+    pub fn parse(items: &[String]) -> Self {
+        let mut nets = Vec::with_capacity(items.len());
+        for item in items {
+            let (addr, prefix) = match item.trim().split_once('/') {
+                Some((addr, prefix)) => (addr, prefix.parse::<u8>().ok()),
+                None => (item.trim(), None),
+            };
+            let Ok(addr) = addr.parse::<IpAddr>() else {
+                tracing::warn!(item, "invalid trusted proxy");
+                continue;
+            };
+            let max = if addr.is_ipv4() { 32 } else { 128 };
+            match prefix {
+                Some(v) if v > max => tracing::warn!(item, "invalid trusted proxy"),
+                Some(v) => nets.push((addr, v)),
+                None if item.contains('/') => tracing::warn!(item, "invalid trusted proxy"),
+                None => nets.push((addr, max)),
+            }
         }
+        Self(nets)
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.0.iter().any(|(net, prefix)| match (net, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - *prefix as u32).unwrap_or(0);
+                u32::from(*net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - *prefix as u32).unwrap_or(0);
+                u128::from(*net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        })
     }
+}
 
-    if limit > 100 {
-        limit = 100
+// 获取客户端IP：连接来自受信任的代理时，使用代理转发的请求头，否则使用连接地址
+pub fn client_ip(proxies: &TrustedProxies, headers: &HeaderMap, extensions: &Extensions) -> String {
+    let peer = match extensions.get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => addr.ip().to_canonical(),
+        None => return String::new(),
+    };
+    if !proxies.contains(peer) {
+        return peer.to_string();
     }
 
-    if let Some(v) = args.get("page") {
-        let page: u64 = v.parse().unwrap_or_default();
-        if page > 0 {
-            offset = (page - 1) * limit
+    // 从右向左跳过受信任的代理，第一个不受信任的地址即客户端
+    if let Some(v) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
+        let mut ip = None;
+        for item in v.rsplit(',') {
+            match item.trim().parse::<IpAddr>() {
+                Ok(addr) => {
+                    ip = Some(addr.to_canonical());
+                    if !proxies.contains(addr) {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
         }
+        if let Some(v) = ip {
+            return v.to_string();
+        }
+    }
+    if let Some(v) = headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<IpAddr>().ok())
+    {
+        return v.to_canonical().to_string();
     }
+    peer.to_string()
+}
 
-    (offset, limit)
+/// 客户端信息（IP、User-Agent）
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: String,
+    pub user_agent: String,
 }
 
-pub fn new_validation_err(s: String) -> ValidationError {
-    ValidationError {
-        code: Cow::from(""),
-        message: Some(Cow::from(s)),
-        params: HashMap::new(),
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        Ok(ClientInfo {
+            ip: client_ip(&state.proxies, &parts.headers, &parts.extensions),
+            user_agent: parts
+                .headers
+                .get(USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use axum::extract::ConnectInfo;
+    use http::{Extensions, HeaderMap};
+
+    use super::{client_ip, TrustedProxies};
+
+    fn ip(proxies: &[&str], peer: &str, forwarded: &str) -> String {
+        let proxies = TrustedProxies::parse(&proxies.iter().map(|v| v.to_string()).collect::<Vec<_>>());
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", forwarded.parse().unwrap());
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(format!("{}:80", peer).parse::<SocketAddr>().unwrap()));
+        client_ip(&proxies, &headers, &extensions)
+    }
+
+    #[test]
+    fn trusted_proxies() {
+        // 不受信任的连接忽略转发的请求头
+        assert_eq!(ip(&[], "1.2.3.4", "9.9.9.9"), "1.2.3.4");
+        assert_eq!(ip(&["10.0.0.0/8"], "11.0.0.1", "9.9.9.9"), "11.0.0.1");
+        // 跳过受信任的代理
+        assert_eq!(ip(&["10.0.0.0/8"], "10.0.0.1", "9.9.9.9, 8.8.8.8, 10.0.0.2"), "8.8.8.8");
+        assert_eq!(ip(&["127.0.0.1", "bad", "10.0.0.0/33"], "127.0.0.1", "9.9.9.9"), "9.9.9.9");
+        assert_eq!(ip(&["::1"], "[::1]", "garbage"), "::1");
+        assert!(client_ip(&TrustedProxies::default(), &HeaderMap::new(), &Extensions::new()).is_empty());
     }
 }
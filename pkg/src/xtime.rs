@@ -9,13 +9,29 @@ pub fn now(offset: time::UtcOffset) -> time::OffsetDateTime {
     time::OffsetDateTime::now_utc().to_offset(offset)
 }
 
+/// 时钟，测试中可替换为固定时间
+pub trait Clock: Send + Sync {
+    /// 当前时间
+    fn now(&self, offset: time::UtcOffset) -> time::OffsetDateTime;
+}
+
+/// 系统时钟
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self, offset: time::UtcOffset) -> time::OffsetDateTime {
+        now(offset)
+    }
+}
+
 // 根据时间字符串生成时间对象
 pub fn from_str(
     fmt: &str,
     datetime: &str,
     offset: time::UtcOffset,
 ) -> anyhow::Result<time::OffsetDateTime> {
-    let format = time::format_description::parse(fmt)?;
+    let format = time::format_description::parse_borrowed::<1>(fmt)?;
     let v = time::PrimitiveDateTime::parse(datetime, &format)?.assume_offset(offset);
     Ok(v)
 }
@@ -34,7 +50,7 @@ pub fn from_timestamp(
 
 // Unix时间戳格式化
 pub fn to_string(fmt: &str, timestamp: i64, offset: time::UtcOffset) -> anyhow::Result<String> {
-    let format = time::format_description::parse(fmt)?;
+    let format = time::format_description::parse_borrowed::<1>(fmt)?;
     if timestamp < 0 {
         let v = time::OffsetDateTime::now_utc()
             .to_offset(offset)
@@ -53,7 +69,7 @@ pub fn to_timestamp(fmt: &str, datetime: &str, offset: time::UtcOffset) -> anyho
         return Ok(0);
     }
 
-    let format = time::format_description::parse(fmt)?;
+    let format = time::format_description::parse_borrowed::<1>(fmt)?;
     let v = time::PrimitiveDateTime::parse(datetime, &format)?
         .assume_offset(offset)
         .unix_timestamp();
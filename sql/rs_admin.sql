@@ -1,176 +0,0 @@
-/*
- Navicat Premium Data Transfer
-
- Source Server         : mysql-db
- Source Server Type    : MySQL
- Source Server Version : 80035
- Source Host           : localhost:3306
- Source Schema         : rs_admin
-
- Target Server Type    : MySQL
- Target Server Version : 80035
- File Encoding         : 65001
-
- Date: 30/09/2024 19:41:44
-*/
-
-SET NAMES utf8mb4;
-SET FOREIGN_KEY_CHECKS = 0;
-
--- ----------------------------
--- Table structure for t_department
--- ----------------------------
-DROP TABLE IF EXISTS `t_department`;
-CREATE TABLE `t_department`  (
-  `department_id` bigint NOT NULL AUTO_INCREMENT COMMENT '部门主键id',
-  `name` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '部门名称',
-  `manager_id` bigint NULL DEFAULT NULL COMMENT '部门负责人id',
-  `parent_id` bigint NOT NULL DEFAULT 0 COMMENT '部门的父级id',
-  `sort` int NOT NULL COMMENT '部门排序',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '更新时间',
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  PRIMARY KEY (`department_id`) USING BTREE,
-  INDEX `parent_id`(`parent_id`) USING BTREE
-) ENGINE = InnoDB AUTO_INCREMENT = 4 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '部门' ROW_FORMAT = DYNAMIC;
-
--- ----------------------------
--- Table structure for t_employee
--- ----------------------------
-DROP TABLE IF EXISTS `t_employee`;
-CREATE TABLE `t_employee`  (
-  `employee_id` bigint NOT NULL AUTO_INCREMENT COMMENT '主键',
-  `login_name` varchar(30) CHARACTER SET utf8mb3 COLLATE utf8mb3_general_ci NOT NULL COMMENT '登录帐号',
-  `login_pwd` varchar(50) CHARACTER SET utf8mb3 COLLATE utf8mb3_general_ci NOT NULL COMMENT '登录密码',
-  `realname` varchar(30) CHARACTER SET utf8mb3 COLLATE utf8mb3_general_ci NOT NULL COMMENT '员工名称',
-  `avatar` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL,
-  `gender` tinyint(1) NOT NULL DEFAULT 0 COMMENT '性别',
-  `phone` varchar(15) CHARACTER SET utf8mb3 COLLATE utf8mb3_general_ci NULL DEFAULT NULL COMMENT '手机号码',
-  `department_id` int NOT NULL COMMENT '部门id',
-  `position_id` bigint NULL DEFAULT NULL COMMENT '职务ID',
-  `email` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '邮箱',
-  `disabled_flag` tinyint UNSIGNED NOT NULL COMMENT '是否被禁用 0否1是',
-  `deleted_flag` tinyint UNSIGNED NOT NULL COMMENT '是否删除0否 1是',
-  `administrator_flag` tinyint NOT NULL DEFAULT 0 COMMENT '是否为超级管理员: 0 不是，1是',
-  `login_token` varchar(32) CHARACTER SET utf8mb4 COLLATE utf8mb4_bin NOT NULL DEFAULT '' COMMENT '当前登录的token',
-  `login_at` bigint NOT NULL DEFAULT 0 COMMENT '最近一次登录时间',
-  `remark` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '备注',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '更新时间',
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  PRIMARY KEY (`employee_id`) USING BTREE
-) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '员工表' ROW_FORMAT = DYNAMIC;
-
--- ----------------------------
--- Table structure for t_menu
--- ----------------------------
-DROP TABLE IF EXISTS `t_menu`;
-CREATE TABLE `t_menu`  (
-  `menu_id` bigint NOT NULL AUTO_INCREMENT COMMENT '菜单ID',
-  `menu_name` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '菜单名称',
-  `menu_type` int NOT NULL COMMENT '类型',
-  `parent_id` bigint NOT NULL COMMENT '父菜单ID',
-  `sort` int NULL DEFAULT NULL COMMENT '显示顺序',
-  `path` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '路由地址',
-  `component` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '组件路径',
-  `perms_type` int NULL DEFAULT NULL COMMENT '权限类型',
-  `api_perms` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL COMMENT '后端权限字符串',
-  `web_perms` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL COMMENT '前端权限字符串',
-  `icon` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '菜单图标',
-  `context_menu_id` bigint NULL DEFAULT NULL COMMENT '功能点关联菜单ID',
-  `frame_flag` tinyint(1) NOT NULL DEFAULT 0 COMMENT '是否为外链',
-  `frame_url` text CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL COMMENT '外链地址',
-  `cache_flag` tinyint(1) NOT NULL DEFAULT 0 COMMENT '是否缓存',
-  `visible_flag` tinyint(1) NOT NULL DEFAULT 1 COMMENT '显示状态',
-  `disabled_flag` tinyint(1) NOT NULL DEFAULT 0 COMMENT '禁用状态',
-  `deleted_flag` tinyint(1) NOT NULL DEFAULT 0 COMMENT '删除状态',
-  `create_user_id` bigint NOT NULL COMMENT '创建人',
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  `update_user_id` bigint NULL DEFAULT NULL COMMENT '更新人',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '更新时间',
-  PRIMARY KEY (`menu_id`) USING BTREE
-) ENGINE = InnoDB AUTO_INCREMENT = 251 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_general_ci COMMENT = '菜单表' ROW_FORMAT = DYNAMIC;
-
--- ----------------------------
--- Table structure for t_operate_log
--- ----------------------------
-DROP TABLE IF EXISTS `t_operate_log`;
-CREATE TABLE `t_operate_log`  (
-  `operate_log_id` bigint NOT NULL AUTO_INCREMENT COMMENT '主键',
-  `operate_user_id` bigint NOT NULL COMMENT '用户id',
-  `operate_user_type` int NOT NULL COMMENT '用户类型',
-  `operate_user_name` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NOT NULL COMMENT '用户名称',
-  `module` varchar(50) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '操作模块',
-  `content` varchar(500) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '操作内容',
-  `url` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '请求路径',
-  `method` varchar(100) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '请求方法',
-  `param` text CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL COMMENT '请求参数',
-  `ip` varchar(255) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '请求ip',
-  `ip_region` varchar(1000) CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL DEFAULT NULL COMMENT '请求ip地区',
-  `user_agent` text CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL COMMENT '请求user-agent',
-  `success_flag` tinyint NULL DEFAULT NULL COMMENT '请求结果 0失败 1成功',
-  `fail_reason` longtext CHARACTER SET utf8mb4 COLLATE utf8mb4_unicode_ci NULL COMMENT '失败原因',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '更新时间',
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  PRIMARY KEY (`operate_log_id`) USING BTREE
-) ENGINE = InnoDB CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '操作记录' ROW_FORMAT = DYNAMIC;
-
--- ----------------------------
--- Table structure for t_position
--- ----------------------------
-DROP TABLE IF EXISTS `t_position`;
-CREATE TABLE `t_position`  (
-  `position_id` bigint NOT NULL AUTO_INCREMENT COMMENT '职务ID',
-  `position_name` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NOT NULL COMMENT '职务名称',
-  `level` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '职级',
-  `sort` int NULL DEFAULT 0 COMMENT '排序',
-  `remark` varchar(200) CHARACTER SET utf8mb4 COLLATE utf8mb4_general_ci NULL DEFAULT NULL COMMENT '备注',
-  `deleted_flag` tinyint(1) NULL DEFAULT 0,
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '更新时间',
-  PRIMARY KEY (`position_id`) USING BTREE
-) ENGINE = InnoDB AUTO_INCREMENT = 6 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_general_ci COMMENT = '职务表' ROW_FORMAT = DYNAMIC;
-
--- ----------------------------
--- Table structure for t_role
--- ----------------------------
-DROP TABLE IF EXISTS `t_role`;
-CREATE TABLE `t_role`  (
-  `role_id` bigint NOT NULL AUTO_INCREMENT COMMENT '主键',
-  `role_name` varchar(20) CHARACTER SET utf8mb3 COLLATE utf8mb3_general_ci NOT NULL COMMENT '角色名称',
-  `role_code` varchar(500) CHARACTER SET utf8mb3 COLLATE utf8mb3_general_ci NULL DEFAULT NULL COMMENT '角色编码',
-  `remark` varchar(255) CHARACTER SET utf8mb3 COLLATE utf8mb3_general_ci NULL DEFAULT NULL COMMENT '角色描述',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  PRIMARY KEY (`role_id`) USING BTREE,
-  UNIQUE INDEX `role_code_uni`(`role_code`) USING BTREE
-) ENGINE = InnoDB AUTO_INCREMENT = 37 CHARACTER SET = utf8mb3 COLLATE = utf8mb3_general_ci COMMENT = '角色表' ROW_FORMAT = DYNAMIC;
-
--- ----------------------------
--- Table structure for t_role_employee
--- ----------------------------
-DROP TABLE IF EXISTS `t_role_employee`;
-CREATE TABLE `t_role_employee`  (
-  `id` bigint NOT NULL AUTO_INCREMENT,
-  `role_id` bigint NOT NULL COMMENT '角色id',
-  `employee_id` bigint NOT NULL COMMENT '员工id',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '更新时间',
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  PRIMARY KEY (`id`) USING BTREE,
-  UNIQUE INDEX `uk_role_employee`(`role_id`, `employee_id`) USING BTREE
-) ENGINE = InnoDB AUTO_INCREMENT = 341 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '角色员工功能表' ROW_FORMAT = DYNAMIC;
-
--- ----------------------------
--- Table structure for t_role_menu
--- ----------------------------
-DROP TABLE IF EXISTS `t_role_menu`;
-CREATE TABLE `t_role_menu`  (
-  `role_menu_id` bigint NOT NULL AUTO_INCREMENT COMMENT '主键id',
-  `role_id` bigint NOT NULL COMMENT '角色id',
-  `menu_id` bigint NOT NULL COMMENT '菜单id',
-  `update_time` bigint NOT NULL DEFAULT 0 COMMENT '更新时间',
-  `create_time` bigint NOT NULL DEFAULT 0 COMMENT '创建时间',
-  PRIMARY KEY (`role_menu_id`) USING BTREE,
-  INDEX `idx_role_id`(`role_id`) USING BTREE,
-  INDEX `idx_menu_id`(`menu_id`) USING BTREE
-) ENGINE = InnoDB AUTO_INCREMENT = 331 CHARACTER SET = utf8mb4 COLLATE = utf8mb4_unicode_ci COMMENT = '角色-菜单\n' ROW_FORMAT = DYNAMIC;
-
-SET FOREIGN_KEY_CHECKS = 1;
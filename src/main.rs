@@ -1,32 +1,45 @@
 use app::api;
 use app::cmd;
 use clap::Parser;
-use pkg::{config, db, logger};
+use pkg::state::{self, AppState};
+use pkg::{config, logger};
 use tracing_appender::non_blocking::WorkerGuard;
 
 #[tokio::main]
 async fn main() {
     let cli = cmd::Cli::parse();
+    // 导出接口文档无需加载配置及连接数据库
+    if let Some(cmd::Command::Openapi { output }) = &cli.command {
+        cmd::openapi::exec(output);
+        return;
+    }
     // _guard 必须在 main 函数中才能使日志生效
-    let _guard = init(&cli.config).await;
+    let (state, _guard) = init(&cli.config).await;
     // 处理subcommand
     if let Some(v) = cli.command {
         match v {
             cmd::Command::Hello { name } => cmd::hello::exec(name),
-            cmd::Command::Serve => api::serve().await,
+            cmd::Command::Serve => {
+                // 按配置在启动前自动执行迁移
+                cmd::migrate::auto(&state).await;
+                api::serve(state).await
+            }
+            cmd::Command::Init { login_name, realname } => cmd::init::exec(&state, login_name, realname).await,
+            cmd::Command::Migrate { action } => cmd::migrate::exec(&state, action).await,
+            cmd::Command::Openapi { .. } => unreachable!(),
         }
     }
 }
 
-async fn init(cfg_file: &str) -> WorkerGuard {
-    // 初始化配置
-    config::init(cfg_file);
+async fn init(cfg_file: &str) -> (AppState, WorkerGuard) {
+    // 加载配置
+    let cfg = config::load(cfg_file);
     // 初始化日志
-    let _guard = logger::init(Some(config::global()));
-    // 初始化数据库
-    db::init(config::global()).await;
-    // 初始化Redis
-    // cache::init_redis(config::global());
+    let _guard = logger::init(Some(&cfg));
+    // 应用状态：数据库、缓存（内存或Redis）、密码哈希算法及密码策略
+    let state = AppState::from_config(cfg).await;
+    // 兼容仍通过全局方式访问的代码
+    state::set(state.clone());
 
-    _guard
+    (state, _guard)
 }